@@ -30,6 +30,16 @@ export async function updateRuntimeData(body: Partial<RuntimeAppData>) {
   }
 }
 
+export async function notifyOutcomeComplete(outcomeId: string) {
+  try {
+    await fetch(new URL(`/outcomes/${outcomeId}/complete`, BACKEND_HTTP), {
+      method: "POST",
+    });
+  } catch (e) {
+    console.error("failed to report outcome completion", e);
+  }
+}
+
 export async function getVTFTKLogo(): Promise<string> {
   const response = await fetch(new URL("/overlay/icon", BACKEND_HTTP));
   const arrayBuffer = await response.arrayBuffer();
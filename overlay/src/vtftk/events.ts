@@ -1,4 +1,4 @@
-import { updateRuntimeData } from "./api";
+import { notifyOutcomeComplete, updateRuntimeData } from "./api";
 import { BACKEND_HTTP } from "../constants";
 import { beginCalibrationStep } from "./calibration";
 import { CalibrationStep } from "./calibration-types";
@@ -55,9 +55,21 @@ export function createEventSource(data: EventSourceData) {
 // eslint-disable-next-line @typescript-eslint/no-explicit-any
 async function onMessage(data: EventSourceData, event: any) {
   switch (event.type) {
+    case "Outcome": {
+      try {
+        await onMessage(data, event.message);
+      } catch (e) {
+        console.error("failed to produce outcome", e);
+      }
+
+      // Frees the outcome slot in the backend outcome queue
+      await notifyOutcomeComplete(event.outcome_id);
+      break;
+    }
+
     case "ThrowItem": {
       if (data.vtSocket && data.modelParameters) {
-        onThrowItemEvent(
+        await onThrowItemEvent(
           data.appData,
           data.vtSocket,
           data.modelCalibration,
@@ -72,7 +84,7 @@ async function onMessage(data: EventSourceData, event: any) {
 
     case "TriggerHotkey": {
       if (data.vtSocket) {
-        onTriggerHotkeyEvent(data.vtSocket, event.hotkey_id);
+        await onTriggerHotkeyEvent(data.vtSocket, event.hotkey_id);
       }
 
       break;
@@ -80,7 +92,7 @@ async function onMessage(data: EventSourceData, event: any) {
 
     case "TriggerHotkeyByName": {
       if (data.vtSocket) {
-        onTriggerHotkeyByNameEvent(
+        await onTriggerHotkeyByNameEvent(
           data.vtSocket,
           event.hotkey_name,
           event.ignore_case,
@@ -92,7 +104,7 @@ async function onMessage(data: EventSourceData, event: any) {
 
     case "PlaySound": {
       if (data.vtSocket) {
-        onPlaySoundEvent(data.appData, event.config);
+        await onPlaySoundEvent(data.appData, event.config);
       }
 
       break;
@@ -100,7 +112,7 @@ async function onMessage(data: EventSourceData, event: any) {
 
     case "PlaySoundSeq": {
       if (data.vtSocket) {
-        onPlaySoundSeqEvent(data.appData, event.configs);
+        await onPlaySoundSeqEvent(data.appData, event.configs);
       }
 
       break;
@@ -162,7 +174,16 @@ async function onUpdateHotkeysEvent(vtSocket: VTubeStudioWebSocket) {
 async function onPlaySoundEvent(appData: AppData, config: PartialSoundModel) {
   const audio = await loadAudio(config.src);
   audio.volume = config.volume * appData.sounds_config.global_volume;
+
+  const completePromise = new Promise<void>((resolve, reject) => {
+    audio.onended = () => resolve();
+    audio.onerror = () => reject();
+  });
+
   audio.play();
+
+  // Wait for the sound to complete fully
+  await completePromise;
 }
 
 async function onPlaySoundSeqEvent(
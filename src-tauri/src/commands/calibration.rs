@@ -13,7 +13,8 @@ pub fn set_calibration_step(
     step: CalibrationStep,
     event_sender: State<'_, EventMessageChannel>,
 ) -> CmdResult<()> {
-    event_sender.send(EventMessage::SetCalibrationStep { step })?;
+    // Overlay may not be connected
+    _ = event_sender.send(EventMessage::SetCalibrationStep { step });
     Ok(())
 }
 
@@ -24,7 +25,8 @@ pub fn calibration_move_model(
     y: f32,
     event_sender: State<'_, EventMessageChannel>,
 ) -> CmdResult<()> {
-    event_sender.send(EventMessage::MoveModel { x, y })?;
+    // Overlay may not be connected
+    _ = event_sender.send(EventMessage::MoveModel { x, y });
     Ok(())
 }
 
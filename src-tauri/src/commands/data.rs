@@ -16,7 +16,8 @@ use tauri::State;
 /// of hotkeys from VTube Studio
 #[tauri::command]
 pub fn update_hotkeys(event_sender: tauri::State<'_, EventMessageChannel>) -> CmdResult<()> {
-    event_sender.send(EventMessage::UpdateHotkeys)?;
+    // Overlay may not be connected
+    _ = event_sender.send(EventMessage::UpdateHotkeys);
     Ok(())
 }
 
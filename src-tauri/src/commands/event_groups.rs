@@ -0,0 +1,124 @@
+//! # Event Groups
+//!
+//! Commands for interacting with event groups from the frontend
+
+use super::CmdResult;
+use crate::{
+    database::entity::event_groups::{CreateEventGroup, EventGroupModel, UpdateEventGroup},
+    events::groups::{self, on_event_groups_changed},
+};
+use anyhow::Context;
+use sea_orm::{DatabaseConnection, ModelTrait};
+use tauri::{AppHandle, State};
+use uuid::Uuid;
+
+/// Get all event groups
+#[tauri::command]
+pub async fn get_event_groups(
+    db: State<'_, DatabaseConnection>,
+) -> CmdResult<Vec<EventGroupModel>> {
+    let db = db.inner();
+    let groups = EventGroupModel::all(db).await?;
+    Ok(groups)
+}
+
+/// Create a new event group
+#[tauri::command]
+pub async fn create_event_group(
+    create: CreateEventGroup,
+    app: AppHandle,
+    db: State<'_, DatabaseConnection>,
+) -> CmdResult<EventGroupModel> {
+    let db = db.inner();
+    let group = EventGroupModel::create(db, create).await?;
+
+    on_event_groups_changed(&app, db).await;
+
+    Ok(group)
+}
+
+/// Update an existing event group
+#[tauri::command]
+pub async fn update_event_group(
+    group_id: Uuid,
+    update: UpdateEventGroup,
+    app: AppHandle,
+    db: State<'_, DatabaseConnection>,
+) -> CmdResult<EventGroupModel> {
+    let db = db.inner();
+    let group = EventGroupModel::get_by_id(db, group_id)
+        .await?
+        .context("event group not found")?;
+    let group = group.update(db, update).await?;
+
+    on_event_groups_changed(&app, db).await;
+
+    Ok(group)
+}
+
+/// Enable or disable all the events in an event group
+#[tauri::command]
+pub async fn set_event_group_enabled(
+    group_id: Uuid,
+    enabled: bool,
+    app: AppHandle,
+    db: State<'_, DatabaseConnection>,
+) -> CmdResult<EventGroupModel> {
+    let db = db.inner();
+    let group = EventGroupModel::get_by_id(db, group_id)
+        .await?
+        .context("event group not found")?;
+    let group = groups::set_event_group_enabled(&app, db, group, enabled).await?;
+    Ok(group)
+}
+
+/// Delete an event group, the events within the group are not deleted
+#[tauri::command]
+pub async fn delete_event_group(
+    group_id: Uuid,
+    app: AppHandle,
+    db: State<'_, DatabaseConnection>,
+) -> CmdResult<()> {
+    let db = db.inner();
+    let group = EventGroupModel::get_by_id(db, group_id)
+        .await?
+        .context("event group not found")?;
+    group.delete(db).await?;
+
+    on_event_groups_changed(&app, db).await;
+
+    Ok(())
+}
+
+/// Get the IDs of the events within an event group
+#[tauri::command]
+pub async fn get_event_group_events(
+    group_id: Uuid,
+    db: State<'_, DatabaseConnection>,
+) -> CmdResult<Vec<Uuid>> {
+    let db = db.inner();
+    let group = EventGroupModel::get_by_id(db, group_id)
+        .await?
+        .context("event group not found")?;
+    let event_ids = group.get_event_ids(db).await?;
+    Ok(event_ids)
+}
+
+/// Set the events within an event group
+#[tauri::command]
+pub async fn set_event_group_events(
+    group_id: Uuid,
+    event_ids: Vec<Uuid>,
+    app: AppHandle,
+    db: State<'_, DatabaseConnection>,
+) -> CmdResult<()> {
+    let db = db.inner();
+    let group = EventGroupModel::get_by_id(db, group_id)
+        .await?
+        .context("event group not found")?;
+    group.set_event_ids(db, &event_ids).await?;
+
+    on_event_groups_changed(&app, db).await;
+
+    Ok(())
+}
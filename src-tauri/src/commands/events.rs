@@ -10,12 +10,16 @@ use crate::{
         shared::{ExecutionsQuery, UpdateOrdering},
     },
     events::{
-        matching::EventData, outcome::produce_outcome_message, scheduler::SchedulerHandle,
+        matching::EventData,
+        processing::{execute_event_outcomes, validate_event_conditions},
+        queue::{OutcomeQueue, OutcomeQueueEntry},
+        scheduler::{validate_timer_trigger, SchedulerHandle},
+        template::{sample_event_data, validate_event_templates, Template, TemplateContext},
         EventMessageChannel,
     },
     twitch::manager::Twitch,
 };
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use sea_orm::{DatabaseConnection, ModelTrait};
 use tauri::State;
 use uuid::Uuid;
@@ -44,11 +48,20 @@ pub async fn create_event(
     scheduler: State<'_, SchedulerHandle>,
 ) -> CmdResult<EventModel> {
     let db = db.inner();
+
+    validate_event_templates(
+        &create.trigger,
+        Some(&create.outcomes),
+        Some(&create.feedback),
+    )?;
+    validate_event_conditions(&create.conditions.0)?;
+    validate_timer_trigger(&create.trigger)?;
+
     let event = EventModel::create(db, create).await?;
 
     // Update the event scheduler
     if let EventTrigger::Timer { .. } = event.trigger {
-        update_scheduler_events(db, scheduler.inner()).await;
+        scheduler.reload_events(db).await;
     }
 
     Ok(event)
@@ -65,16 +78,46 @@ pub async fn update_event(
     let event = EventModel::get_by_id(db, event_id)
         .await?
         .context("event not found")?;
+
+    // Template variables depend on the trigger so templates are validated
+    // again when the trigger changes
+    if update.trigger.is_some() || update.outcomes.is_some() || update.feedback.is_some() {
+        validate_event_templates(
+            update.trigger.as_ref().unwrap_or(&event.trigger),
+            Some(update.outcomes.as_ref().unwrap_or(&event.outcomes)),
+            Some(update.feedback.as_ref().unwrap_or(&event.feedback)),
+        )?;
+    }
+
+    if let Some(conditions) = &update.conditions {
+        validate_event_conditions(&conditions.0)?;
+    }
+
+    if let Some(trigger) = &update.trigger {
+        validate_timer_trigger(trigger)?;
+    }
+
     let event = event.update(db, update).await?;
 
     // Update the event scheduler
     if let EventTrigger::Timer { .. } = event.trigger {
-        update_scheduler_events(db, scheduler.inner()).await;
+        scheduler.reload_events(db).await;
     }
 
     Ok(event)
 }
 
+/// Renders a chat message `template` against sample data for
+/// the `trigger` type
+#[tauri::command]
+pub async fn preview_template(template: String, trigger: EventTriggerType) -> CmdResult<String> {
+    let template = Template::parse(&template)?;
+    let event_data = sample_event_data(&trigger);
+    let ctx = TemplateContext::from_event_data(&event_data);
+
+    Ok(template.render(&ctx, &mut rand::thread_rng()))
+}
+
 #[tauri::command]
 pub async fn delete_event(
     event_id: Uuid,
@@ -92,24 +135,19 @@ pub async fn delete_event(
 
     // Update the event scheduler to handle deleted timer
     if is_timer_event {
-        update_scheduler_events(db, scheduler.inner()).await;
+        scheduler.reload_events(db).await;
     }
 
     Ok(())
 }
 
-async fn update_scheduler_events(db: &DatabaseConnection, scheduler: &SchedulerHandle) {
-    if let Ok(events) = EventModel::get_by_trigger_type(db, EventTriggerType::Timer).await {
-        _ = scheduler.update_events(events).await;
-    }
-}
-
 #[tauri::command]
 pub async fn test_event_by_id(
     event_id: Uuid,
     event_data: EventData,
     db: State<'_, DatabaseConnection>,
     event_sender: State<'_, EventMessageChannel>,
+    outcome_queue: State<'_, OutcomeQueue>,
     twitch: State<'_, Twitch>,
 ) -> CmdResult<()> {
     let db = db.inner();
@@ -117,13 +155,32 @@ pub async fn test_event_by_id(
         .await?
         .context("unknown event")?;
 
-    if let Some(msg) = produce_outcome_message(db, &twitch, event, event_data).await? {
-        _ = event_sender.send(msg);
+    let result = execute_event_outcomes(
+        db,
+        &twitch,
+        &event_sender,
+        &outcome_queue,
+        &event,
+        &event_data,
+        &[],
+    )
+    .await;
+
+    // Report the first failed step
+    if let Some(error) = result.errors.first() {
+        return Err(anyhow!("outcome step {} failed: {}", error.step + 1, error.error).into());
     }
 
     Ok(())
 }
 
+#[tauri::command]
+pub async fn get_outcome_queue(
+    outcome_queue: State<'_, OutcomeQueue>,
+) -> CmdResult<Vec<OutcomeQueueEntry>> {
+    Ok(outcome_queue.entries())
+}
+
 #[tauri::command]
 pub async fn update_event_orderings(
     update: Vec<UpdateOrdering>,
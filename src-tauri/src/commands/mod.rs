@@ -2,6 +2,7 @@ use serde::Serialize;
 
 pub mod calibration;
 pub mod data;
+pub mod event_groups;
 pub mod events;
 pub mod items;
 pub mod sounds;
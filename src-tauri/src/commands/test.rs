@@ -25,12 +25,13 @@ pub async fn test_throw(
     let db = db.inner();
     let items = resolve_items(db, &item_ids).await?;
 
-    event_sender.send(EventMessage::ThrowItem(ThrowItemMessage {
+    // Overlay may not be connected
+    _ = event_sender.send(EventMessage::ThrowItem(ThrowItemMessage {
         items,
         config: ThrowItemConfig::All {
             amount: amount.unwrap_or(1),
         },
-    }))?;
+    }));
 
     Ok(())
 }
@@ -48,14 +49,15 @@ pub async fn test_throw_barrage(
     let db = db.inner();
     let items = resolve_items(db, &item_ids).await?;
 
-    event_sender.send(EventMessage::ThrowItem(ThrowItemMessage {
+    // Overlay may not be connected
+    _ = event_sender.send(EventMessage::ThrowItem(ThrowItemMessage {
         items,
         config: ThrowItemConfig::Barrage {
             amount_per_throw,
             amount,
             frequency,
         },
-    }))?;
+    }));
 
     Ok(())
 }
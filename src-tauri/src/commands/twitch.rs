@@ -1,12 +1,13 @@
 use crate::commands::CmdResult;
-use crate::database::entity::app_data::AppDataModel;
+use crate::database::entity::{app_data::AppDataModel, twitch_access::TwitchAccessModel};
 use crate::twitch::manager::Twitch;
+use crate::twitch::rewards::{CreateReward, UpdateReward};
 use anyhow::Context;
 use reqwest::Url;
 use sea_orm::DatabaseConnection;
 use std::sync::Arc;
 use tauri::State;
-use twitch_api::helix::points::CustomReward;
+use twitch_api::{helix::points::CustomReward, types::RewardIdRef};
 
 /// Requests the list of available redeems from the broadcasters channel.
 ///
@@ -27,6 +28,74 @@ pub async fn refresh_redeems_list(twitch: State<'_, Twitch>) -> CmdResult<()> {
     Ok(())
 }
 
+/// Creates a new channel point reward owned by the app
+#[tauri::command]
+pub async fn create_reward(
+    twitch: State<'_, Twitch>,
+    create: CreateReward,
+) -> CmdResult<CustomReward> {
+    Ok(twitch.create_reward(&create).await?)
+}
+
+/// Updates a channel point reward, only rewards created
+/// by the app can be updated
+#[tauri::command]
+pub async fn update_reward(
+    twitch: State<'_, Twitch>,
+    reward_id: String,
+    update: UpdateReward,
+) -> CmdResult<CustomReward> {
+    Ok(twitch
+        .update_reward(RewardIdRef::from_str(&reward_id), &update)
+        .await?)
+}
+
+/// Pauses or unpauses a channel point reward, viewers
+/// cannot redeem paused rewards
+#[tauri::command]
+pub async fn set_reward_paused(
+    twitch: State<'_, Twitch>,
+    reward_id: String,
+    paused: bool,
+) -> CmdResult<CustomReward> {
+    let update = UpdateReward {
+        paused: Some(paused),
+        ..Default::default()
+    };
+
+    Ok(twitch
+        .update_reward(RewardIdRef::from_str(&reward_id), &update)
+        .await?)
+}
+
+/// Enables or disables a channel point reward, disabled
+/// rewards are hidden from viewers
+#[tauri::command]
+pub async fn set_reward_enabled(
+    twitch: State<'_, Twitch>,
+    reward_id: String,
+    enabled: bool,
+) -> CmdResult<CustomReward> {
+    let update = UpdateReward {
+        enabled: Some(enabled),
+        ..Default::default()
+    };
+
+    Ok(twitch
+        .update_reward(RewardIdRef::from_str(&reward_id), &update)
+        .await?)
+}
+
+/// Deletes a channel point reward, only rewards created
+/// by the app can be deleted
+#[tauri::command]
+pub async fn delete_reward(twitch: State<'_, Twitch>, reward_id: String) -> CmdResult<()> {
+    twitch
+        .delete_reward(RewardIdRef::from_str(&reward_id))
+        .await?;
+    Ok(())
+}
+
 /// Obtain a URL for use logging into twitch using OAuth2
 #[tauri::command]
 pub async fn get_twitch_oauth_uri(
@@ -42,6 +111,39 @@ pub async fn get_twitch_oauth_uri(
     Ok(url)
 }
 
+/// Obtain a URL for use logging into the separate bot account
+/// used to send chat messages
+#[tauri::command]
+pub async fn get_twitch_bot_oauth_uri(
+    twitch: State<'_, Twitch>,
+    db: tauri::State<'_, DatabaseConnection>,
+) -> CmdResult<String> {
+    let http_port = AppDataModel::get_http_port(db.inner()).await?;
+
+    let redirect_url = format!("http://localhost:{http_port}/oauth",);
+    let redirect_url = Url::parse(&redirect_url).context("invalid redirect_uri")?;
+    let url = twitch.create_bot_oauth_uri(redirect_url)?;
+
+    Ok(url)
+}
+
+#[tauri::command]
+pub async fn is_bot_authenticated(twitch: tauri::State<'_, Twitch>) -> CmdResult<bool> {
+    Ok(twitch.is_bot_authenticated().await)
+}
+
+/// Logs out of the bot account, chat messages set to send from
+/// the bot account will fail until logging in again
+#[tauri::command]
+pub async fn logout_bot(
+    twitch: tauri::State<'_, Twitch>,
+    db: tauri::State<'_, DatabaseConnection>,
+) -> CmdResult<()> {
+    twitch.reset_bot().await;
+    TwitchAccessModel::delete_bot(db.inner()).await?;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn is_authenticated(twitch: tauri::State<'_, Twitch>) -> CmdResult<bool> {
     Ok(twitch.is_authenticated().await)
@@ -48,6 +48,7 @@ impl PartialEq for AppData {
 #[serde(default)]
 pub struct AppConfig {
     pub main_config: MainConfig,
+    pub outcome_queue_config: OutcomeQueueConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -108,6 +109,55 @@ impl Default for MainConfig {
     }
 }
 
+/// Configuration for the outcome queue, each kind of outcome
+/// has its own separate queue
+#[derive(Debug, Clone, Serialize, Deserialize, Default, FromJsonQueryResult)]
+#[serde(default)]
+pub struct OutcomeQueueConfig {
+    /// Queue for throwing items, bits and emotes
+    pub throw: OutcomeQueueKindConfig,
+    /// Queue for playing sounds
+    pub sound: OutcomeQueueKindConfig,
+    /// Queue for triggering VTube Studio hotkeys
+    pub hotkey: OutcomeQueueKindConfig,
+    /// Queue for sending chat messages
+    pub chat: OutcomeQueueKindConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutcomeQueueKindConfig {
+    /// Maximum number of outcomes that can run at once (0 for no limit)
+    pub max_concurrent: u32,
+    /// Minimum time in milliseconds between the start of each outcome
+    pub spacing: u32,
+    /// Maximum number of outcomes waiting in the queue (0 for no limit)
+    pub max_length: u32,
+    /// How to handle new outcomes when the queue is full
+    pub drop_policy: OutcomeQueueDropPolicy,
+}
+
+impl Default for OutcomeQueueKindConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 1,
+            spacing: 0,
+            max_length: 100,
+            drop_policy: OutcomeQueueDropPolicy::DropNewest,
+        }
+    }
+}
+
+/// Policy for handling new outcomes when the queue is full
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutcomeQueueDropPolicy {
+    /// Drop the new outcome
+    #[default]
+    DropNewest,
+    /// Drop the oldest waiting outcome to make room for the new outcome
+    DropOldest,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct VTubeStudioConfig {
@@ -360,4 +410,27 @@ impl Model {
             .map(|value| value.main_config)
             .unwrap_or_default())
     }
+    pub async fn get_outcome_queue_config<C>(db: &C) -> anyhow::Result<OutcomeQueueConfig>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        #[derive(Default, FromQueryResult)]
+        struct PartialModel {
+            outcome_queue_config: Option<OutcomeQueueConfig>,
+        }
+
+        // Loaded for every outcome so only the queue config is selected, the config
+        // is not present in app data saved before the queue was added
+        Ok(Entity::find_by_id(Self::SINGLETON_ID)
+            .select_only()
+            .expr_as(
+                Expr::cust("json_extract(data, '$.outcome_queue_config')"),
+                "outcome_queue_config",
+            )
+            .into_model::<PartialModel>()
+            .one(db)
+            .await?
+            .and_then(|value| value.outcome_queue_config)
+            .unwrap_or_default())
+    }
 }
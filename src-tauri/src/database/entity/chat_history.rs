@@ -22,6 +22,8 @@ pub struct Model {
     pub cheer: Option<u32>,
     /// Creation time of the chat message
     pub created_at: DateTimeUtc,
+    /// Stream session the message was sent during
+    pub stream_session_id: Option<Uuid>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -41,6 +43,8 @@ pub struct CreateChatHistory {
     pub cheer: Option<u32>,
     /// Creation time of the chat message
     pub created_at: DateTimeUtc,
+    /// Stream session the message was sent during
+    pub stream_session_id: Option<Uuid>,
 }
 
 impl Model {
@@ -55,6 +59,7 @@ impl Model {
             message: Set(create.message),
             cheer: Set(create.cheer),
             created_at: Set(create.created_at),
+            stream_session_id: Set(create.stream_session_id),
         };
 
         Entity::insert(active_model)
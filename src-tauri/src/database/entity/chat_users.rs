@@ -0,0 +1,128 @@
+use super::shared::DbResult;
+use sea_orm::{entity::prelude::*, sea_query::OnConflict, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+// Type alias helpers for the database entity types
+pub type ChatUserModel = Model;
+
+/// Users that have been seen chatting, unlike the chat history this is
+/// not cleaned up so it can be used to know if a user has chatted before
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "chat_users")]
+pub struct Model {
+    /// ID of the twitch user
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: String,
+    /// Time the user first sent a chat message
+    pub first_seen_at: DateTimeUtc,
+    /// Time the user last sent a chat message
+    pub last_seen_at: DateTimeUtc,
+    /// Stream session the user last sent a chat message during
+    pub last_stream_session_id: Option<Uuid>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Creates the user if they have not been seen before, returns
+    /// whether the user was created
+    pub async fn create_if_new<C>(
+        db: &C,
+        user_id: String,
+        stream_session_id: Option<Uuid>,
+        seen_at: DateTimeUtc,
+    ) -> DbResult<bool>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        let active_model = ActiveModel {
+            user_id: Set(user_id),
+            first_seen_at: Set(seen_at),
+            last_seen_at: Set(seen_at),
+            last_stream_session_id: Set(stream_session_id),
+        };
+
+        let inserted = Entity::insert(active_model)
+            .on_conflict(OnConflict::column(Column::UserId).do_nothing().to_owned())
+            .exec_without_returning(db)
+            .await?;
+
+        Ok(inserted > 0)
+    }
+
+    /// Marks the user as seen during the stream session, returns whether
+    /// this is the first time the user was seen during the session
+    ///
+    /// The check and update happen in a single statement so concurrent
+    /// messages cannot both be the first
+    pub async fn mark_seen_in_session<C>(
+        db: &C,
+        user_id: &str,
+        stream_session_id: Uuid,
+        seen_at: DateTimeUtc,
+    ) -> DbResult<bool>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        let result = Entity::update_many()
+            .col_expr(Column::LastSeenAt, Expr::value(seen_at))
+            .col_expr(Column::LastStreamSessionId, Expr::value(stream_session_id))
+            .filter(Column::UserId.eq(user_id))
+            .filter(Expr::col(Column::LastStreamSessionId).is_not(stream_session_id))
+            .exec(db)
+            .await?;
+
+        if result.rows_affected > 0 {
+            return Ok(true);
+        }
+
+        Self::mark_seen(db, user_id, seen_at).await?;
+        Ok(false)
+    }
+
+    /// Marks the user as seen, returns whether the user was last seen
+    /// before `since`
+    ///
+    /// The check and update happen in a single statement so concurrent
+    /// messages cannot both be the first
+    pub async fn mark_seen_since<C>(
+        db: &C,
+        user_id: &str,
+        since: DateTimeUtc,
+        seen_at: DateTimeUtc,
+    ) -> DbResult<bool>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        let result = Entity::update_many()
+            .col_expr(Column::LastSeenAt, Expr::value(seen_at))
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::LastSeenAt.lt(since))
+            .exec(db)
+            .await?;
+
+        if result.rows_affected > 0 {
+            return Ok(true);
+        }
+
+        Self::mark_seen(db, user_id, seen_at).await?;
+        Ok(false)
+    }
+
+    /// Updates the time the user was last seen
+    async fn mark_seen<C>(db: &C, user_id: &str, seen_at: DateTimeUtc) -> DbResult<()>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        Entity::update_many()
+            .col_expr(Column::LastSeenAt, Expr::value(seen_at))
+            .filter(Column::UserId.eq(user_id))
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+}
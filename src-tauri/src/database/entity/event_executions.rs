@@ -1,7 +1,7 @@
 use anyhow::Context;
 use sea_orm::{
     entity::prelude::*, sea_query::Func, ActiveValue::Set, FromJsonQueryResult, FromQueryResult,
-    QuerySelect,
+    IntoActiveModel, QuerySelect,
 };
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
@@ -23,6 +23,11 @@ pub struct Model {
     pub event_id: Uuid,
     pub metadata: EventExecutionMetadata,
     pub created_at: DateTimeUtc,
+    /// Stream session the event was executed during
+    pub stream_session_id: Option<Uuid>,
+    /// ID of the user who triggered the event, duplicate of the
+    /// user from "metadata" to allow indexed querying
+    pub user_id: Option<String>,
 }
 
 #[serde_as]
@@ -61,6 +66,7 @@ pub struct CreateEventExecution {
     pub event_id: Uuid,
     pub metadata: EventExecutionMetadata,
     pub created_at: DateTimeUtc,
+    pub stream_session_id: Option<Uuid>,
 }
 
 impl Model {
@@ -70,11 +76,18 @@ impl Model {
         C: ConnectionTrait + Send + 'static,
     {
         let id = Uuid::new_v4();
+        let user_id = create
+            .metadata
+            .user
+            .as_ref()
+            .map(|user| user.id.to_string());
         let active_model = ActiveModel {
             id: Set(id),
             event_id: Set(create.event_id),
             metadata: Set(create.metadata),
             created_at: Set(create.created_at),
+            stream_session_id: Set(create.stream_session_id),
+            user_id: Set(user_id),
         };
 
         Entity::insert(active_model)
@@ -88,6 +101,18 @@ impl Model {
         Ok(model)
     }
 
+    /// Replaces the metadata of the execution
+    pub async fn set_metadata<C>(self, db: &C, metadata: EventExecutionMetadata) -> DbResult<Self>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        let mut this = self.into_active_model();
+        this.metadata = Set(metadata);
+
+        let this = this.update(db).await?;
+        Ok(this)
+    }
+
     pub async fn get_by_id<C>(db: &C, id: Uuid) -> DbResult<Option<Self>>
     where
         C: ConnectionTrait + Send + 'static,
@@ -0,0 +1,50 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Type alias helpers for the database entity types
+pub type EventGroupEventsEntity = Entity;
+pub type EventGroupEventsActiveModel = ActiveModel;
+pub type EventGroupEventsColumn = Column;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "event_group_events")]
+pub struct Model {
+    /// ID of the event group
+    #[sea_orm(primary_key)]
+    pub group_id: Uuid,
+    /// ID of the event
+    #[sea_orm(primary_key)]
+    pub event_id: Uuid,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Event group half of the relationship
+    #[sea_orm(
+        belongs_to = "super::event_groups::Entity",
+        from = "Column::GroupId",
+        to = "super::event_groups::Column::Id"
+    )]
+    Group,
+    /// Event half of the relationship
+    #[sea_orm(
+        belongs_to = "super::events::Entity",
+        from = "Column::EventId",
+        to = "super::events::Column::Id"
+    )]
+    Event,
+}
+
+impl Related<super::event_groups::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Group.def()
+    }
+}
+
+impl Related<super::events::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Event.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
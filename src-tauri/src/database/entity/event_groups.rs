@@ -0,0 +1,170 @@
+use super::{
+    event_group_events::{
+        EventGroupEventsActiveModel, EventGroupEventsColumn, EventGroupEventsEntity,
+    },
+    shared::DbResult,
+};
+use anyhow::Context;
+use chrono::Utc;
+use sea_orm::{
+    entity::prelude::*, sea_query::Func, ActiveValue::Set, IntoActiveModel, QueryOrder, QuerySelect,
+};
+use serde::{Deserialize, Serialize};
+
+// Type alias helpers for the database entity types
+pub type EventGroupModel = Model;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "event_groups")]
+pub struct Model {
+    /// Unique ID of the event group
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    /// Name of the event group
+    pub name: String,
+    /// Whether the events in the group are enabled
+    pub enabled: bool,
+    /// Date time of creation
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Event group can have many events
+    #[sea_orm(has_many = "super::event_group_events::Entity")]
+    Events,
+}
+
+impl Related<super::event_group_events::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Events.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateEventGroup {
+    pub name: String,
+    pub enabled: bool,
+}
+
+#[derive(Default, Deserialize)]
+pub struct UpdateEventGroup {
+    pub name: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+impl Model {
+    /// Create a new event group
+    pub async fn create<C>(db: &C, create: CreateEventGroup) -> anyhow::Result<Model>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        let id = Uuid::new_v4();
+        let active_model = ActiveModel {
+            id: Set(id),
+            name: Set(create.name),
+            enabled: Set(create.enabled),
+            created_at: Set(Utc::now()),
+        };
+
+        Entity::insert(active_model)
+            .exec_without_returning(db)
+            .await?;
+
+        let model = Self::get_by_id(db, id)
+            .await?
+            .context("model was not inserted")?;
+
+        Ok(model)
+    }
+
+    /// Find a specific event group by ID
+    pub async fn get_by_id<C>(db: &C, id: Uuid) -> DbResult<Option<Self>>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        Entity::find_by_id(id).one(db).await
+    }
+
+    /// Find a specific event group by name (Case insensitive)
+    pub async fn get_by_name<C>(db: &C, name: &str) -> DbResult<Option<Self>>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        Entity::find()
+            .filter(Expr::expr(Func::lower(Expr::col(Column::Name))).eq(name.to_lowercase()))
+            .one(db)
+            .await
+    }
+
+    /// Find all event groups
+    pub async fn all<C>(db: &C) -> DbResult<Vec<Self>>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        Entity::find().order_by_asc(Column::Name).all(db).await
+    }
+
+    /// Update the current event group
+    pub async fn update<C>(self, db: &C, data: UpdateEventGroup) -> DbResult<Self>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        let mut this = self.into_active_model();
+
+        this.name = data.name.map(Set).unwrap_or(this.name);
+        this.enabled = data.enabled.map(Set).unwrap_or(this.enabled);
+
+        let this = this.update(db).await?;
+        Ok(this)
+    }
+
+    /// Get the IDs of the events within the group
+    pub async fn get_event_ids<C>(&self, db: &C) -> DbResult<Vec<Uuid>>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        EventGroupEventsEntity::find()
+            .select_only()
+            .column(EventGroupEventsColumn::EventId)
+            .filter(EventGroupEventsColumn::GroupId.eq(self.id))
+            .into_tuple()
+            .all(db)
+            .await
+    }
+
+    /// Replace the events within the group with the provided `event_ids`
+    pub async fn set_event_ids<C>(&self, db: &C, event_ids: &[Uuid]) -> DbResult<()>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        // Remove events that are no longer in the group
+        EventGroupEventsEntity::delete_many()
+            .filter(
+                EventGroupEventsColumn::GroupId
+                    .eq(self.id)
+                    .and(EventGroupEventsColumn::EventId.is_not_in(event_ids.iter().copied())),
+            )
+            .exec(db)
+            .await?;
+
+        if event_ids.is_empty() {
+            return Ok(());
+        }
+
+        // Add the new events to the group
+        EventGroupEventsEntity::insert_many(event_ids.iter().map(|event_id| {
+            EventGroupEventsActiveModel {
+                group_id: Set(self.id),
+                event_id: Set(*event_id),
+            }
+        }))
+        .on_conflict_do_nothing()
+        .exec_without_returning(db)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -1,15 +1,23 @@
 use super::{
     event_executions::{EventExecutionColumn, EventExecutionModel},
-    shared::{DbResult, ExecutionsQuery, MinMax, MinimumRequireRole, UpdateOrdering},
+    event_group_events, event_groups,
+    shared::{
+        DbResult, ExecutionsQuery, MinMax, MinimumRequireRole, RoleRequirements, UpdateOrdering,
+    },
 };
+use crate::twitch::models::ChatIdentity;
 use anyhow::Context;
-use chrono::Utc;
+use chrono::{NaiveTime, Utc};
 use futures::{future::BoxFuture, stream::FuturesUnordered, TryStreamExt};
 use sea_orm::{
-    entity::prelude::*, sea_query::CaseStatement, ActiveValue::Set, FromJsonQueryResult,
-    IntoActiveModel, QueryOrder, QuerySelect, UpdateResult,
+    entity::prelude::*,
+    sea_query::{CaseStatement, Query},
+    ActiveValue::Set,
+    Condition, FromJsonQueryResult, IntoActiveModel, QueryOrder, QuerySelect, UpdateResult,
 };
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use twitch_api::{helix::chat::AnnouncementColor, types::SubscriptionTier};
 
 // Type alias helpers for the database entity types
 pub type EventModel = Model;
@@ -30,14 +38,22 @@ pub struct Model {
     pub trigger_type: EventTriggerType,
     /// Input that should trigger the event
     pub trigger: EventTrigger,
-    /// Outcome the event should trigger
-    pub outcome: EventOutcome,
+    /// Ordered steps of outcomes the event should trigger
+    pub outcomes: EventOutcomes,
     /// Cooldown between each trigger of the even
     pub cooldown: EventCooldown,
     /// Minimum required role to trigger the event
     pub require_role: MinimumRequireRole,
-    /// Delay before executing the outcome
+    /// Additional requirements for the required role
+    pub role_requirements: RoleRequirements,
+    /// Delay before executing the outcome steps
     pub outcome_delay: u32,
+    /// Conditions that must all be met for the event to execute
+    pub conditions: EventConditions,
+    /// Chat replies for when the event is skipped
+    pub feedback: EventFeedback,
+    /// Limits on the number of times the event can be executed
+    pub usage_limits: EventUsageLimits,
     /// Ordering
     pub order: u32,
 
@@ -72,12 +88,16 @@ pub enum EventTriggerType {
     Redeem,
     #[sea_orm(string_value = "Command")]
     Command,
+    #[sea_orm(string_value = "ChatPattern")]
+    ChatPattern,
     #[sea_orm(string_value = "Follow")]
     Follow,
     #[sea_orm(string_value = "Subscription")]
     Subscription,
     #[sea_orm(string_value = "GiftedSubscription")]
     GiftedSubscription,
+    #[sea_orm(string_value = "ReSubscription")]
+    ReSubscription,
     #[sea_orm(string_value = "Bits")]
     Bits,
     #[sea_orm(string_value = "Raid")]
@@ -88,6 +108,36 @@ pub enum EventTriggerType {
     AdBreakBegin,
     #[sea_orm(string_value = "ShoutoutReceive")]
     ShoutoutReceive,
+    #[sea_orm(string_value = "HypeTrainBegin")]
+    HypeTrainBegin,
+    #[sea_orm(string_value = "HypeTrainProgress")]
+    HypeTrainProgress,
+    #[sea_orm(string_value = "HypeTrainEnd")]
+    HypeTrainEnd,
+    #[sea_orm(string_value = "StreamOnline")]
+    StreamOnline,
+    #[sea_orm(string_value = "StreamOffline")]
+    StreamOffline,
+    #[sea_orm(string_value = "FirstMessage")]
+    FirstMessage,
+    #[sea_orm(string_value = "PollBegin")]
+    PollBegin,
+    #[sea_orm(string_value = "PollProgress")]
+    PollProgress,
+    #[sea_orm(string_value = "PollEnd")]
+    PollEnd,
+    #[sea_orm(string_value = "PredictionBegin")]
+    PredictionBegin,
+    #[sea_orm(string_value = "PredictionProgress")]
+    PredictionProgress,
+    #[sea_orm(string_value = "PredictionLock")]
+    PredictionLock,
+    #[sea_orm(string_value = "PredictionEnd")]
+    PredictionEnd,
+    #[sea_orm(string_value = "CharityDonation")]
+    CharityDonation,
+    #[sea_orm(string_value = "Webhook")]
+    Webhook,
 }
 
 impl EventTriggerType {
@@ -95,14 +145,31 @@ impl EventTriggerType {
         match trigger {
             EventTrigger::Redeem { .. } => EventTriggerType::Redeem,
             EventTrigger::Command { .. } => EventTriggerType::Command,
+            EventTrigger::ChatPattern { .. } => EventTriggerType::ChatPattern,
             EventTrigger::Follow => EventTriggerType::Follow,
-            EventTrigger::Subscription => EventTriggerType::Subscription,
-            EventTrigger::GiftedSubscription => EventTriggerType::GiftedSubscription,
+            EventTrigger::Subscription { .. } => EventTriggerType::Subscription,
+            EventTrigger::GiftedSubscription { .. } => EventTriggerType::GiftedSubscription,
+            EventTrigger::ReSubscription { .. } => EventTriggerType::ReSubscription,
             EventTrigger::Bits { .. } => EventTriggerType::Bits,
             EventTrigger::Raid { .. } => EventTriggerType::Raid,
             EventTrigger::Timer { .. } => EventTriggerType::Timer,
             EventTrigger::AdBreakBegin => EventTriggerType::AdBreakBegin,
             EventTrigger::ShoutoutReceive { .. } => EventTriggerType::ShoutoutReceive,
+            EventTrigger::HypeTrainBegin => EventTriggerType::HypeTrainBegin,
+            EventTrigger::HypeTrainProgress { .. } => EventTriggerType::HypeTrainProgress,
+            EventTrigger::HypeTrainEnd { .. } => EventTriggerType::HypeTrainEnd,
+            EventTrigger::StreamOnline => EventTriggerType::StreamOnline,
+            EventTrigger::StreamOffline => EventTriggerType::StreamOffline,
+            EventTrigger::FirstMessage { .. } => EventTriggerType::FirstMessage,
+            EventTrigger::PollBegin => EventTriggerType::PollBegin,
+            EventTrigger::PollProgress => EventTriggerType::PollProgress,
+            EventTrigger::PollEnd { .. } => EventTriggerType::PollEnd,
+            EventTrigger::PredictionBegin => EventTriggerType::PredictionBegin,
+            EventTrigger::PredictionProgress => EventTriggerType::PredictionProgress,
+            EventTrigger::PredictionLock => EventTriggerType::PredictionLock,
+            EventTrigger::PredictionEnd { .. } => EventTriggerType::PredictionEnd,
+            EventTrigger::CharityDonation { .. } => EventTriggerType::CharityDonation,
+            EventTrigger::Webhook { .. } => EventTriggerType::Webhook,
         }
     }
 }
@@ -114,18 +181,65 @@ pub enum EventTrigger {
     Redeem {
         /// ID of the reward required
         reward_id: String,
+        /// Whether to mark the redemption as fulfilled when all
+        /// the outcomes succeed
+        #[serde(default)]
+        fulfil_redemption: bool,
+        /// Whether to cancel the redemption (Refunding the points) when an
+        /// outcome fails or the event is skipped due to the cooldown, usage
+        /// limits or a missing role
+        #[serde(default)]
+        refund_redemption: bool,
     },
     /// Command was sent
     Command {
         /// Command message required
         message: String,
+        /// Additional messages that also trigger the command
+        #[serde(default)]
+        aliases: Vec<String>,
+        /// Arguments the command accepts, when empty any arguments are accepted
+        #[serde(default)]
+        arguments: Vec<CommandArgument>,
+        /// Whether to reply with the command usage when the arguments are invalid
+        #[serde(default)]
+        reply_usage: bool,
+    },
+    /// Chat message matched a regular expression
+    ChatPattern {
+        /// Regular expression that must match somewhere in the message,
+        /// named capture groups are provided as event input data (A group
+        /// named "amount" is used as the input amount for throwables)
+        pattern: String,
     },
     /// User followed
     Follow,
     /// User subscribed
-    Subscription,
+    Subscription {
+        /// Tiers allowed to trigger the event, any tier when empty
+        #[serde(default)]
+        tiers: Vec<SubscriptionTier>,
+        /// Whether the subscription must be (or must not be) a gift,
+        /// gifted and non gifted subscriptions are allowed when not set
+        #[serde(default)]
+        is_gift: Option<bool>,
+    },
     /// User gifted subscription
-    GiftedSubscription,
+    GiftedSubscription {
+        /// Minimum number of subscriptions gifted at once
+        #[serde(default)]
+        min_total: u32,
+    },
+    /// User resubscribed and shared the resubscription message
+    ReSubscription {
+        /// Minimum total months the user must have been subscribed for
+        #[serde(default)]
+        min_cumulative_months: u32,
+        /// Minimum consecutive months the user must have been subscribed for
+        /// (Users that don't share their streak have a streak of zero)
+        #[serde(default)]
+        min_streak_months: u32,
+    },
     /// User gifts bits
     Bits {
         /// Minimum bits to trigger the event
@@ -137,11 +251,30 @@ pub enum EventTrigger {
         min_raiders: u32,
     },
 
-    /// Run the event automatically on a fixed interval timer
+    /// Run the event automatically on a fixed interval timer or cron schedule
     Timer {
-        /// Interval in seconds to run
+        /// Interval in seconds to run (Used when no cron schedule is set)
+        #[serde(default)]
         interval: u64,
 
+        /// Cron expression to run on in local time, takes priority over
+        /// the interval when set
+        #[serde(default)]
+        cron: Option<String>,
+
+        /// Random offset range in seconds applied to each scheduled run
+        /// (Minimum can be negative to run early)
+        #[serde(default)]
+        jitter: Option<MinMax<i64>>,
+
+        /// Local time window the timer is allowed to run within
+        #[serde(default)]
+        active_window: Option<TimerActiveWindow>,
+
+        /// Only run the timer while the stream is live
+        #[serde(default)]
+        only_while_live: bool,
+
         /// Minimum chat messages that must have been received between each interval
         /// for the timer to trigger to prevent spamming when nobody is chatting
         #[serde(default)]
@@ -156,6 +289,253 @@ pub enum EventTrigger {
         /// Minimum viewers required
         min_viewers: u32,
     },
+
+    /// Hype train started
+    HypeTrainBegin,
+
+    /// Hype train received a contribution
+    HypeTrainProgress {
+        /// Minimum hype train level required
+        #[serde(default)]
+        min_level: u32,
+    },
+
+    /// Hype train ended
+    HypeTrainEnd {
+        /// Minimum final hype train level required
+        #[serde(default)]
+        min_level: u32,
+    },
+
+    /// Stream went live
+    StreamOnline,
+
+    /// Stream went offline
+    StreamOffline,
+
+    /// User sent their first chat message of the current stream
+    FirstMessage {
+        /// Only trigger for the first chat message the user has ever sent
+        #[serde(default)]
+        first_time_ever: bool,
+    },
+
+    /// Poll started
+    PollBegin,
+
+    /// Poll received votes
+    PollProgress,
+
+    /// Poll completed or was terminated
+    PollEnd {
+        /// Title of the choice that must have won (Case insensitive),
+        /// any choice when not set
+        #[serde(default)]
+        winning_choice: Option<String>,
+    },
+
+    /// Prediction started
+    PredictionBegin,
+
+    /// Prediction received predictions
+    PredictionProgress,
+
+    /// Prediction was locked
+    PredictionLock,
+
+    /// Prediction was resolved
+    PredictionEnd {
+        /// Title of the outcome that must have won (Case insensitive),
+        /// any outcome when not set
+        #[serde(default)]
+        winning_outcome: Option<String>,
+    },
+
+    /// User donated to the charity campaign
+    ///
+    /// Hype chat donations have no trigger, Twitch does not provide
+    /// them through the EventSub notifications we subscribe to
+    CharityDonation {
+        /// Minimum donation amount required (In the currency major
+        /// unit, i.e dollars for USD)
+        #[serde(default)]
+        min_amount: u32,
+    },
+
+    /// External tool sent a request to the webhook endpoint
+    /// (POST /trigger/:event_id)
+    Webhook {
+        /// Optional slug the webhook can be triggered by instead
+        /// of the event ID
+        #[serde(default)]
+        slug: Option<String>,
+        /// Secret token the request must provide
+        secret: String,
+    },
+}
+
+/// Window of local time that a timer can run within, the window
+/// wraps over midnight when the end is before the start
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct TimerActiveWindow {
+    /// Time the window starts
+    pub start: NaiveTime,
+    /// Time the window ends
+    pub end: NaiveTime,
+}
+
+impl TimerActiveWindow {
+    /// Checks if the provided time is within the window
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        match self.start.cmp(&self.end) {
+            Ordering::Less => self.start <= time && time < self.end,
+            Ordering::Greater => time >= self.start || time < self.end,
+            // Same start and end covers the entire day
+            Ordering::Equal => true,
+        }
+    }
+}
+
+/// Argument accepted by a command trigger
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CommandArgument {
+    /// Name of the argument (Shown in the command usage)
+    pub name: String,
+    /// Type of value the argument accepts
+    pub kind: CommandArgumentKind,
+    /// Whether the argument can be omitted
+    #[serde(default)]
+    pub optional: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CommandArgumentKind {
+    /// Whole number, the first integer argument is used as the
+    /// input amount for throwables
+    Integer,
+    /// Twitch username (Leading @ is removed)
+    User,
+    /// Name of an existing throwable item
+    Item,
+    /// Any text, consumes the remainder of the message
+    Text,
+}
+
+/// Creates the usage message for a command (i.e "Usage: !bonk <amount> [user]")
+pub fn command_usage(message: &str, arguments: &[CommandArgument]) -> String {
+    let mut usage = format!("Usage: {}", message.trim());
+
+    for argument in arguments {
+        if argument.optional {
+            usage.push_str(&format!(" [{}]", argument.name));
+        } else {
+            usage.push_str(&format!(" <{}>", argument.name));
+        }
+    }
+
+    usage
+}
+
+/// Chat replies sent when an event is skipped, replies use the same
+/// template variables as the send chat message outcome
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(default)]
+pub struct EventFeedback {
+    /// Reply when the event is on cooldown, the "$(remaining)" variable
+    /// is replaced with the remaining cooldown in seconds
+    pub cooldown_reply: Option<String>,
+    /// Reply when the user doesn't have the required role
+    pub role_reply: Option<String>,
+    /// Minimum time in seconds between feedback replies for the event
+    pub reply_interval: u32,
+}
+
+impl Default for EventFeedback {
+    fn default() -> Self {
+        Self {
+            cooldown_reply: None,
+            role_reply: None,
+            reply_interval: 30,
+        }
+    }
+}
+
+/// Limits on the number of times an event can be executed, limits
+/// that are not set are not checked
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(default)]
+pub struct EventUsageLimits {
+    /// Maximum number of executions per stream session
+    pub per_stream: Option<u32>,
+    /// Maximum number of executions for each user per stream session
+    pub per_user_per_stream: Option<u32>,
+    /// Maximum number of executions within a rolling time window
+    pub window: Option<EventUsageWindow>,
+}
+
+/// Limit on the number of executions within a rolling time window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventUsageWindow {
+    /// Maximum number of executions within the window
+    pub max: u32,
+    /// Length of the window in seconds
+    pub duration: u32,
+    /// Whether the limit applies to each user separately
+    #[serde(default)]
+    pub per_user: bool,
+}
+
+/// List of conditions that must all be met for an event to execute
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(transparent)]
+pub struct EventConditions(pub Vec<EventCondition>);
+
+/// Condition checked against the event data before executing an event
+///
+/// Conditions that don't apply to the data of an event (i.e a tier condition
+/// on a chat message) are never met
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum EventCondition {
+    /// Numeric value of the event (Bits, viewers, gift total, cumulative
+    /// months, reward cost, ad duration, cheer) must match the comparison
+    Numeric { comparison: NumericComparison },
+    /// Message of the event must contain the provided text
+    MessageContains {
+        /// Text to search for
+        value: String,
+        /// Whether to ignore case when searching
+        #[serde(default)]
+        ignore_case: bool,
+    },
+    /// Message of the event must match the provided regular expression
+    MessageMatches { pattern: String },
+    /// User must be one of the listed users (Username or ID)
+    UserInList { users: Vec<String> },
+    /// Subscription tier must be the provided tier
+    SubscriptionTier { tier: SubscriptionTier },
+    /// Subscription must be (or must not be) a gift
+    IsGift { value: bool },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum NumericComparison {
+    /// Value must be exactly the provided value
+    Equal { value: i64 },
+    /// Value must be within the inclusive range, missing bounds are unbounded
+    Range { min: Option<i64>, max: Option<i64> },
+}
+
+impl NumericComparison {
+    pub fn is_match(&self, value: i64) -> bool {
+        match self {
+            NumericComparison::Equal { value: expected } => value == *expected,
+            NumericComparison::Range { min, max } => {
+                min.is_none_or(|min| value >= min) && max.is_none_or(|max| value <= max)
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -244,9 +624,50 @@ pub struct EventOutcomePlaySound {
     pub sound_id: Uuid,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
 pub struct EventOutcomeSendChat {
     pub template: String,
+    /// Reply in a thread to the chat message that triggered the
+    /// event (Only applies to chat triggers)
+    #[serde(default)]
+    pub reply: bool,
+    /// Send the message as an announcement highlighted with the
+    /// color, announcements cannot be replies
+    #[serde(default)]
+    pub announcement: Option<AnnouncementColor>,
+    /// Account to send the message from
+    #[serde(default)]
+    pub identity: ChatIdentity,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EventOutcomeRandom {
+    /// Outcomes to pick from
+    pub choices: Vec<EventOutcomeRandomChoice>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EventOutcomeRandomChoice {
+    /// Weight of the choice relative to the other choices, choices
+    /// with a zero weight are never picked
+    pub weight: u32,
+    /// Outcome to run when picked
+    pub outcome: EventOutcome,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EventOutcomeRunEvent {
+    /// IDs of the events to run
+    pub event_ids: Vec<Uuid>,
+    /// Delay in milliseconds before running the events
+    #[serde(default)]
+    pub delay: u32,
+    /// Whether the cooldown of the events should be checked
+    #[serde(default)]
+    pub check_cooldown: bool,
+    /// Whether the required role of the events should be checked
+    #[serde(default)]
+    pub check_role: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -255,7 +676,94 @@ pub struct EventOutcomeChannelEmotes {
     pub amount: ThrowableAmountData,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, FromJsonQueryResult)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EventOutcomeSetRewardState {
+    /// IDs of the rewards to update
+    #[serde(default)]
+    pub reward_ids: Vec<String>,
+    /// Whether to also update the reward that was redeemed to
+    /// trigger the event (Only applies to redeem triggers)
+    #[serde(default)]
+    pub include_redeemed_reward: bool,
+    /// Paused state to set, unchanged when not specified
+    #[serde(default)]
+    pub paused: Option<bool>,
+    /// Enabled state to set, unchanged when not specified
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    /// Duration in seconds before the previous state is restored, the
+    /// state is kept when zero
+    ///
+    /// Pending reverts are not persisted, rewards keep the updated state
+    /// if the app is closed before the duration has elapsed
+    #[serde(default)]
+    pub duration: u32,
+}
+
+/// User targeted by a moderation outcome
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EventOutcomeTargetUser {
+    /// User who triggered the event
+    #[default]
+    Trigger,
+    /// User named by the first word of the redeem message or
+    /// the first command argument (i.e "@username")
+    Input,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EventOutcomeTimeoutUser {
+    /// User to timeout
+    #[serde(default)]
+    pub target: EventOutcomeTargetUser,
+    /// Duration of the timeout in seconds
+    pub duration: u32,
+    /// Template for the reason of the timeout
+    #[serde(default)]
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EventOutcomeShoutout {
+    /// User to shoutout
+    #[serde(default)]
+    pub target: EventOutcomeTargetUser,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EventOutcomeAnnouncement {
+    /// Template for the announcement message
+    pub template: String,
+    /// Color to highlight the announcement with
+    #[serde(default)]
+    pub color: AnnouncementColor,
+    /// Account to send the announcement from
+    #[serde(default)]
+    pub identity: ChatIdentity,
+}
+
+/// Ordered list of outcome steps, steps are executed sequentially
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(transparent)]
+pub struct EventOutcomes(pub Vec<EventOutcomeStep>);
+
+impl From<EventOutcome> for EventOutcomes {
+    fn from(outcome: EventOutcome) -> Self {
+        Self(vec![EventOutcomeStep { delay: 0, outcome }])
+    }
+}
+
+/// Single step of an event outcome sequence
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventOutcomeStep {
+    /// Delay in milliseconds before executing the step
+    #[serde(default)]
+    pub delay: u32,
+    /// Outcome the step should trigger
+    pub outcome: EventOutcome,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type")]
 pub enum EventOutcome {
     /// Throw bits (Only compatible with bits trigger)
@@ -270,6 +778,20 @@ pub enum EventOutcome {
     SendChatMessage(EventOutcomeSendChat),
     /// Throw the emotes of a specific channel
     ChannelEmotes(EventOutcomeChannelEmotes),
+    /// Run other events
+    RunEvent(EventOutcomeRunEvent),
+    /// Run one outcome picked at random
+    Random(EventOutcomeRandom),
+    /// Pause, unpause, enable or disable channel point rewards
+    SetRewardState(EventOutcomeSetRewardState),
+    /// Timeout a user
+    TimeoutUser(EventOutcomeTimeoutUser),
+    /// Delete the chat message that triggered the event
+    DeleteMessage,
+    /// Send an official shoutout to a user
+    Shoutout(EventOutcomeShoutout),
+    /// Send a highlighted announcement to chat
+    Announcement(EventOutcomeAnnouncement),
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -277,6 +799,9 @@ pub enum Relation {
     /// Event can have many executions
     #[sea_orm(has_many = "super::event_executions::Entity")]
     Executions,
+    /// Event can be in many groups
+    #[sea_orm(has_many = "super::event_group_events::Entity")]
+    Groups,
 }
 
 impl Related<super::event_executions::Entity> for Entity {
@@ -285,6 +810,12 @@ impl Related<super::event_executions::Entity> for Entity {
     }
 }
 
+impl Related<super::event_group_events::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Groups.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
 
 #[derive(Debug, Deserialize)]
@@ -292,10 +823,18 @@ pub struct CreateEvent {
     pub enabled: bool,
     pub name: String,
     pub trigger: EventTrigger,
-    pub outcome: EventOutcome,
+    pub outcomes: EventOutcomes,
     pub cooldown: EventCooldown,
     pub require_role: MinimumRequireRole,
+    #[serde(default)]
+    pub role_requirements: RoleRequirements,
     pub outcome_delay: u32,
+    #[serde(default)]
+    pub conditions: EventConditions,
+    #[serde(default)]
+    pub feedback: EventFeedback,
+    #[serde(default)]
+    pub usage_limits: EventUsageLimits,
 }
 
 #[derive(Default, Deserialize)]
@@ -303,10 +842,14 @@ pub struct UpdateEvent {
     pub enabled: Option<bool>,
     pub name: Option<String>,
     pub trigger: Option<EventTrigger>,
-    pub outcome: Option<EventOutcome>,
+    pub outcomes: Option<EventOutcomes>,
     pub cooldown: Option<EventCooldown>,
     pub require_role: Option<MinimumRequireRole>,
+    pub role_requirements: Option<RoleRequirements>,
     pub outcome_delay: Option<u32>,
+    pub conditions: Option<EventConditions>,
+    pub feedback: Option<EventFeedback>,
+    pub usage_limits: Option<EventUsageLimits>,
     pub order: Option<u32>,
 }
 
@@ -323,10 +866,14 @@ impl Model {
             name: Set(create.name),
             trigger_type: Set(EventTriggerType::from_event_trigger(&create.trigger)),
             trigger: Set(create.trigger),
-            outcome: Set(create.outcome),
+            outcomes: Set(create.outcomes),
             cooldown: Set(create.cooldown),
             require_role: Set(create.require_role),
+            role_requirements: Set(create.role_requirements),
             outcome_delay: Set(create.outcome_delay),
+            conditions: Set(create.conditions),
+            feedback: Set(create.feedback),
+            usage_limits: Set(create.usage_limits),
             order: Set(0),
             created_at: Set(Utc::now()),
         };
@@ -343,19 +890,68 @@ impl Model {
     }
 
     /// Find the most recent execution of this event
-    pub async fn last_execution<C>(
+    pub async fn last_execution<C>(&self, db: &C) -> DbResult<Option<EventExecutionModel>>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        self.find_related(super::event_executions::Entity)
+            .order_by_desc(EventExecutionColumn::CreatedAt)
+            .one(db)
+            .await
+    }
+
+    /// Count the executions of this event, optionally only counting executions
+    /// within the stream session `stream_session_id`, executions triggered by
+    /// the user with the `user_id` and executions created after `since`
+    pub async fn count_executions<C>(
         &self,
         db: &C,
-        offset: u64,
+        stream_session_id: Option<Uuid>,
+        user_id: Option<&str>,
+        since: Option<DateTimeUtc>,
+    ) -> DbResult<u64>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        let mut select = self.find_related(super::event_executions::Entity);
+
+        if let Some(stream_session_id) = stream_session_id {
+            select = select.filter(EventExecutionColumn::StreamSessionId.eq(stream_session_id));
+        }
+
+        if let Some(user_id) = user_id {
+            select = select.filter(EventExecutionColumn::UserId.eq(user_id));
+        }
+
+        if let Some(since) = since {
+            select = select.filter(EventExecutionColumn::CreatedAt.gt(since));
+        }
+
+        select.count(db).await
+    }
+
+    /// Find the most recent execution of this event triggered by
+    /// the user with the provided `user_id`
+    pub async fn last_user_execution<C>(
+        &self,
+        db: &C,
+        user_id: &str,
     ) -> DbResult<Option<EventExecutionModel>>
     where
         C: ConnectionTrait + Send + 'static,
     {
+        self.last_user_execution_query(user_id).one(db).await
+    }
+
+    /// Query for the latest execution of the event by the user, covered by
+    /// the event, user and creation time index of the executions
+    pub fn last_user_execution_query(
+        &self,
+        user_id: &str,
+    ) -> Select<super::event_executions::Entity> {
         self.find_related(super::event_executions::Entity)
+            .filter(EventExecutionColumn::UserId.eq(user_id))
             .order_by_desc(EventExecutionColumn::CreatedAt)
-            .offset(offset)
-            .one(db)
-            .await
     }
 
     /// Find a specific event by ID
@@ -366,6 +962,19 @@ impl Model {
         Entity::find_by_id(id).one(db).await
     }
 
+    /// Find an event by ID that is allowed to run, the event must be
+    /// enabled and pass the same group condition as [Self::get_by_trigger_type]
+    pub async fn get_runnable_by_id<C>(db: &C, id: Uuid) -> DbResult<Option<Self>>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        Entity::find_by_id(id)
+            .filter(Column::Enabled.eq(true))
+            .filter(Self::group_enabled_condition())
+            .one(db)
+            .await
+    }
+
     /// Find a specific event by a specific trigger type
     ///
     /// Filters to only events marked as enabled
@@ -382,10 +991,47 @@ impl Model {
                     .eq(trigger_type)
                     .and(Column::Enabled.eq(true)),
             )
+            .filter(Self::group_enabled_condition())
             .all(db)
             .await
     }
 
+    /// Condition for events whose groups allow them to run, events that are not in any
+    /// groups can always run otherwise at least one of their groups must be enabled
+    fn group_enabled_condition() -> Condition {
+        Condition::any()
+            .add(
+                Column::Id.not_in_subquery(
+                    Query::select()
+                        .column(event_group_events::Column::EventId)
+                        .from(event_group_events::Entity)
+                        .to_owned(),
+                ),
+            )
+            .add(
+                Column::Id.in_subquery(
+                    Query::select()
+                        .column((
+                            event_group_events::Entity,
+                            event_group_events::Column::EventId,
+                        ))
+                        .from(event_group_events::Entity)
+                        .inner_join(
+                            event_groups::Entity,
+                            Expr::col((event_groups::Entity, event_groups::Column::Id)).equals((
+                                event_group_events::Entity,
+                                event_group_events::Column::GroupId,
+                            )),
+                        )
+                        .and_where(
+                            Expr::col((event_groups::Entity, event_groups::Column::Enabled))
+                                .eq(true),
+                        )
+                        .to_owned(),
+                ),
+            )
+    }
+
     /// Find all events
     pub async fn all<C>(db: &C) -> DbResult<Vec<Self>>
     where
@@ -414,10 +1060,17 @@ impl Model {
             .map(Set)
             .unwrap_or(this.trigger_type);
         this.trigger = data.trigger.map(Set).unwrap_or(this.trigger);
-        this.outcome = data.outcome.map(Set).unwrap_or(this.outcome);
+        this.outcomes = data.outcomes.map(Set).unwrap_or(this.outcomes);
         this.cooldown = data.cooldown.map(Set).unwrap_or(this.cooldown);
         this.require_role = data.require_role.map(Set).unwrap_or(this.require_role);
+        this.role_requirements = data
+            .role_requirements
+            .map(Set)
+            .unwrap_or(this.role_requirements);
         this.outcome_delay = data.outcome_delay.map(Set).unwrap_or(this.outcome_delay);
+        this.conditions = data.conditions.map(Set).unwrap_or(this.conditions);
+        this.feedback = data.feedback.map(Set).unwrap_or(this.feedback);
+        this.usage_limits = data.usage_limits.map(Set).unwrap_or(this.usage_limits);
         this.order = data.order.map(Set).unwrap_or(this.order);
 
         let this = this.update(db).await?;
@@ -8,8 +8,10 @@ use anyhow::Context;
 use chrono::Utc;
 use futures::{future::BoxFuture, stream::FuturesUnordered, TryStreamExt};
 use sea_orm::{
-    entity::prelude::*, sea_query::CaseStatement, ActiveValue::Set, FromJsonQueryResult,
-    IntoActiveModel, QueryOrder, UpdateResult,
+    entity::prelude::*,
+    sea_query::{CaseStatement, Func},
+    ActiveValue::Set,
+    FromJsonQueryResult, IntoActiveModel, QueryOrder, UpdateResult,
 };
 use serde::{Deserialize, Serialize};
 
@@ -145,6 +147,17 @@ impl Model {
         Entity::find_by_id(id).one(db).await
     }
 
+    /// Find a specific item by name (Case insensitive)
+    pub async fn get_by_name<C>(db: &C, name: &str) -> DbResult<Option<Self>>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        Entity::find()
+            .filter(Expr::expr(Func::lower(Expr::col(Column::Name))).eq(name.to_lowercase()))
+            .one(db)
+            .await
+    }
+
     /// Find items with IDs present in the provided list
     pub async fn get_by_ids_with_sounds<C>(
         db: &C,
@@ -1,6 +1,9 @@
 pub mod app_data;
 pub mod chat_history;
+pub mod chat_users;
 pub mod event_executions;
+pub mod event_group_events;
+pub mod event_groups;
 pub mod events;
 pub mod items;
 pub mod items_sounds;
@@ -8,5 +11,6 @@ pub mod links;
 pub mod model_data;
 pub mod shared;
 pub mod sounds;
+pub mod stream_sessions;
 pub mod twitch_access;
 pub mod vt_access;
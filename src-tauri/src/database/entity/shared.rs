@@ -1,6 +1,7 @@
 use sea_orm::prelude::*;
-use sea_orm::{DeriveActiveEnum, EnumIter};
+use sea_orm::{DeriveActiveEnum, EnumIter, FromJsonQueryResult};
 use serde::{Deserialize, Serialize};
+use twitch_api::types::SubscriptionTier;
 
 pub type DbResult<T> = Result<T, DbErr>;
 
@@ -14,6 +15,8 @@ pub enum MinimumRequireRole {
     None,
     #[sea_orm(string_value = "Follower")]
     Follower,
+    #[sea_orm(string_value = "Subscriber")]
+    Subscriber,
     #[sea_orm(string_value = "Vip")]
     Vip,
     #[sea_orm(string_value = "Mod")]
@@ -22,6 +25,18 @@ pub enum MinimumRequireRole {
     Broadcaster,
 }
 
+/// Additional requirements for the [MinimumRequireRole]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(default)]
+pub struct RoleRequirements {
+    /// Minimum subscription tier for the [MinimumRequireRole::Subscriber]
+    /// role, any tier when not set
+    pub min_subscriber_tier: Option<SubscriptionTier>,
+    /// Minimum number of days a user must have been following
+    /// for the [MinimumRequireRole::Follower] role
+    pub min_follow_days: u32,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MinMax<T> {
     /// Minimum value
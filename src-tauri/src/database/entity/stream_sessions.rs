@@ -0,0 +1,92 @@
+use super::shared::DbResult;
+use anyhow::Context;
+use sea_orm::{entity::prelude::*, ActiveValue::Set, QueryOrder};
+use serde::{Deserialize, Serialize};
+
+// Type alias helpers for the database entity types
+pub type StreamSessionModel = Model;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "stream_sessions")]
+pub struct Model {
+    /// Unique ID of the stream session
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    /// Twitch ID of the stream
+    pub stream_id: String,
+    /// Time the stream started
+    pub started_at: DateTimeUtc,
+    /// Time the stream ended, not present while the stream is live
+    pub ended_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Debug)]
+pub struct CreateStreamSession {
+    /// Twitch ID of the stream
+    pub stream_id: String,
+    /// Time the stream started
+    pub started_at: DateTimeUtc,
+}
+
+impl Model {
+    /// Create a new stream session
+    pub async fn create<C>(db: &C, create: CreateStreamSession) -> anyhow::Result<Model>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        let id = Uuid::new_v4();
+        let active_model = ActiveModel {
+            id: Set(id),
+            stream_id: Set(create.stream_id),
+            started_at: Set(create.started_at),
+            ended_at: Set(None),
+        };
+
+        Entity::insert(active_model)
+            .exec_without_returning(db)
+            .await?;
+
+        let model = Self::get_by_id(db, id)
+            .await?
+            .context("model was not inserted")?;
+
+        Ok(model)
+    }
+
+    pub async fn get_by_id<C>(db: &C, id: Uuid) -> DbResult<Option<Self>>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        Entity::find_by_id(id).one(db).await
+    }
+
+    /// Get the session for the stream that is currently live
+    pub async fn get_current<C>(db: &C) -> DbResult<Option<Self>>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        Entity::find()
+            .filter(Column::EndedAt.is_null())
+            .order_by_desc(Column::StartedAt)
+            .one(db)
+            .await
+    }
+
+    /// Ends any sessions that have not yet ended
+    pub async fn end_current<C>(db: &C, ended_at: DateTimeUtc) -> DbResult<()>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        Entity::update_many()
+            .col_expr(Column::EndedAt, Expr::value(ended_at))
+            .filter(Column::EndedAt.is_null())
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+}
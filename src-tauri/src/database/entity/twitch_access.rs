@@ -45,13 +45,31 @@ impl Model {
     /// Only one row should ever be created and should have this ID
     const SINGLETON_ID: i32 = 1;
 
+    /// ID of the row storing the access for the separate bot account
+    const BOT_ID: i32 = 2;
+
     /// Create a new sound
     pub async fn set<C>(db: &C, create: SetTwitchAccess) -> anyhow::Result<Model>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        Self::set_by_id(db, Self::SINGLETON_ID, create).await
+    }
+
+    /// Set the access for the bot account
+    pub async fn set_bot<C>(db: &C, create: SetTwitchAccess) -> anyhow::Result<Model>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        Self::set_by_id(db, Self::BOT_ID, create).await
+    }
+
+    async fn set_by_id<C>(db: &C, id: i32, create: SetTwitchAccess) -> anyhow::Result<Model>
     where
         C: ConnectionTrait + Send + 'static,
     {
         let active_model = ActiveModel {
-            id: Set(Self::SINGLETON_ID),
+            id: Set(id),
             access_token: Set(DbAccessToken(create.access_token)),
             scopes: Set(DbScopes(create.scopes)),
             created_at: Set(Utc::now()),
@@ -66,7 +84,10 @@ impl Model {
             .exec_without_returning(db)
             .await?;
 
-        let model = Self::get(db).await?.context("model was not inserted")?;
+        let model = Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .context("model was not inserted")?;
 
         Ok(model)
     }
@@ -77,4 +98,19 @@ impl Model {
     {
         Entity::find_by_id(Self::SINGLETON_ID).one(db).await
     }
+
+    pub async fn get_bot<C>(db: &C) -> DbResult<Option<Self>>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        Entity::find_by_id(Self::BOT_ID).one(db).await
+    }
+
+    pub async fn delete_bot<C>(db: &C) -> DbResult<()>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        Entity::delete_by_id(Self::BOT_ID).exec(db).await?;
+        Ok(())
+    }
 }
@@ -0,0 +1,182 @@
+//! # Split Re-Subscription Trigger
+//!
+//! Resubscriptions previously triggered "Subscription" events. They now have
+//! their own "ReSubscription" trigger, so this migration creates a matching
+//! "ReSubscription" copy of each existing "Subscription" event to preserve
+//! the previous behavior
+//!
+//! Reverting converts "ReSubscription" events back into "Subscription" events,
+//! unchanged copies created by this migration are removed instead as the
+//! original "Subscription" event already handles resubscriptions
+
+use sea_orm::{ConnectionTrait, TransactionTrait};
+use sea_orm_migration::prelude::*;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Trigger for the copied events, accepts any resubscription
+const RE_SUBSCRIPTION_TRIGGER: &str =
+    r#"{"type":"ReSubscription","min_cumulative_months":0,"min_streak_months":0}"#;
+
+/// Trigger for reverted events, accepts any subscription
+const SUBSCRIPTION_TRIGGER: &str = r#"{"type":"Subscription"}"#;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let db = db.begin().await?;
+        let backend = db.get_database_backend();
+
+        let rows = db
+            .query_all(
+                backend.build(
+                    Query::select()
+                        .columns([Events::Id, Events::Name])
+                        .from(Events::Table)
+                        .and_where(Expr::col(Events::TriggerType).eq("Subscription")),
+                ),
+            )
+            .await?;
+
+        for row in rows {
+            let id: Uuid = row.try_get("", "id")?;
+            let name: String = row.try_get("", "name")?;
+
+            let insert = Query::insert()
+                .into_table(Events::Table)
+                .columns([
+                    Events::Id,
+                    Events::Enabled,
+                    Events::Name,
+                    Events::TriggerType,
+                    Events::Trigger,
+                    Events::Outcome,
+                    Events::Cooldown,
+                    Events::RequireRole,
+                    Events::OutcomeDelay,
+                    Events::Order,
+                    Events::CreatedAt,
+                    Events::Conditions,
+                ])
+                .select_from(
+                    Query::select()
+                        .expr(Expr::value(Uuid::new_v4()))
+                        .column(Events::Enabled)
+                        .expr(Expr::value(format!("{name} (Resubscription)")))
+                        .expr(Expr::value("ReSubscription"))
+                        .expr(Expr::value(RE_SUBSCRIPTION_TRIGGER))
+                        .columns([
+                            Events::Outcome,
+                            Events::Cooldown,
+                            Events::RequireRole,
+                            Events::OutcomeDelay,
+                            Events::Order,
+                            Events::CreatedAt,
+                            Events::Conditions,
+                        ])
+                        .from(Events::Table)
+                        .and_where(Expr::col(Events::Id).eq(id))
+                        .to_owned(),
+                )
+                .map_err(|err| DbErr::Custom(err.to_string()))?
+                .to_owned();
+
+            db.execute(backend.build(&insert)).await?;
+        }
+
+        db.commit().await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let db = db.begin().await?;
+        let backend = db.get_database_backend();
+
+        let subscription_names = db
+            .query_all(
+                backend.build(
+                    Query::select()
+                        .column(Events::Name)
+                        .from(Events::Table)
+                        .and_where(Expr::col(Events::TriggerType).eq("Subscription")),
+                ),
+            )
+            .await?
+            .into_iter()
+            .map(|row| row.try_get::<String>("", "name"))
+            .collect::<Result<HashSet<String>, DbErr>>()?;
+
+        // Copies that still use the default trigger
+        let copies = db
+            .query_all(
+                backend.build(
+                    Query::select()
+                        .columns([Events::Id, Events::Name])
+                        .from(Events::Table)
+                        .and_where(Expr::col(Events::TriggerType).eq("ReSubscription"))
+                        .and_where(Expr::col(Events::Trigger).eq(RE_SUBSCRIPTION_TRIGGER)),
+                ),
+            )
+            .await?;
+
+        for row in copies {
+            let id: Uuid = row.try_get("", "id")?;
+            let name: String = row.try_get("", "name")?;
+
+            let is_copy = name
+                .strip_suffix(" (Resubscription)")
+                .is_some_and(|name| subscription_names.contains(name));
+
+            if is_copy {
+                db.execute(
+                    backend.build(
+                        Query::delete()
+                            .from_table(Events::Table)
+                            .and_where(Expr::col(Events::Id).eq(id)),
+                    ),
+                )
+                .await?;
+            }
+        }
+
+        db.execute(
+            backend.build(
+                Query::update()
+                    .table(Events::Table)
+                    .values([
+                        (Events::TriggerType, "Subscription".into()),
+                        (Events::Trigger, SUBSCRIPTION_TRIGGER.into()),
+                    ])
+                    .and_where(Expr::col(Events::TriggerType).eq("ReSubscription")),
+            ),
+        )
+        .await?;
+
+        db.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Events {
+    Table,
+    Id,
+    Enabled,
+    Name,
+    TriggerType,
+    Trigger,
+    Outcome,
+    Cooldown,
+    RequireRole,
+    OutcomeDelay,
+    Order,
+    CreatedAt,
+    Conditions,
+}
@@ -0,0 +1,95 @@
+//! # Create Stream Sessions
+//!
+//! Migration that creates the stream sessions table and adds a session
+//! reference to event executions and chat history
+
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(StreamSessions::Table)
+                    .if_not_exists()
+                    .col(pk_uuid(StreamSessions::Id))
+                    .col(string(StreamSessions::StreamId))
+                    .col(date_time(StreamSessions::StartedAt))
+                    .col(date_time_null(StreamSessions::EndedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Existing executions and chat history have no session
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(EventExecutions::Table)
+                    .add_column(uuid_null(EventExecutions::StreamSessionId))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChatHistory::Table)
+                    .add_column(uuid_null(ChatHistory::StreamSessionId))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChatHistory::Table)
+                    .drop_column(ChatHistory::StreamSessionId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(EventExecutions::Table)
+                    .drop_column(EventExecutions::StreamSessionId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(StreamSessions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StreamSessions {
+    Table,
+    /// Unique ID of the session
+    Id,
+    /// Twitch ID of the stream
+    StreamId,
+    /// Time the stream started
+    StartedAt,
+    /// Time the stream ended (Null while live)
+    EndedAt,
+}
+
+#[derive(DeriveIden)]
+enum EventExecutions {
+    Table,
+    StreamSessionId,
+}
+
+#[derive(DeriveIden)]
+enum ChatHistory {
+    Table,
+    StreamSessionId,
+}
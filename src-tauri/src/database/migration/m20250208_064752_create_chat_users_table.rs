@@ -0,0 +1,75 @@
+//! # Create Chat Users
+//!
+//! Migration that creates the table for tracking users that have been
+//! seen chatting, existing chat history is used to populate the table
+
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChatUsers::Table)
+                    .if_not_exists()
+                    .col(string(ChatUsers::UserId).primary_key())
+                    .col(date_time(ChatUsers::FirstSeenAt))
+                    .col(date_time(ChatUsers::LastSeenAt))
+                    .col(uuid_null(ChatUsers::LastStreamSessionId))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Populate from the existing chat history
+        let insert = Query::insert()
+            .into_table(ChatUsers::Table)
+            .columns([
+                ChatUsers::UserId,
+                ChatUsers::FirstSeenAt,
+                ChatUsers::LastSeenAt,
+            ])
+            .select_from(
+                Query::select()
+                    .column(ChatHistory::UserId)
+                    .expr(Func::min(Expr::col(ChatHistory::CreatedAt)))
+                    .expr(Func::max(Expr::col(ChatHistory::CreatedAt)))
+                    .from(ChatHistory::Table)
+                    .group_by_col(ChatHistory::UserId)
+                    .to_owned(),
+            )
+            .map_err(|err| DbErr::Custom(err.to_string()))?
+            .to_owned();
+
+        manager.exec_stmt(insert).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ChatUsers::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ChatUsers {
+    Table,
+    /// Twitch user ID
+    UserId,
+    /// Time of the first chat message
+    FirstSeenAt,
+    /// Time of the last chat message
+    LastSeenAt,
+    /// Stream session of the last chat message (Optional)
+    LastStreamSessionId,
+}
+
+#[derive(DeriveIden)]
+enum ChatHistory {
+    Table,
+    UserId,
+    CreatedAt,
+}
@@ -0,0 +1,144 @@
+//! # Create Event Outcome Steps
+//!
+//! Events previously had a single "outcome", they now have an ordered list
+//! of "outcomes" steps. This migration adds the "outcomes" column, moves each
+//! existing outcome into a single step without a delay and then drops the
+//! previous "outcome" column
+
+use sea_orm::{ConnectionTrait, TransactionTrait};
+use sea_orm_migration::prelude::*;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Events::Table)
+                    .add_column(
+                        ColumnDef::new(Events::Outcomes)
+                            .json()
+                            .not_null()
+                            .default("[]"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+        let db = db.begin().await?;
+        let backend = db.get_database_backend();
+
+        let rows = db
+            .query_all(
+                backend.build(
+                    Query::select()
+                        .columns([Events::Id, Events::Outcome])
+                        .from(Events::Table),
+                ),
+            )
+            .await?;
+
+        for row in rows {
+            let id: Uuid = row.try_get("", "id")?;
+            let outcome: Value = row.try_get("", "outcome")?;
+            let outcomes = json!([{ "delay": 0, "outcome": outcome }]);
+
+            db.execute(
+                backend.build(
+                    Query::update()
+                        .table(Events::Table)
+                        .value(Events::Outcomes, outcomes)
+                        .and_where(Expr::col(Events::Id).eq(id)),
+                ),
+            )
+            .await?;
+        }
+
+        db.commit().await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Events::Table)
+                    .drop_column(Events::Outcome)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Events::Table)
+                    .add_column(
+                        ColumnDef::new(Events::Outcome)
+                            .json()
+                            .not_null()
+                            .default("{}"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+        let db = db.begin().await?;
+        let backend = db.get_database_backend();
+
+        let rows = db
+            .query_all(
+                backend.build(
+                    Query::select()
+                        .columns([Events::Id, Events::Outcomes])
+                        .from(Events::Table),
+                ),
+            )
+            .await?;
+
+        for row in rows {
+            let id: Uuid = row.try_get("", "id")?;
+            let outcomes: Value = row.try_get("", "outcomes")?;
+
+            // Only the first step can be kept
+            let outcome = match outcomes.get(0).and_then(|step| step.get("outcome")) {
+                Some(value) => value.clone(),
+                None => continue,
+            };
+
+            db.execute(
+                backend.build(
+                    Query::update()
+                        .table(Events::Table)
+                        .value(Events::Outcome, outcome)
+                        .and_where(Expr::col(Events::Id).eq(id)),
+                ),
+            )
+            .await?;
+        }
+
+        db.commit().await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Events::Table)
+                    .drop_column(Events::Outcomes)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Events {
+    Table,
+    Id,
+    Outcome,
+    Outcomes,
+}
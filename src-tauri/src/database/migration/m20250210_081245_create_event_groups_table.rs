@@ -0,0 +1,92 @@
+//! # Create Event Groups
+//!
+//! Migration that creates the "event_groups" table for groups of events that
+//! can be enabled and disabled together, along with the "event_group_events"
+//! junction table which stores the connection between groups and events
+
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20241208_060138_create_events_table::Events;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(EventGroups::Table)
+                    .if_not_exists()
+                    .col(pk_uuid(EventGroups::Id))
+                    .col(string(EventGroups::Name))
+                    .col(boolean(EventGroups::Enabled))
+                    .col(date_time(EventGroups::CreatedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(EventGroupEvents::Table)
+                    .if_not_exists()
+                    .col(uuid(EventGroupEvents::GroupId))
+                    .col(uuid(EventGroupEvents::EventId))
+                    // Junction table uses a composite key of the group and event id combined
+                    .primary_key(
+                        Index::create()
+                            .name("pk_event_group_events")
+                            .col(EventGroupEvents::GroupId)
+                            .col(EventGroupEvents::EventId),
+                    )
+                    // Connect to event groups table
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_event_group_events_group_id")
+                            .from(EventGroupEvents::Table, EventGroupEvents::GroupId)
+                            .to(EventGroups::Table, EventGroups::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    // Connect to events table
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_event_group_events_event_id")
+                            .from(EventGroupEvents::Table, EventGroupEvents::EventId)
+                            .to(Events::Table, Events::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(EventGroupEvents::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(EventGroups::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum EventGroups {
+    Table,
+    Id,
+    Name,
+    Enabled,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum EventGroupEvents {
+    Table,
+    GroupId,
+    EventId,
+}
@@ -0,0 +1,79 @@
+//! # Add Event Execution User ID
+//!
+//! The user who triggered an event execution was previously only stored
+//! within the execution "metadata". This migration adds an indexed "user_id"
+//! column so per user cooldowns can be checked with a single query, the
+//! column is backfilled from the existing metadata
+
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(EventExecutions::Table)
+                    .add_column(string_null(EventExecutions::UserId))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Backfill the user ID from the execution metadata
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(EventExecutions::Table)
+                    .value(
+                        EventExecutions::UserId,
+                        Expr::cust("json_extract(\"metadata\", '$.user.id')"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-event-executions-event-user-created")
+                    .table(EventExecutions::Table)
+                    .col(EventExecutions::EventId)
+                    .col(EventExecutions::UserId)
+                    .col(EventExecutions::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-event-executions-event-user-created")
+                    .table(EventExecutions::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(EventExecutions::Table)
+                    .drop_column(EventExecutions::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum EventExecutions {
+    Table,
+    EventId,
+    /// ID of the user that triggered the execution
+    UserId,
+    CreatedAt,
+}
@@ -0,0 +1,47 @@
+//! # Add Event Feedback
+//!
+//! Migration that adds the "feedback" column to the events table, storing
+//! the chat replies sent when an event is skipped due to the cooldown or
+//! a missing role
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Events::Table)
+                    .add_column(
+                        ColumnDef::new(Events::Feedback)
+                            .json()
+                            .not_null()
+                            // Existing events have no feedback replies
+                            .default("{}"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Events::Table)
+                    .drop_column(Events::Feedback)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Events {
+    Table,
+    Feedback,
+}
@@ -0,0 +1,47 @@
+//! # Add Event Usage Limits
+//!
+//! Migration that adds the "usage_limits" column to the events table, storing
+//! the maximum number of times an event can be executed per stream, per
+//! user per stream and within a rolling time window
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Events::Table)
+                    .add_column(
+                        ColumnDef::new(Events::UsageLimits)
+                            .json()
+                            .not_null()
+                            // Existing events have no usage limits
+                            .default("{}"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Events::Table)
+                    .drop_column(Events::UsageLimits)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Events {
+    Table,
+    UsageLimits,
+}
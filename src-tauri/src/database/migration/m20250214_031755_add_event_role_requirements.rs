@@ -0,0 +1,47 @@
+//! # Add Event Role Requirements
+//!
+//! Migration that adds the "role_requirements" column to the events table, storing
+//! the minimum subscription tier and follow age required on top of the
+//! minimum required role
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Events::Table)
+                    .add_column(
+                        ColumnDef::new(Events::RoleRequirements)
+                            .json()
+                            .not_null()
+                            // Existing events have no additional requirements
+                            .default("{}"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Events::Table)
+                    .drop_column(Events::RoleRequirements)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Events {
+    Table,
+    RoleRequirements,
+}
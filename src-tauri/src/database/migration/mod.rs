@@ -11,6 +11,16 @@ mod m20250104_053253_create_twitch_access_table;
 mod m20250104_060420_create_vt_access_table;
 mod m20250104_071851_create_app_data_table;
 mod m20250124_082703_create_chat_history_table;
+mod m20250205_091512_add_event_conditions;
+mod m20250206_043120_split_re_subscription_trigger;
+mod m20250207_021408_create_stream_sessions_table;
+mod m20250208_064752_create_chat_users_table;
+mod m20250209_052317_create_event_outcome_steps;
+mod m20250210_081245_create_event_groups_table;
+mod m20250211_043902_add_event_execution_user_id;
+mod m20250212_072114_add_event_feedback;
+mod m20250213_094530_add_event_usage_limits;
+mod m20250214_031755_add_event_role_requirements;
 
 pub struct Migrator;
 
@@ -29,6 +39,16 @@ impl MigratorTrait for Migrator {
             Box::new(m20250104_060420_create_vt_access_table::Migration),
             Box::new(m20250104_071851_create_app_data_table::Migration),
             Box::new(m20250124_082703_create_chat_history_table::Migration),
+            Box::new(m20250205_091512_add_event_conditions::Migration),
+            Box::new(m20250206_043120_split_re_subscription_trigger::Migration),
+            Box::new(m20250207_021408_create_stream_sessions_table::Migration),
+            Box::new(m20250208_064752_create_chat_users_table::Migration),
+            Box::new(m20250209_052317_create_event_outcome_steps::Migration),
+            Box::new(m20250210_081245_create_event_groups_table::Migration),
+            Box::new(m20250211_043902_add_event_execution_user_id::Migration),
+            Box::new(m20250212_072114_add_event_feedback::Migration),
+            Box::new(m20250213_094530_add_event_usage_limits::Migration),
+            Box::new(m20250214_031755_add_event_role_requirements::Migration),
         ]
     }
 }
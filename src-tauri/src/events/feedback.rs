@@ -0,0 +1,123 @@
+//! # Feedback
+//!
+//! Chat replies sent to viewers when an event is skipped because of
+//! the event cooldown, a missing role or invalid command arguments
+
+use super::{
+    matching::EventData,
+    outcome::send_chat_text,
+    template::{render_template, TemplateContext},
+};
+use crate::{database::entity::events::EventModel, twitch::manager::Twitch};
+use chrono::TimeDelta;
+use log::debug;
+use std::time::Duration;
+
+/// Sends a feedback reply for the `event` using the reply `template`, replies
+/// are rate limited using the reply interval of the event
+///
+/// `remaining` is the remaining cooldown for cooldown replies
+pub async fn send_feedback_reply(
+    twitch: &Twitch,
+    event: &EventModel,
+    template: Option<&str>,
+    event_data: &EventData,
+    remaining: Option<TimeDelta>,
+) -> anyhow::Result<()> {
+    let template = match template {
+        Some(value) if !value.trim().is_empty() => value,
+        _ => return Ok(()),
+    };
+
+    if !try_acquire_feedback_reply(twitch, event, event_data) {
+        return Ok(());
+    }
+
+    let message = format_feedback_template(template, event_data.clone(), remaining);
+    send_chat_text(twitch, &message).await
+}
+
+/// Sends the command `usage` message as a feedback reply for the `event`,
+/// rate limited the same as other feedback replies
+pub async fn send_usage_reply(
+    twitch: &Twitch,
+    event: &EventModel,
+    usage: &str,
+    event_data: &EventData,
+) -> anyhow::Result<()> {
+    if !try_acquire_feedback_reply(twitch, event, event_data) {
+        return Ok(());
+    }
+
+    send_chat_text(twitch, usage).await
+}
+
+/// Checks if a feedback reply can be sent for the `event`, replies are
+/// only sent to users and at most once per reply interval of the event
+fn try_acquire_feedback_reply(twitch: &Twitch, event: &EventModel, event_data: &EventData) -> bool {
+    // Replies are only sent to users
+    if event_data.user.is_none() {
+        return false;
+    }
+
+    let interval = Duration::from_secs(event.feedback.reply_interval as u64);
+    if !twitch.try_acquire_reply(event.id, interval) {
+        debug!("skipping feedback reply: rate limited");
+        return false;
+    }
+
+    true
+}
+
+/// Renders a feedback reply `template`, cooldown replies can use
+/// the "$(remaining)" variable for the remaining cooldown
+fn format_feedback_template(
+    template: &str,
+    event_data: EventData,
+    remaining: Option<TimeDelta>,
+) -> String {
+    let mut ctx = TemplateContext::from_event_data(&event_data);
+
+    if let Some(remaining) = remaining {
+        // Round up to avoid telling users to wait 0 seconds
+        let remaining_seconds = (remaining.num_milliseconds() + 999) / 1000;
+        ctx.set("remaining", remaining_seconds);
+    }
+
+    render_template(template, &ctx)
+}
+
+#[cfg(test)]
+mod test {
+    use super::format_feedback_template;
+    use crate::{
+        events::matching::{EventData, EventInputData},
+        twitch::models::TwitchEventUser,
+    };
+    use chrono::TimeDelta;
+    use twitch_api::types::{DisplayName, UserId, UserName};
+
+    /// Tests the cooldown variables are replaced in feedback replies
+    #[test]
+    fn test_format_feedback_template() {
+        let event_data = EventData {
+            user: Some(TwitchEventUser {
+                id: UserId::from_static("mock-user-id"),
+                name: UserName::from_static("mockuser"),
+                display_name: DisplayName::from_static("Mock User"),
+            }),
+            input_data: EventInputData::None,
+            remaining_uses: None,
+        };
+
+        let message = format_feedback_template(
+            "$(user), this is on cooldown for $(remaining)s",
+            event_data.clone(),
+            Some(TimeDelta::milliseconds(12_300)),
+        );
+        assert_eq!(message, "mockuser, this is on cooldown for 13s");
+
+        let message = format_feedback_template("$(user), mods only", event_data, None);
+        assert_eq!(message, "mockuser, mods only");
+    }
+}
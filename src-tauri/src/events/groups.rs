@@ -0,0 +1,172 @@
+//! # Event Groups
+//!
+//! Logic for enabling and disabling groups of events, used by the
+//! commands, the tray menu and the event group chat command
+
+use super::{processing::has_required_role, scheduler::SchedulerHandle};
+use crate::{
+    database::entity::{
+        event_groups::{EventGroupModel, UpdateEventGroup},
+        shared::MinimumRequireRole,
+    },
+    tray::update_tray_menu,
+    twitch::{manager::Twitch, models::TwitchEventChatMsg},
+};
+use log::error;
+use sea_orm::DatabaseConnection;
+use tauri::{AppHandle, Manager};
+
+/// Chat command for changing the state of an event group
+const EVENT_GROUP_COMMAND: &str = "!group";
+
+/// Action to perform on an event group
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventGroupAction {
+    Enable,
+    Disable,
+    Toggle,
+}
+
+/// Sets whether an event group is enabled, the timer scheduler and tray
+/// menu are updated to reflect the new group state
+pub async fn set_event_group_enabled(
+    app: &AppHandle,
+    db: &DatabaseConnection,
+    group: EventGroupModel,
+    enabled: bool,
+) -> anyhow::Result<EventGroupModel> {
+    let group = group
+        .update(
+            db,
+            UpdateEventGroup {
+                enabled: Some(enabled),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    on_event_groups_changed(app, db).await;
+
+    Ok(group)
+}
+
+/// Updates the timer scheduler and tray menu after the event groups have changed
+pub async fn on_event_groups_changed(app: &AppHandle, db: &DatabaseConnection) {
+    // Timer events may have been enabled or disabled
+    if let Some(scheduler) = app.try_state::<SchedulerHandle>() {
+        scheduler.reload_events(db).await;
+    }
+
+    let groups = match EventGroupModel::all(db).await {
+        Ok(value) => value,
+        Err(err) => {
+            error!("failed to load event groups: {err:?}");
+            return;
+        }
+    };
+
+    if let Err(err) = update_tray_menu(app, &groups) {
+        error!("failed to update tray menu: {err:?}");
+    }
+}
+
+/// Handles the event group chat command (i.e "!group enable Gaming") if the
+/// message is for the command, only moderators can use the command
+pub async fn handle_event_group_command(
+    app: &AppHandle,
+    db: &DatabaseConnection,
+    twitch: &Twitch,
+    event: &TwitchEventChatMsg,
+) -> anyhow::Result<()> {
+    let (action, name) = match parse_event_group_command(&event.message.text) {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+
+    if !has_required_role(
+        twitch,
+        Some(event.user_id.clone()),
+        &MinimumRequireRole::Mod,
+        &Default::default(),
+    )
+    .await
+    {
+        return Ok(());
+    }
+
+    let group = match EventGroupModel::get_by_name(db, name).await? {
+        Some(value) => value,
+        None => {
+            twitch
+                .send_chat_message(&format!("Unknown event group \"{name}\""))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let enabled = match action {
+        EventGroupAction::Enable => true,
+        EventGroupAction::Disable => false,
+        EventGroupAction::Toggle => !group.enabled,
+    };
+
+    let group = set_event_group_enabled(app, db, group, enabled).await?;
+    let state = if group.enabled { "enabled" } else { "disabled" };
+
+    twitch
+        .send_chat_message(&format!("Event group \"{}\" {state}", group.name))
+        .await?;
+
+    Ok(())
+}
+
+/// Parses the action and group name from an event group chat command
+fn parse_event_group_command(message: &str) -> Option<(EventGroupAction, &str)> {
+    let message = message.trim();
+
+    let (command, message) = message.split_once(char::is_whitespace)?;
+    if !command.eq_ignore_ascii_case(EVENT_GROUP_COMMAND) {
+        return None;
+    }
+
+    let (action, name) = message.trim_start().split_once(char::is_whitespace)?;
+    let action = match action.to_lowercase().as_str() {
+        "enable" | "on" => EventGroupAction::Enable,
+        "disable" | "off" => EventGroupAction::Disable,
+        "toggle" => EventGroupAction::Toggle,
+        _ => return None,
+    };
+
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    Some((action, name))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_event_group_command, EventGroupAction};
+
+    #[test]
+    fn test_parse_event_group_command() {
+        assert_eq!(
+            parse_event_group_command("!group enable Gaming"),
+            Some((EventGroupAction::Enable, "Gaming"))
+        );
+        assert_eq!(
+            parse_event_group_command("!GROUP off  Just Chatting "),
+            Some((EventGroupAction::Disable, "Just Chatting"))
+        );
+        assert_eq!(
+            parse_event_group_command("!group toggle Gaming"),
+            Some((EventGroupAction::Toggle, "Gaming"))
+        );
+
+        assert_eq!(parse_event_group_command("!group enable"), None);
+        assert_eq!(parse_event_group_command("!group explode Gaming"), None);
+        assert_eq!(parse_event_group_command("!groups enable Gaming"), None);
+        assert_eq!(parse_event_group_command("hello world"), None);
+    }
+}
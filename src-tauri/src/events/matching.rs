@@ -1,25 +1,34 @@
-use std::str::FromStr;
+use std::{collections::HashMap, str::FromStr};
 
 use anyhow::Context;
-use chrono::Utc;
+use chrono::{DateTime, TimeDelta, Utc};
 use log::error;
 use sea_orm::DatabaseConnection;
 use serde::{Deserialize, Deserializer, Serialize};
 use twitch_api::{
     eventsub::channel::chat::Fragment,
-    types::{MsgId, SubscriptionTier},
+    types::{MsgId, SubscriptionTier, UserId},
 };
 use uuid::Uuid;
 
 use crate::{
     database::entity::{
         chat_history::{ChatHistoryModel, CreateChatHistory},
+        chat_users::ChatUserModel,
         events::{EventModel, EventTrigger, EventTriggerType},
+        stream_sessions::{CreateStreamSession, StreamSessionModel},
     },
-    twitch::models::{
-        TwitchEventAdBreakBegin, TwitchEventChatMsg, TwitchEventCheerBits, TwitchEventFollow,
-        TwitchEventGiftSub, TwitchEventRaid, TwitchEventReSub, TwitchEventRedeem,
-        TwitchEventShoutoutReceive, TwitchEventSub, TwitchEventUser,
+    events::pattern::compile_pattern,
+    twitch::{
+        manager::Twitch,
+        models::{
+            TwitchEventAdBreakBegin, TwitchEventCharityDonation, TwitchEventChatMsg,
+            TwitchEventCheerBits, TwitchEventFollow, TwitchEventGiftSub, TwitchEventHypeTrain,
+            TwitchEventHypeTrainEnd, TwitchEventPoll, TwitchEventPrediction, TwitchEventRaid,
+            TwitchEventReSub, TwitchEventRedeem, TwitchEventShoutoutReceive,
+            TwitchEventStreamOnline, TwitchEventSub, TwitchEventUser, TwitchPollChoice,
+            TwitchPredictionOutcome,
+        },
     },
 };
 
@@ -30,6 +39,35 @@ pub struct EventMatchingData {
 
     /// Additional data attached to the event
     pub event_data: EventData,
+
+    /// Named capture groups from the patterns of matched chat pattern
+    /// events, keyed by the ID of the event whose pattern produced them
+    pub pattern_captures: HashMap<Uuid, HashMap<String, String>>,
+}
+
+impl EventMatchingData {
+    pub fn new(events: Vec<EventModel>, event_data: EventData) -> Self {
+        Self {
+            events,
+            event_data,
+            pattern_captures: HashMap::new(),
+        }
+    }
+
+    /// Creates the event data for one of the matched events, chat pattern
+    /// events are given the captures from their own pattern
+    pub fn event_data_for(&self, event: &EventModel) -> EventData {
+        let mut event_data = self.event_data.clone();
+
+        if let (Some(pattern_captures), EventInputData::Chat { captures, .. }) = (
+            self.pattern_captures.get(&event.id),
+            &mut event_data.input_data,
+        ) {
+            captures.clone_from(pattern_captures);
+        }
+
+        event_data
+    }
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
@@ -40,6 +78,11 @@ pub struct EventData {
     /// Additional input data
     #[serde(flatten)]
     pub input_data: EventInputData,
+
+    /// Remaining uses of the event for the user after the current
+    /// execution, set when the event has usage limits
+    #[serde(skip)]
+    pub remaining_uses: Option<u32>,
 }
 
 /// Additional event-specific input data
@@ -124,6 +167,15 @@ pub enum EventInputData {
 
         /// Optional amount of bits cheered (If user cheered bits)
         cheer: Option<usize>,
+
+        /// Named capture groups from any matching chat patterns
+        #[serde(default)]
+        captures: HashMap<String, String>,
+
+        /// Arguments provided after the command message (Parsed against the
+        /// command arguments before the event is executed)
+        #[serde(default)]
+        args: Vec<String>,
     },
 
     /// Raid specific data
@@ -147,12 +199,99 @@ pub enum EventInputData {
         viewer_count: i64,
     },
 
+    /// Hype train specific data
+    #[serde(rename_all = "camelCase")]
+    HypeTrain {
+        /// Current level of the hype train
+        level: i64,
+        /// Total points contributed to the hype train
+        total: i64,
+        /// Points contributed to the current level (Not present once ended)
+        progress: Option<i64>,
+        /// Points required to reach the next level (Not present once ended)
+        goal: Option<i64>,
+    },
+
+    /// Poll specific data
+    #[serde(rename_all = "camelCase")]
+    Poll {
+        /// Question asked by the poll
+        title: String,
+        /// Choices for the poll
+        choices: Vec<TwitchPollChoice>,
+        /// Title of the choice with the most votes (The winning
+        /// choice once the poll has ended)
+        winning_choice: Option<String>,
+        /// Total votes across all choices
+        total_votes: i64,
+    },
+
+    /// Prediction specific data
+    #[serde(rename_all = "camelCase")]
+    Prediction {
+        /// Title of the prediction
+        title: String,
+        /// Possible outcomes for the prediction
+        outcomes: Vec<TwitchPredictionOutcome>,
+        /// Title of the winning outcome (Only present once resolved)
+        winning_outcome: Option<String>,
+        /// Total channel points used across all outcomes
+        total_points: i64,
+    },
+
+    /// Charity donation specific data
+    #[serde(rename_all = "camelCase")]
+    CharityDonation {
+        /// Name of the charity donated to
+        charity_name: String,
+        /// Amount donated (In the currency major unit, i.e dollars for USD)
+        amount: f64,
+        /// Number of decimal places used by the currency
+        decimal_places: i32,
+        /// ISO-4217 three-letter currency code
+        currency: String,
+    },
+
     /// No additional input data
     #[default]
     #[serde(deserialize_with = "deserialize_ignore_any")]
     None,
 }
 
+impl EventInputData {
+    /// Numeric value associated with the input data (i.e the number of bits or raiders)
+    pub fn numeric_value(&self) -> Option<i64> {
+        match self {
+            EventInputData::Redeem { cost, .. } => Some(*cost),
+            EventInputData::Bits { bits, .. } => Some(*bits),
+            EventInputData::GiftedSubscription { total, .. } => Some(*total),
+            EventInputData::ReSubscription {
+                cumulative_months, ..
+            } => Some(*cumulative_months),
+            EventInputData::Chat { cheer, .. } => cheer.map(|value| value as i64),
+            EventInputData::Raid { viewers } => Some(*viewers),
+            EventInputData::AdBreakBegin { duration_seconds } => Some(*duration_seconds as i64),
+            EventInputData::ShoutoutReceive { viewer_count } => Some(*viewer_count),
+            EventInputData::HypeTrain { level, .. } => Some(*level),
+            EventInputData::Poll { total_votes, .. } => Some(*total_votes),
+            EventInputData::Prediction { total_points, .. } => Some(*total_points),
+            EventInputData::CharityDonation { amount, .. } => Some(amount.floor() as i64),
+            _ => None,
+        }
+    }
+
+    /// User provided message associated with the input data
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            EventInputData::Redeem { user_input, .. } => Some(user_input),
+            EventInputData::Bits { message, .. } => Some(message),
+            EventInputData::ReSubscription { message, .. } => Some(message),
+            EventInputData::Chat { message, .. } => Some(message),
+            _ => None,
+        }
+    }
+}
+
 pub fn deserialize_ignore_any<'de, D: Deserializer<'de>, T: Default>(
     deserializer: D,
 ) -> Result<T, D::Error> {
@@ -181,7 +320,7 @@ pub async fn match_redeem_event(
         .filter(|event| {
             matches!(
                 &event.trigger,
-                EventTrigger::Redeem { reward_id } if event_reward_id.eq(reward_id)
+                EventTrigger::Redeem { reward_id, .. } if event_reward_id.eq(reward_id)
             )
         })
         .collect();
@@ -199,9 +338,10 @@ pub async fn match_redeem_event(
             name: event.user_name,
             display_name: event.user_display_name,
         }),
+        remaining_uses: None,
     };
 
-    Ok(EventMatchingData { events, event_data })
+    Ok(EventMatchingData::new(events, event_data))
 }
 
 pub async fn match_cheer_bits_event(
@@ -244,9 +384,52 @@ pub async fn match_cheer_bits_event(
             message: event.message,
         },
         user,
+        remaining_uses: None,
+    };
+
+    Ok(EventMatchingData::new(events, event_data))
+}
+
+pub async fn match_charity_donation_event(
+    db: &DatabaseConnection,
+    event: TwitchEventCharityDonation,
+) -> anyhow::Result<EventMatchingData> {
+    // Convert from the currency minor unit
+    let amount = event.amount as f64 / 10_f64.powi(event.decimal_places);
+
+    let events = EventModel::get_by_trigger_type(db, EventTriggerType::CharityDonation).await;
+    let events = match events {
+        Ok(value) => value,
+        Err(err) => {
+            error!("failed to load events: {:?}", err);
+            Default::default()
+        }
+    };
+
+    // Filter events for the minimum donation amount
+    let events = events
+        .into_iter()
+        .filter(|event| {
+            matches!(&event.trigger, EventTrigger::CharityDonation { min_amount } if amount >= *min_amount as f64)
+        })
+        .collect();
+
+    let event_data = EventData {
+        input_data: EventInputData::CharityDonation {
+            charity_name: event.charity_name,
+            amount,
+            decimal_places: event.decimal_places,
+            currency: event.currency,
+        },
+        user: Some(TwitchEventUser {
+            id: event.user_id,
+            name: event.user_name,
+            display_name: event.user_display_name,
+        }),
+        remaining_uses: None,
     };
 
-    Ok(EventMatchingData { events, event_data })
+    Ok(EventMatchingData::new(events, event_data))
 }
 
 pub async fn match_follow_event(
@@ -271,7 +454,7 @@ pub async fn match_follow_event(
         ..Default::default()
     };
 
-    Ok(EventMatchingData { events, event_data })
+    Ok(EventMatchingData::new(events, event_data))
 }
 
 pub async fn match_subscription_event(
@@ -287,6 +470,18 @@ pub async fn match_subscription_event(
         }
     };
 
+    // Filter events for the matching tier and gift state
+    let events = events
+        .into_iter()
+        .filter(|value| match &value.trigger {
+            EventTrigger::Subscription { tiers, is_gift } => {
+                (tiers.is_empty() || tiers.contains(&event.tier))
+                    && is_gift.is_none_or(|is_gift| is_gift == event.is_gift)
+            }
+            _ => false,
+        })
+        .collect();
+
     let event_data = EventData {
         input_data: EventInputData::Subscription {
             tier: event.tier,
@@ -297,9 +492,10 @@ pub async fn match_subscription_event(
             name: event.user_name,
             display_name: event.user_display_name,
         }),
+        remaining_uses: None,
     };
 
-    Ok(EventMatchingData { events, event_data })
+    Ok(EventMatchingData::new(events, event_data))
 }
 
 pub async fn match_gifted_subscription_event(
@@ -315,6 +511,15 @@ pub async fn match_gifted_subscription_event(
         }
     };
 
+    // Filter events for the minimum gifted total
+    let total = event.total;
+    let events = events
+        .into_iter()
+        .filter(|event| {
+            matches!(&event.trigger, EventTrigger::GiftedSubscription { min_total } if total >= *min_total as i64)
+        })
+        .collect();
+
     // Create user (Bits can be anonymous)
     let user = match (event.user_id, event.user_name, event.user_display_name) {
         (Some(user_id), Some(user_name), Some(user_display_name)) => Some(TwitchEventUser {
@@ -333,16 +538,17 @@ pub async fn match_gifted_subscription_event(
             total: event.total,
         },
         user,
+        remaining_uses: None,
     };
 
-    Ok(EventMatchingData { events, event_data })
+    Ok(EventMatchingData::new(events, event_data))
 }
 
 pub async fn match_re_subscription_event(
     db: &DatabaseConnection,
     event: TwitchEventReSub,
 ) -> anyhow::Result<EventMatchingData> {
-    let events = EventModel::get_by_trigger_type(db, EventTriggerType::Subscription).await;
+    let events = EventModel::get_by_trigger_type(db, EventTriggerType::ReSubscription).await;
     let events = match events {
         Ok(value) => value,
         Err(err) => {
@@ -351,6 +557,23 @@ pub async fn match_re_subscription_event(
         }
     };
 
+    // Filter events for the minimum subscription months
+    let cumulative_months = event.cumulative_months;
+    let streak_months = event.streak_months.unwrap_or_default();
+    let events = events
+        .into_iter()
+        .filter(|event| match &event.trigger {
+            EventTrigger::ReSubscription {
+                min_cumulative_months,
+                min_streak_months,
+            } => {
+                cumulative_months >= *min_cumulative_months as i64
+                    && streak_months >= *min_streak_months as i64
+            }
+            _ => false,
+        })
+        .collect();
+
     let event_data = EventData {
         input_data: EventInputData::ReSubscription {
             cumulative_months: event.cumulative_months,
@@ -364,14 +587,29 @@ pub async fn match_re_subscription_event(
             name: event.user_name,
             display_name: event.user_display_name,
         }),
+        remaining_uses: None,
     };
 
-    Ok(EventMatchingData { events, event_data })
+    Ok(EventMatchingData::new(events, event_data))
+}
+
+/// Time without chatting before a message counts as the first message
+/// when there is no stream session
+const OFFLINE_FIRST_MESSAGE_WINDOW: TimeDelta = TimeDelta::hours(12);
+
+/// Whether a chat message is the first message from the user
+#[derive(Debug, Default, Clone, Copy)]
+struct FirstMessageState {
+    /// First message from the user in the current stream
+    stream: bool,
+    /// First message the user has ever sent
+    ever: bool,
 }
 
 async fn store_chat_event(
     db: &DatabaseConnection,
     event: &TwitchEventChatMsg,
+    stream_session_id: Option<Uuid>,
 ) -> anyhow::Result<()> {
     let id = Uuid::from_str(event.message_id.as_str()).context("invalid message ID")?;
 
@@ -383,6 +621,7 @@ async fn store_chat_event(
             message: event.message.text.clone(),
             cheer: event.cheer.as_ref().map(|value| value.bits as u32),
             created_at: Utc::now(),
+            stream_session_id,
         },
     )
     .await?;
@@ -390,14 +629,66 @@ async fn store_chat_event(
     Ok(())
 }
 
+/// Marks the chat user as seen, determining whether this is the first
+/// message from the user
+///
+/// Without a stream session (i.e the stream is offline) the message is the
+/// first for the stream when the user hasn't chatted for [OFFLINE_FIRST_MESSAGE_WINDOW]
+async fn store_chat_user(
+    db: &DatabaseConnection,
+    user_id: &UserId,
+    stream_session_id: Option<Uuid>,
+) -> anyhow::Result<FirstMessageState> {
+    let seen_at = Utc::now();
+
+    if ChatUserModel::create_if_new(db, user_id.to_string(), stream_session_id, seen_at).await? {
+        return Ok(FirstMessageState {
+            stream: true,
+            ever: true,
+        });
+    }
+
+    let stream = match stream_session_id {
+        Some(stream_session_id) => {
+            ChatUserModel::mark_seen_in_session(db, user_id.as_str(), stream_session_id, seen_at)
+                .await?
+        }
+        None => {
+            let since = seen_at - OFFLINE_FIRST_MESSAGE_WINDOW;
+            ChatUserModel::mark_seen_since(db, user_id.as_str(), since, seen_at).await?
+        }
+    };
+
+    Ok(FirstMessageState {
+        stream,
+        ever: false,
+    })
+}
+
 pub async fn match_chat_event(
     db: &DatabaseConnection,
     event: TwitchEventChatMsg,
 ) -> anyhow::Result<EventMatchingData> {
-    if let Err(err) = store_chat_event(db, &event).await {
+    let stream_session_id = match StreamSessionModel::get_current(db).await {
+        Ok(value) => value.map(|session| session.id),
+        Err(err) => {
+            error!("failed to get current stream session: {err}");
+            None
+        }
+    };
+
+    if let Err(err) = store_chat_event(db, &event, stream_session_id).await {
         error!("failed to log chat message history: {err}");
     }
 
+    let first_message = match store_chat_user(db, &event.user_id, stream_session_id).await {
+        Ok(value) => value,
+        Err(err) => {
+            error!("failed to store chat user: {err}");
+            FirstMessageState::default()
+        }
+    };
+
     let message = event.message.text.clone();
     let mut args: Vec<String> = message
         .split_whitespace()
@@ -410,7 +701,7 @@ pub async fn match_chat_event(
         Some(args.remove(0))
     };
 
-    let events = if let Some(first_arg) = first_arg {
+    let mut events: Vec<EventModel> = if let Some(first_arg) = first_arg {
         // Get the command argument from the first argument
         let command_arg = first_arg.trim().to_lowercase();
 
@@ -427,27 +718,107 @@ pub async fn match_chat_event(
         // Filter events for matching command messages
         events
             .into_iter()
-            .filter(|event| matches!(&event.trigger, EventTrigger::Command { message } if message.trim().to_lowercase().eq(&command_arg)))
+            .filter(|event| matches!(&event.trigger, EventTrigger::Command { message, aliases, .. } if is_command_match(message, aliases, &command_arg)))
             .collect()
     } else {
         Default::default()
     };
 
+    let pattern_events = EventModel::get_by_trigger_type(db, EventTriggerType::ChatPattern).await;
+    let pattern_events = match pattern_events {
+        Ok(value) => value,
+        Err(err) => {
+            error!("failed to load events: {:?}", err);
+            Default::default()
+        }
+    };
+
+    let mut pattern_captures = HashMap::new();
+
+    // Filter events for patterns matching the message
+    for event in pattern_events {
+        let EventTrigger::ChatPattern { pattern } = &event.trigger else {
+            continue;
+        };
+
+        if let Some(captures) = match_chat_pattern(pattern, &message) {
+            pattern_captures.insert(event.id, captures);
+            events.push(event);
+        }
+    }
+
+    if first_message.stream {
+        let first_message_events =
+            EventModel::get_by_trigger_type(db, EventTriggerType::FirstMessage).await;
+        let first_message_events = match first_message_events {
+            Ok(value) => value,
+            Err(err) => {
+                error!("failed to load events: {:?}", err);
+                Default::default()
+            }
+        };
+
+        // Filter events for the first message mode
+        events.extend(first_message_events.into_iter().filter(|event| {
+            matches!(&event.trigger, EventTrigger::FirstMessage { first_time_ever } if !first_time_ever || first_message.ever)
+        }));
+    }
+
     let event_data = EventData {
         input_data: EventInputData::Chat {
             message_id: event.message_id,
             message: event.message.text,
             fragments: event.message.fragments,
             cheer: event.cheer.map(|cheer| cheer.bits),
+            captures: HashMap::new(),
+            args,
         },
         user: Some(TwitchEventUser {
             id: event.user_id,
             name: event.user_name,
             display_name: event.user_display_name,
         }),
+        remaining_uses: None,
+    };
+
+    Ok(EventMatchingData {
+        events,
+        event_data,
+        pattern_captures,
+    })
+}
+
+/// Checks if the `command_arg` matches the command message or any of its aliases
+fn is_command_match(message: &str, aliases: &[String], command_arg: &str) -> bool {
+    std::iter::once(message)
+        .chain(aliases.iter().map(String::as_str))
+        .any(|value| value.trim().to_lowercase().eq(command_arg))
+}
+
+/// Checks if the chat `pattern` matches the provided `message`, provides the
+/// named capture groups of the pattern when matched
+fn match_chat_pattern(pattern: &str, message: &str) -> Option<HashMap<String, String>> {
+    let regex = match compile_pattern(pattern) {
+        Ok(value) => value,
+        Err(err) => {
+            error!("invalid chat pattern {pattern:?}: {err}");
+            return None;
+        }
     };
 
-    Ok(EventMatchingData { events, event_data })
+    let matched = regex.captures(message)?;
+
+    Some(
+        regex
+            .capture_names()
+            .flatten()
+            .filter_map(|name| {
+                matched
+                    .name(name)
+                    .map(|value| (name.to_string(), value.as_str().to_string()))
+            })
+            .collect(),
+    )
 }
 
 pub async fn match_raid_event(
@@ -483,9 +854,10 @@ pub async fn match_raid_event(
             name: event.user_name,
             display_name: event.user_display_name,
         }),
+        remaining_uses: None,
     };
 
-    Ok(EventMatchingData { events, event_data })
+    Ok(EventMatchingData::new(events, event_data))
 }
 
 pub async fn match_ad_break_event(
@@ -507,9 +879,10 @@ pub async fn match_ad_break_event(
             duration_seconds: event.duration_seconds,
         },
         user: None,
+        remaining_uses: None,
     };
 
-    Ok(EventMatchingData { events, event_data })
+    Ok(EventMatchingData::new(events, event_data))
 }
 
 pub async fn match_shoutout_receive_event(
@@ -545,129 +918,1817 @@ pub async fn match_shoutout_receive_event(
             name: event.user_name,
             display_name: event.user_display_name,
         }),
+        remaining_uses: None,
     };
 
-    Ok(EventMatchingData { events, event_data })
+    Ok(EventMatchingData::new(events, event_data))
 }
 
-#[cfg(test)]
-mod test {
-    use super::{
-        match_ad_break_event, match_chat_event, match_cheer_bits_event, match_follow_event,
-        match_gifted_subscription_event, match_raid_event, match_re_subscription_event,
-        match_redeem_event, match_shoutout_receive_event, match_subscription_event,
-    };
-    use crate::{
-        database::{
-            entity::events::{
-                CreateEvent, EventModel, EventOutcome, EventOutcomeSendChat, EventTrigger,
-            },
-            mock_database,
-        },
-        twitch::models::{
-            TwitchEventAdBreakBegin, TwitchEventChatMsg, TwitchEventCheerBits, TwitchEventFollow,
-            TwitchEventGiftSub, TwitchEventRaid, TwitchEventReSub, TwitchEventRedeem,
-            TwitchEventShoutoutReceive, TwitchEventSub,
-        },
-    };
-    use twitch_api::{
-        eventsub::channel::{
-            channel_points_custom_reward_redemption::Reward,
-            subscription::message::SubscriptionMessage,
-        },
-        types::{DisplayName, RedemptionId, SubscriptionTier, UserId, UserName},
+pub async fn match_hype_train_begin_event(
+    db: &DatabaseConnection,
+    event: TwitchEventHypeTrain,
+) -> anyhow::Result<EventMatchingData> {
+    let events = EventModel::get_by_trigger_type(db, EventTriggerType::HypeTrainBegin).await;
+    let events = match events {
+        Ok(value) => value,
+        Err(err) => {
+            error!("failed to load events: {:?}", err);
+            Default::default()
+        }
     };
 
-    /// Tests that a reward redemption event can successfully match using "match_redeem_event"
-    /// when the ID is the same as the stored database model
-    #[tokio::test]
-    async fn test_match_redeem_event() {
-        let db = mock_database().await;
+    Ok(EventMatchingData::new(
+        events,
+        create_hype_train_event_data(event),
+    ))
+}
 
-        let expected_event = EventModel::create(
-            &db,
-            CreateEvent {
-                enabled: true,
-                name: "Test Event".to_string(),
-                trigger: EventTrigger::Redeem {
-                    reward_id: "test-reward".to_string(),
-                },
-                cooldown: Default::default(),
-                outcome: EventOutcome::SendChatMessage(EventOutcomeSendChat {
-                    template: "test".to_string(),
-                }),
-                outcome_delay: Default::default(),
-                require_role: Default::default(),
-            },
-        )
-        .await
-        .unwrap();
+pub async fn match_hype_train_progress_event(
+    db: &DatabaseConnection,
+    event: TwitchEventHypeTrain,
+) -> anyhow::Result<EventMatchingData> {
+    let events = EventModel::get_by_trigger_type(db, EventTriggerType::HypeTrainProgress).await;
+    let events = match events {
+        Ok(value) => value,
+        Err(err) => {
+            error!("failed to load events: {:?}", err);
+            Default::default()
+        }
+    };
 
-        let reward = serde_json::json!({
-            "cost": 0,
-            "id": "test-reward",
-            "prompt": "",
-            "title": "",
-        });
+    let level = event.level;
 
-        let reward: Reward = serde_json::from_value(reward).unwrap();
+    // Filter events for the minimum level
+    let events = events
+        .into_iter()
+        .filter(|event| {
+            matches!(&event.trigger, EventTrigger::HypeTrainProgress { min_level } if level >= *min_level as i64)
+        })
+        .collect();
 
-        let found_event = match_redeem_event(
-            &db,
-            TwitchEventRedeem {
-                id: RedemptionId::from_static("mock-redemption-id"),
-                reward,
-                user_id: UserId::from_static("mock-user-id"),
-                user_name: UserName::from_static("mockuser"),
-                user_display_name: DisplayName::from_static("Mock User"),
-                user_input: "test".to_string(),
-            },
-        )
-        .await
-        .expect("missing expected redeem event");
+    Ok(EventMatchingData::new(
+        events,
+        create_hype_train_event_data(event),
+    ))
+}
 
-        let event = found_event.events.first().expect("missing matching event");
+pub async fn match_hype_train_end_event(
+    db: &DatabaseConnection,
+    event: TwitchEventHypeTrainEnd,
+) -> anyhow::Result<EventMatchingData> {
+    let events = EventModel::get_by_trigger_type(db, EventTriggerType::HypeTrainEnd).await;
+    let events = match events {
+        Ok(value) => value,
+        Err(err) => {
+            error!("failed to load events: {:?}", err);
+            Default::default()
+        }
+    };
 
-        // Expect found event to match created
-        assert_eq!(event.id, expected_event.id);
-    }
+    let level = event.level;
 
-    /// Tests that no events will be found if the required if "match_redeem_event" has no
-    /// matching database events
-    #[tokio::test]
-    async fn test_match_redeem_event_non_existent() {
-        let db = mock_database().await;
+    // Filter events for the minimum level
+    let events = events
+        .into_iter()
+        .filter(|event| {
+            matches!(&event.trigger, EventTrigger::HypeTrainEnd { min_level } if level >= *min_level as i64)
+        })
+        .collect();
 
-        let reward = serde_json::json!({
-            "cost": 0,
-            "id": "test-reward",
-            "prompt": "",
-            "title": "",
-        });
+    let event_data = EventData {
+        input_data: EventInputData::HypeTrain {
+            level: event.level,
+            total: event.total,
+            progress: None,
+            goal: None,
+        },
+        user: None,
+        remaining_uses: None,
+    };
 
-        let reward: Reward = serde_json::from_value(reward).unwrap();
+    Ok(EventMatchingData::new(events, event_data))
+}
 
-        let found_event = match_redeem_event(
-            &db,
-            TwitchEventRedeem {
-                id: RedemptionId::from_static("mock-redemption-id"),
+/// Starts a new stream session for the stream, any previous sessions that were
+/// not ended (i.e the app was closed while live) are ended
+///
+/// Does nothing if the current session is already for the stream
+async fn start_stream_session(
+    db: &DatabaseConnection,
+    stream_id: &str,
+    started_at: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    let current = StreamSessionModel::get_current(db).await?;
+    if current.is_some_and(|session| session.stream_id == stream_id) {
+        return Ok(());
+    }
+
+    StreamSessionModel::end_current(db, started_at).await?;
+    StreamSessionModel::create(
+        db,
+        CreateStreamSession {
+            stream_id: stream_id.to_string(),
+            started_at,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn parse_stream_started_at(started_at: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(started_at)
+        .map(|value| value.to_utc())
+        .unwrap_or_else(|_| Utc::now())
+}
+
+async fn store_stream_online_event(
+    db: &DatabaseConnection,
+    event: &TwitchEventStreamOnline,
+) -> anyhow::Result<()> {
+    let started_at = parse_stream_started_at(event.started_at.as_str());
+    start_stream_session(db, &event.stream_id, started_at).await
+}
+
+/// Updates the stream sessions to match the `live` stream (Stream ID and start time),
+/// sessions left open while the stream is offline are ended
+async fn reconcile_stream_session(
+    db: &DatabaseConnection,
+    live: Option<(&str, DateTime<Utc>)>,
+) -> anyhow::Result<()> {
+    match live {
+        Some((stream_id, started_at)) => start_stream_session(db, stream_id, started_at).await,
+        None => {
+            StreamSessionModel::end_current(db, Utc::now()).await?;
+            Ok(())
+        }
+    }
+}
+
+/// Syncs the stream sessions with the current stream state from Twitch
+///
+/// Stream online/offline events are missed while the app is closed, this
+/// is run after authenticating to close stale sessions and start a session
+/// for a stream that went live in the meantime
+pub async fn sync_stream_session(db: &DatabaseConnection, twitch: &Twitch) {
+    let stream = match twitch.get_live_stream().await {
+        Ok(value) => value,
+        Err(err) => {
+            error!("failed to get live stream: {err:?}");
+            return;
+        }
+    };
+
+    let live = stream.as_ref().map(|stream| {
+        (
+            stream.id.as_str(),
+            parse_stream_started_at(stream.started_at.as_str()),
+        )
+    });
+
+    if let Err(err) = reconcile_stream_session(db, live).await {
+        error!("failed to sync stream session: {err:?}");
+    }
+}
+
+pub async fn match_stream_online_event(
+    db: &DatabaseConnection,
+    event: TwitchEventStreamOnline,
+) -> anyhow::Result<EventMatchingData> {
+    if let Err(err) = store_stream_online_event(db, &event).await {
+        error!("failed to store stream session: {err}");
+    }
+
+    let events = EventModel::get_by_trigger_type(db, EventTriggerType::StreamOnline).await;
+    let events = match events {
+        Ok(value) => value,
+        Err(err) => {
+            error!("failed to load events: {:?}", err);
+            Default::default()
+        }
+    };
+
+    let event_data = EventData {
+        input_data: EventInputData::None,
+        user: None,
+        remaining_uses: None,
+    };
+
+    Ok(EventMatchingData::new(events, event_data))
+}
+
+pub async fn match_stream_offline_event(
+    db: &DatabaseConnection,
+) -> anyhow::Result<EventMatchingData> {
+    if let Err(err) = StreamSessionModel::end_current(db, Utc::now()).await {
+        error!("failed to end stream session: {err}");
+    }
+
+    let events = EventModel::get_by_trigger_type(db, EventTriggerType::StreamOffline).await;
+    let events = match events {
+        Ok(value) => value,
+        Err(err) => {
+            error!("failed to load events: {:?}", err);
+            Default::default()
+        }
+    };
+
+    let event_data = EventData {
+        input_data: EventInputData::None,
+        user: None,
+        remaining_uses: None,
+    };
+
+    Ok(EventMatchingData::new(events, event_data))
+}
+
+/// Matches poll events for the provided poll `trigger_type`
+pub async fn match_poll_event(
+    db: &DatabaseConnection,
+    trigger_type: EventTriggerType,
+    event: TwitchEventPoll,
+) -> anyhow::Result<EventMatchingData> {
+    let events = EventModel::get_by_trigger_type(db, trigger_type).await;
+    let events = match events {
+        Ok(value) => value,
+        Err(err) => {
+            error!("failed to load events: {:?}", err);
+            Default::default()
+        }
+    };
+
+    let total_votes: i64 = event.choices.iter().map(|choice| choice.votes).sum();
+
+    // Choice with the most votes, the first choice wins ties
+    let winning_choice = event
+        .choices
+        .iter()
+        .rev()
+        .max_by_key(|choice| choice.votes)
+        .filter(|_| total_votes > 0)
+        .map(|choice| choice.title.clone());
+
+    // Filter events for the required winning choice
+    let events = events
+        .into_iter()
+        .filter(|event| match &event.trigger {
+            EventTrigger::PollEnd {
+                winning_choice: Some(required),
+            } => winning_choice
+                .as_ref()
+                .is_some_and(|value| value.eq_ignore_ascii_case(required.trim())),
+            _ => true,
+        })
+        .collect();
+
+    let event_data = EventData {
+        input_data: EventInputData::Poll {
+            title: event.title,
+            choices: event.choices,
+            winning_choice,
+            total_votes,
+        },
+        user: None,
+        remaining_uses: None,
+    };
+
+    Ok(EventMatchingData::new(events, event_data))
+}
+
+/// Matches prediction events for the provided prediction `trigger_type`
+pub async fn match_prediction_event(
+    db: &DatabaseConnection,
+    trigger_type: EventTriggerType,
+    event: TwitchEventPrediction,
+) -> anyhow::Result<EventMatchingData> {
+    let events = EventModel::get_by_trigger_type(db, trigger_type).await;
+    let events = match events {
+        Ok(value) => value,
+        Err(err) => {
+            error!("failed to load events: {:?}", err);
+            Default::default()
+        }
+    };
+
+    let total_points: i64 = event
+        .outcomes
+        .iter()
+        .map(|outcome| outcome.channel_points)
+        .sum();
+
+    let winning_outcome = event.winning_outcome_id.as_ref().and_then(|winning_id| {
+        event
+            .outcomes
+            .iter()
+            .find(|outcome| outcome.id.eq(winning_id))
+            .map(|outcome| outcome.title.clone())
+    });
+
+    // Filter events for the required winning outcome
+    let events = events
+        .into_iter()
+        .filter(|event| match &event.trigger {
+            EventTrigger::PredictionEnd {
+                winning_outcome: Some(required),
+            } => winning_outcome
+                .as_ref()
+                .is_some_and(|value| value.eq_ignore_ascii_case(required.trim())),
+            _ => true,
+        })
+        .collect();
+
+    let event_data = EventData {
+        input_data: EventInputData::Prediction {
+            title: event.title,
+            outcomes: event.outcomes,
+            winning_outcome,
+            total_points,
+        },
+        user: None,
+        remaining_uses: None,
+    };
+
+    Ok(EventMatchingData::new(events, event_data))
+}
+
+/// Finds the webhook event identified by `key`, the key can either be
+/// the ID of the event or the slug of the webhook trigger
+pub async fn match_webhook_event(
+    db: &DatabaseConnection,
+    key: &str,
+) -> anyhow::Result<Option<EventModel>> {
+    let events = EventModel::get_by_trigger_type(db, EventTriggerType::Webhook)
+        .await
+        .context("failed to load events")?;
+
+    let event_id = Uuid::from_str(key).ok();
+
+    let event = events.into_iter().find(|event| {
+        if event_id.is_some_and(|event_id| event.id == event_id) {
+            return true;
+        }
+
+        matches!(
+            &event.trigger,
+            EventTrigger::Webhook { slug: Some(slug), .. } if slug.eq_ignore_ascii_case(key)
+        )
+    });
+
+    Ok(event)
+}
+
+/// Checks that the `provided` webhook secret matches the `expected` secret,
+/// compares the full length of both secrets to avoid leaking timing
+pub fn is_webhook_secret_valid(expected: &str, provided: &str) -> bool {
+    // Empty secrets are never accepted
+    if expected.is_empty() {
+        return false;
+    }
+
+    let expected = expected.as_bytes();
+    let provided = provided.as_bytes();
+
+    let mut diff = expected.len() ^ provided.len();
+    for (index, value) in expected.iter().enumerate() {
+        let other = provided.get(index).copied().unwrap_or_default();
+        diff |= (value ^ other) as usize;
+    }
+
+    diff == 0
+}
+
+/// Creates the event data for a hype train begin or progress event, the
+/// user is the last contributor to the hype train
+fn create_hype_train_event_data(event: TwitchEventHypeTrain) -> EventData {
+    EventData {
+        input_data: EventInputData::HypeTrain {
+            level: event.level,
+            total: event.total,
+            progress: Some(event.progress),
+            goal: Some(event.goal),
+        },
+        user: Some(TwitchEventUser {
+            id: event.user_id,
+            name: event.user_name,
+            display_name: event.user_display_name,
+        }),
+        remaining_uses: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        is_webhook_secret_valid, match_ad_break_event, match_charity_donation_event,
+        match_chat_event, match_cheer_bits_event, match_follow_event,
+        match_gifted_subscription_event, match_hype_train_begin_event, match_hype_train_end_event,
+        match_hype_train_progress_event, match_poll_event, match_prediction_event,
+        match_raid_event, match_re_subscription_event, match_redeem_event,
+        match_shoutout_receive_event, match_stream_offline_event, match_stream_online_event,
+        match_subscription_event, match_webhook_event, reconcile_stream_session, store_chat_user,
+        EventInputData,
+    };
+    use crate::{
+        database::{
+            entity::{
+                event_groups::{CreateEventGroup, EventGroupModel, UpdateEventGroup},
+                events::{
+                    CreateEvent, EventModel, EventOutcome, EventOutcomeSendChat, EventTrigger,
+                    EventTriggerType,
+                },
+                stream_sessions::StreamSessionModel,
+            },
+            mock_database,
+        },
+        twitch::models::{
+            TwitchEventAdBreakBegin, TwitchEventCharityDonation, TwitchEventChatMsg,
+            TwitchEventCheerBits, TwitchEventFollow, TwitchEventGiftSub, TwitchEventHypeTrain,
+            TwitchEventHypeTrainEnd, TwitchEventPoll, TwitchEventPrediction, TwitchEventRaid,
+            TwitchEventReSub, TwitchEventRedeem, TwitchEventShoutoutReceive,
+            TwitchEventStreamOnline, TwitchEventSub, TwitchPollChoice, TwitchPredictionOutcome,
+        },
+    };
+    use chrono::Utc;
+    use twitch_api::{
+        eventsub::channel::{
+            channel_points_custom_reward_redemption::Reward,
+            subscription::message::SubscriptionMessage,
+        },
+        types::{DisplayName, RedemptionId, SubscriptionTier, Timestamp, UserId, UserName},
+    };
+    use uuid::Uuid;
+
+    /// Tests that a reward redemption event can successfully match using "match_redeem_event"
+    /// when the ID is the same as the stored database model
+    #[tokio::test]
+    async fn test_match_redeem_event() {
+        let db = mock_database().await;
+
+        let expected_event = EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::Redeem {
+                    reward_id: "test-reward".to_string(),
+                    fulfil_redemption: false,
+                    refund_redemption: false,
+                },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let reward = serde_json::json!({
+            "cost": 0,
+            "id": "test-reward",
+            "prompt": "",
+            "title": "",
+        });
+
+        let reward: Reward = serde_json::from_value(reward).unwrap();
+
+        let found_event = match_redeem_event(
+            &db,
+            TwitchEventRedeem {
+                id: RedemptionId::from_static("mock-redemption-id"),
+                reward,
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+                user_input: "test".to_string(),
+            },
+        )
+        .await
+        .expect("missing expected redeem event");
+
+        let event = found_event.events.first().expect("missing matching event");
+
+        // Expect found event to match created
+        assert_eq!(event.id, expected_event.id);
+    }
+
+    /// Tests that no events will be found if the required if "match_redeem_event" has no
+    /// matching database events
+    #[tokio::test]
+    async fn test_match_redeem_event_non_existent() {
+        let db = mock_database().await;
+
+        let reward = serde_json::json!({
+            "cost": 0,
+            "id": "test-reward",
+            "prompt": "",
+            "title": "",
+        });
+
+        let reward: Reward = serde_json::from_value(reward).unwrap();
+
+        let found_event = match_redeem_event(
+            &db,
+            TwitchEventRedeem {
+                id: RedemptionId::from_static("mock-redemption-id"),
                 reward,
                 user_id: UserId::from_static("mock-user-id"),
                 user_name: UserName::from_static("mockuser"),
                 user_display_name: DisplayName::from_static("Mock User"),
-                user_input: "test".to_string(),
+                user_input: "test".to_string(),
+            },
+        )
+        .await
+        .expect("missing expected redeem event");
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+    }
+
+    /// Tests that a cheer bits event can successfully match using "match_cheer_bits_event"
+    /// when the ID is the same as the stored database model
+    #[tokio::test]
+    async fn test_match_cheer_bits_event() {
+        let db = mock_database().await;
+
+        let expected_event = EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::Bits { min_bits: 0 },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let found_event = match_cheer_bits_event(
+            &db,
+            TwitchEventCheerBits {
+                bits: 100,
+                anonymous: false,
+                user_id: Some(UserId::from_static("mock-user-id")),
+                user_name: Some(UserName::from_static("mockuser")),
+                user_display_name: Some(DisplayName::from_static("Mock User")),
+                message: "test".to_string(),
+            },
+        )
+        .await
+        .expect("missing expected redeem event");
+
+        let event = found_event.events.first().expect("missing matching event");
+
+        // Expect found event to match created
+        assert_eq!(event.id, expected_event.id);
+    }
+
+    /// Tests that a cheer bits event will not match using "match_cheer_bits_event"
+    /// when the amount of bits is not enough
+    #[tokio::test]
+    async fn test_match_cheer_bits_event_not_enough_bits() {
+        let db = mock_database().await;
+
+        EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::Bits { min_bits: 500 },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let found_event = match_cheer_bits_event(
+            &db,
+            TwitchEventCheerBits {
+                bits: 100,
+                anonymous: false,
+                user_id: Some(UserId::from_static("mock-user-id")),
+                user_name: Some(UserName::from_static("mockuser")),
+                user_display_name: Some(DisplayName::from_static("Mock User")),
+                message: "test".to_string(),
+            },
+        )
+        .await
+        .expect("missing expected redeem event");
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+    }
+
+    /// Tests that no events will be found if the required if "match_cheer_bits_event" has no
+    /// matching database events
+    #[tokio::test]
+    async fn test_match_cheer_bits_event_non_existent() {
+        let db = mock_database().await;
+
+        let found_event = match_cheer_bits_event(
+            &db,
+            TwitchEventCheerBits {
+                bits: 100,
+                anonymous: false,
+                user_id: Some(UserId::from_static("mock-user-id")),
+                user_name: Some(UserName::from_static("mockuser")),
+                user_display_name: Some(DisplayName::from_static("Mock User")),
+                message: "test".to_string(),
+            },
+        )
+        .await
+        .expect("missing expected redeem event");
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+    }
+
+    fn mock_charity_donation(amount: i64) -> TwitchEventCharityDonation {
+        TwitchEventCharityDonation {
+            user_id: UserId::from_static("mock-user-id"),
+            user_name: UserName::from_static("mockuser"),
+            user_display_name: DisplayName::from_static("Mock User"),
+            charity_name: "Mock Charity".to_string(),
+            amount,
+            decimal_places: 2,
+            currency: "USD".to_string(),
+        }
+    }
+
+    /// Tests that a charity donation event can successfully match using "match_charity_donation_event"
+    /// and that the amount is converted from the currency minor unit
+    #[tokio::test]
+    async fn test_match_charity_donation_event() {
+        let db = mock_database().await;
+
+        let expected_event = EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::CharityDonation { min_amount: 5 },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let found_event = match_charity_donation_event(&db, mock_charity_donation(550))
+            .await
+            .unwrap();
+
+        let event = found_event.events.first().expect("missing matching event");
+
+        // Expect found event to match created
+        assert_eq!(event.id, expected_event.id);
+
+        // Expect the amount in dollars and the whole amount as the numeric value
+        assert!(matches!(
+            found_event.event_data.input_data,
+            EventInputData::CharityDonation { amount, .. } if amount == 5.5
+        ));
+        assert_eq!(found_event.event_data.input_data.numeric_value(), Some(5));
+    }
+
+    #[tokio::test]
+    async fn test_match_charity_donation_event_not_enough_amount() {
+        let db = mock_database().await;
+
+        EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::CharityDonation { min_amount: 5 },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let found_event = match_charity_donation_event(&db, mock_charity_donation(499))
+            .await
+            .unwrap();
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+    }
+
+    #[tokio::test]
+    async fn test_match_charity_donation_event_non_existent() {
+        let db = mock_database().await;
+
+        let found_event = match_charity_donation_event(&db, mock_charity_donation(550))
+            .await
+            .unwrap();
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+    }
+
+    #[tokio::test]
+    async fn test_match_follow_event() {
+        let db = mock_database().await;
+
+        let expected_event = EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::Follow,
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let found_event = match_follow_event(
+            &db,
+            TwitchEventFollow {
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+            },
+        )
+        .await
+        .unwrap();
+
+        let event = found_event.events.first().expect("missing matching event");
+
+        // Expect found event to match created
+        assert_eq!(event.id, expected_event.id);
+    }
+
+    #[tokio::test]
+    async fn test_match_follow_event_non_existent() {
+        let db = mock_database().await;
+
+        let found_event = match_follow_event(
+            &db,
+            TwitchEventFollow {
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+    }
+
+    #[tokio::test]
+    async fn test_match_subscription_event() {
+        let db = mock_database().await;
+
+        let expected_event = EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::Subscription {
+                    tiers: Vec::new(),
+                    is_gift: None,
+                },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let found_event = match_subscription_event(
+            &db,
+            TwitchEventSub {
+                is_gift: false,
+                tier: SubscriptionTier::Tier1,
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+            },
+        )
+        .await
+        .unwrap();
+
+        let event = found_event.events.first().expect("missing matching event");
+
+        // Expect found event to match created
+        assert_eq!(event.id, expected_event.id);
+    }
+
+    #[tokio::test]
+    async fn test_match_subscription_event_filtered() {
+        let db = mock_database().await;
+
+        EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::Subscription {
+                    tiers: vec![SubscriptionTier::Tier2, SubscriptionTier::Tier3],
+                    is_gift: Some(false),
+                },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        // Tier is not allowed
+        let found_event = match_subscription_event(
+            &db,
+            TwitchEventSub {
+                is_gift: false,
+                tier: SubscriptionTier::Tier1,
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+
+        // Subscription was a gift
+        let found_event = match_subscription_event(
+            &db,
+            TwitchEventSub {
+                is_gift: true,
+                tier: SubscriptionTier::Tier2,
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+
+        // Allowed tier and not a gift
+        let found_event = match_subscription_event(
+            &db,
+            TwitchEventSub {
+                is_gift: false,
+                tier: SubscriptionTier::Tier3,
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(found_event.events.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_match_subscription_event_non_existent() {
+        let db = mock_database().await;
+
+        let found_event = match_subscription_event(
+            &db,
+            TwitchEventSub {
+                is_gift: false,
+                tier: SubscriptionTier::Tier1,
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+    }
+
+    #[tokio::test]
+    async fn test_match_gifted_subscription_event() {
+        let db = mock_database().await;
+
+        let expected_event = EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::GiftedSubscription { min_total: 0 },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let found_event = match_gifted_subscription_event(
+            &db,
+            TwitchEventGiftSub {
+                anonymous: true,
+                total: 1,
+                cumulative_total: None,
+                tier: SubscriptionTier::Tier1,
+                user_id: Some(UserId::from_static("mock-user-id")),
+                user_name: Some(UserName::from_static("mockuser")),
+                user_display_name: Some(DisplayName::from_static("Mock User")),
+            },
+        )
+        .await
+        .unwrap();
+
+        let event = found_event.events.first().expect("missing matching event");
+
+        // Expect found event to match created
+        assert_eq!(event.id, expected_event.id);
+    }
+
+    #[tokio::test]
+    async fn test_match_gifted_subscription_event_not_enough_total() {
+        let db = mock_database().await;
+
+        EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::GiftedSubscription { min_total: 5 },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let found_event = match_gifted_subscription_event(
+            &db,
+            TwitchEventGiftSub {
+                anonymous: true,
+                total: 4,
+                cumulative_total: None,
+                tier: SubscriptionTier::Tier1,
+                user_id: Some(UserId::from_static("mock-user-id")),
+                user_name: Some(UserName::from_static("mockuser")),
+                user_display_name: Some(DisplayName::from_static("Mock User")),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+    }
+
+    #[tokio::test]
+    async fn test_match_gifted_subscription_event_non_existent() {
+        let db = mock_database().await;
+
+        let found_event = match_gifted_subscription_event(
+            &db,
+            TwitchEventGiftSub {
+                anonymous: true,
+                total: 1,
+                cumulative_total: None,
+                tier: SubscriptionTier::Tier1,
+                user_id: Some(UserId::from_static("mock-user-id")),
+                user_name: Some(UserName::from_static("mockuser")),
+                user_display_name: Some(DisplayName::from_static("Mock User")),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+    }
+
+    #[tokio::test]
+    async fn test_match_re_subscription_event() {
+        let db = mock_database().await;
+
+        let expected_event = EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::ReSubscription {
+                    min_cumulative_months: 0,
+                    min_streak_months: 0,
+                },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let message = serde_json::json!({
+            "text": "",
+            "emotes": []
+        });
+        let message: SubscriptionMessage = serde_json::from_value(message).unwrap();
+
+        let found_event = match_re_subscription_event(
+            &db,
+            TwitchEventReSub {
+                cumulative_months: 1,
+                duration_months: 1,
+                message,
+                streak_months: Some(1),
+                tier: SubscriptionTier::Tier1,
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+            },
+        )
+        .await
+        .unwrap();
+
+        let event = found_event.events.first().expect("missing matching event");
+
+        // Expect found event to match created
+        assert_eq!(event.id, expected_event.id);
+    }
+
+    #[tokio::test]
+    async fn test_match_re_subscription_event_not_enough_months() {
+        let db = mock_database().await;
+
+        EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::ReSubscription {
+                    min_cumulative_months: 12,
+                    min_streak_months: 6,
+                },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let message = serde_json::json!({
+            "text": "",
+            "emotes": []
+        });
+        let message: SubscriptionMessage = serde_json::from_value(message).unwrap();
+
+        // Enough total months but the streak is not shared
+        let found_event = match_re_subscription_event(
+            &db,
+            TwitchEventReSub {
+                cumulative_months: 24,
+                duration_months: 1,
+                message,
+                streak_months: None,
+                tier: SubscriptionTier::Tier1,
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+    }
+
+    /// Subscription events should not be triggered by resubscriptions
+    #[tokio::test]
+    async fn test_match_re_subscription_event_subscription_trigger() {
+        let db = mock_database().await;
+
+        EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::Subscription {
+                    tiers: Vec::new(),
+                    is_gift: None,
+                },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let message = serde_json::json!({
+            "text": "",
+            "emotes": []
+        });
+        let message: SubscriptionMessage = serde_json::from_value(message).unwrap();
+
+        let found_event = match_re_subscription_event(
+            &db,
+            TwitchEventReSub {
+                cumulative_months: 1,
+                duration_months: 1,
+                message,
+                streak_months: Some(1),
+                tier: SubscriptionTier::Tier1,
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+    }
+
+    #[tokio::test]
+    async fn test_match_re_subscription_event_non_existent() {
+        let db = mock_database().await;
+
+        let message = serde_json::json!({
+            "text": "",
+            "emotes": []
+        });
+        let message: SubscriptionMessage = serde_json::from_value(message).unwrap();
+
+        let found_event = match_re_subscription_event(
+            &db,
+            TwitchEventReSub {
+                cumulative_months: 1,
+                duration_months: 1,
+                message,
+                streak_months: Some(1),
+                tier: SubscriptionTier::Tier1,
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+    }
+
+    #[tokio::test]
+    async fn test_match_chat_event() {
+        let db = mock_database().await;
+
+        let expected_event = EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::Command {
+                    message: "!test".to_string(),
+                    aliases: Vec::new(),
+                    arguments: Vec::new(),
+                    reply_usage: false,
+                },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let message = serde_json::json!({
+            "text": "!test",
+            "fragments": []
+        });
+        let message: twitch_api::eventsub::channel::chat::Message =
+            serde_json::from_value(message).unwrap();
+
+        let found_event = match_chat_event(
+            &db,
+            TwitchEventChatMsg {
+                message_id: "mock-message".into(),
+                message,
+                cheer: None,
+
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+            },
+        )
+        .await
+        .unwrap();
+
+        let event = found_event.events.first().expect("missing matching event");
+
+        // Expect found event to match created
+        assert_eq!(event.id, expected_event.id);
+    }
+
+    /// Tests that a command event can be matched using one of its aliases and
+    /// that the remaining arguments are provided as input data
+    #[tokio::test]
+    async fn test_match_chat_event_alias() {
+        let db = mock_database().await;
+
+        let expected_event = EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::Command {
+                    message: "!test".to_string(),
+                    aliases: vec!["!alias".to_string()],
+                    arguments: Vec::new(),
+                    reply_usage: false,
+                },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let message = serde_json::json!({
+            "text": "!ALIAS 5 @mockuser",
+            "fragments": []
+        });
+        let message: twitch_api::eventsub::channel::chat::Message =
+            serde_json::from_value(message).unwrap();
+
+        let found_event = match_chat_event(
+            &db,
+            TwitchEventChatMsg {
+                message_id: "mock-message".into(),
+                message,
+                cheer: None,
+
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+            },
+        )
+        .await
+        .unwrap();
+
+        let event = found_event.events.first().expect("missing matching event");
+
+        // Expect found event to match created
+        assert_eq!(event.id, expected_event.id);
+
+        // Expect the arguments to be present
+        let args = match found_event.event_data.input_data {
+            EventInputData::Chat { args, .. } => args,
+            _ => panic!("unexpected input data"),
+        };
+        assert_eq!(args, vec!["5".to_string(), "@mockuser".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_match_chat_event_not_matching() {
+        let db = mock_database().await;
+
+        EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::Command {
+                    message: "!test".to_string(),
+                    aliases: Vec::new(),
+                    arguments: Vec::new(),
+                    reply_usage: false,
+                },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let message = serde_json::json!({
+            "text": "",
+            "fragments": []
+        });
+        let message: twitch_api::eventsub::channel::chat::Message =
+            serde_json::from_value(message).unwrap();
+
+        let found_event = match_chat_event(
+            &db,
+            TwitchEventChatMsg {
+                message_id: "mock-message".into(),
+                message,
+                cheer: None,
+
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+    }
+
+    #[tokio::test]
+    async fn test_match_chat_event_non_existent() {
+        let db = mock_database().await;
+
+        let message = serde_json::json!({
+            "text": "",
+            "fragments": []
+        });
+        let message: twitch_api::eventsub::channel::chat::Message =
+            serde_json::from_value(message).unwrap();
+
+        let found_event = match_chat_event(
+            &db,
+            TwitchEventChatMsg {
+                message_id: "mock-message".into(),
+                message,
+                cheer: None,
+
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+    }
+
+    /// Tests that a chat pattern event matches anywhere in the message and provides
+    /// the named capture groups as input data
+    #[tokio::test]
+    async fn test_match_chat_pattern_event() {
+        let db = mock_database().await;
+
+        let expected_event = EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::ChatPattern {
+                    pattern: r"bonk me (?<amount>\d+) times".to_string(),
+                },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let message = serde_json::json!({
+            "text": "please bonk me 5 times",
+            "fragments": []
+        });
+        let message: twitch_api::eventsub::channel::chat::Message =
+            serde_json::from_value(message).unwrap();
+
+        let found_event = match_chat_event(
+            &db,
+            TwitchEventChatMsg {
+                message_id: "mock-message".into(),
+                message,
+                cheer: None,
+
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+            },
+        )
+        .await
+        .unwrap();
+
+        let event = found_event.events.first().expect("missing matching event");
+
+        // Expect found event to match created
+        assert_eq!(event.id, expected_event.id);
+
+        // Expect the named capture to be present
+        let captures = match found_event.event_data_for(event).input_data {
+            EventInputData::Chat { captures, .. } => captures,
+            _ => panic!("unexpected input data"),
+        };
+        assert_eq!(captures.get("amount").map(String::as_str), Some("5"));
+    }
+
+    /// Tests that captures are only given to the chat pattern event
+    /// whose pattern produced them
+    #[tokio::test]
+    async fn test_match_chat_pattern_event_captures() {
+        let db = mock_database().await;
+
+        let create = |trigger: EventTrigger| CreateEvent {
+            enabled: true,
+            name: "Test Event".to_string(),
+            trigger,
+            cooldown: Default::default(),
+            outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                template: "test".to_string(),
+                ..Default::default()
+            })
+            .into(),
+            outcome_delay: Default::default(),
+            require_role: Default::default(),
+            role_requirements: Default::default(),
+            conditions: Default::default(),
+            feedback: Default::default(),
+            usage_limits: Default::default(),
+        };
+
+        let bonk_event = EventModel::create(
+            &db,
+            create(EventTrigger::ChatPattern {
+                pattern: r"bonk me (?<amount>\d+) times".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+        let number_event = EventModel::create(
+            &db,
+            create(EventTrigger::ChatPattern {
+                pattern: r"(?<amount>\d+)".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+        let command_event = EventModel::create(
+            &db,
+            create(EventTrigger::Command {
+                message: "!bonk".to_string(),
+                aliases: Vec::new(),
+                arguments: Vec::new(),
+                reply_usage: false,
+            }),
+        )
+        .await
+        .unwrap();
+
+        let message = serde_json::json!({
+            "text": "!bonk 3 please bonk me 5 times",
+            "fragments": []
+        });
+        let message: twitch_api::eventsub::channel::chat::Message =
+            serde_json::from_value(message).unwrap();
+
+        let found_event = match_chat_event(
+            &db,
+            TwitchEventChatMsg {
+                message_id: "mock-message".into(),
+                message,
+                cheer: None,
+
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(found_event.events.len(), 3);
+
+        let amount_capture = |event: &EventModel| match found_event.event_data_for(event).input_data
+        {
+            EventInputData::Chat { captures, .. } => captures.get("amount").cloned(),
+            _ => panic!("unexpected input data"),
+        };
+
+        assert_eq!(amount_capture(&bonk_event).as_deref(), Some("5"));
+        assert_eq!(amount_capture(&number_event).as_deref(), Some("3"));
+        assert_eq!(amount_capture(&command_event), None);
+    }
+
+    /// Tests that a chat pattern event will not match a message that doesn't
+    /// match the pattern
+    #[tokio::test]
+    async fn test_match_chat_pattern_event_not_matching() {
+        let db = mock_database().await;
+
+        EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::ChatPattern {
+                    pattern: r"bonk me (?<amount>\d+) times".to_string(),
+                },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let message = serde_json::json!({
+            "text": "please bonk me",
+            "fragments": []
+        });
+        let message: twitch_api::eventsub::channel::chat::Message =
+            serde_json::from_value(message).unwrap();
+
+        let found_event = match_chat_event(
+            &db,
+            TwitchEventChatMsg {
+                message_id: "mock-message".into(),
+                message,
+                cheer: None,
+
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+    }
+
+    #[tokio::test]
+    async fn test_match_raid_event() {
+        let db = mock_database().await;
+
+        let expected_event = EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::Raid { min_raiders: 1 },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let found_event = match_raid_event(
+            &db,
+            TwitchEventRaid {
+                viewers: 1,
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+            },
+        )
+        .await
+        .unwrap();
+
+        let event = found_event.events.first().expect("missing matching event");
+
+        // Expect found event to match created
+        assert_eq!(event.id, expected_event.id);
+    }
+
+    #[tokio::test]
+    async fn test_match_raid_event_not_enough_raiders() {
+        let db = mock_database().await;
+
+        EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::Raid { min_raiders: 5 },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let found_event = match_raid_event(
+            &db,
+            TwitchEventRaid {
+                viewers: 1,
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+    }
+
+    #[tokio::test]
+    async fn test_match_raid_event_non_existent() {
+        let db = mock_database().await;
+
+        let found_event = match_raid_event(
+            &db,
+            TwitchEventRaid {
+                viewers: 1,
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
             },
         )
         .await
-        .expect("missing expected redeem event");
+        .unwrap();
 
         assert!(found_event.events.is_empty(), "should not match any events");
     }
 
-    /// Tests that a cheer bits event can successfully match using "match_cheer_bits_event"
-    /// when the ID is the same as the stored database model
     #[tokio::test]
-    async fn test_match_cheer_bits_event() {
+    async fn test_match_ad_break_event() {
         let db = mock_database().await;
 
         let expected_event = EventModel::create(
@@ -675,31 +2736,32 @@ mod test {
             CreateEvent {
                 enabled: true,
                 name: "Test Event".to_string(),
-                trigger: EventTrigger::Bits { min_bits: 0 },
+                trigger: EventTrigger::AdBreakBegin,
                 cooldown: Default::default(),
-                outcome: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
                     template: "test".to_string(),
-                }),
+                    ..Default::default()
+                })
+                .into(),
                 outcome_delay: Default::default(),
                 require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
             },
         )
         .await
         .unwrap();
 
-        let found_event = match_cheer_bits_event(
+        let found_event = match_ad_break_event(
             &db,
-            TwitchEventCheerBits {
-                bits: 100,
-                anonymous: false,
-                user_id: Some(UserId::from_static("mock-user-id")),
-                user_name: Some(UserName::from_static("mockuser")),
-                user_display_name: Some(DisplayName::from_static("Mock User")),
-                message: "test".to_string(),
+            TwitchEventAdBreakBegin {
+                duration_seconds: 1,
             },
         )
         .await
-        .expect("missing expected redeem event");
+        .unwrap();
 
         let event = found_event.events.first().expect("missing matching event");
 
@@ -707,93 +2769,98 @@ mod test {
         assert_eq!(event.id, expected_event.id);
     }
 
-    /// Tests that a cheer bits event will not match using "match_cheer_bits_event"
-    /// when the amount of bits is not enough
     #[tokio::test]
-    async fn test_match_cheer_bits_event_not_enough_bits() {
+    async fn test_match_ad_break_event_non_existent() {
         let db = mock_database().await;
 
-        EventModel::create(
+        let found_event = match_ad_break_event(
+            &db,
+            TwitchEventAdBreakBegin {
+                duration_seconds: 1,
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+    }
+
+    #[tokio::test]
+    async fn test_match_shoutout_receive_event() {
+        let db = mock_database().await;
+
+        let expected_event = EventModel::create(
             &db,
             CreateEvent {
                 enabled: true,
                 name: "Test Event".to_string(),
-                trigger: EventTrigger::Bits { min_bits: 500 },
+                trigger: EventTrigger::ShoutoutReceive { min_viewers: 1 },
                 cooldown: Default::default(),
-                outcome: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
                     template: "test".to_string(),
-                }),
+                    ..Default::default()
+                })
+                .into(),
                 outcome_delay: Default::default(),
                 require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
             },
         )
         .await
         .unwrap();
 
-        let found_event = match_cheer_bits_event(
+        let found_event = match_shoutout_receive_event(
             &db,
-            TwitchEventCheerBits {
-                bits: 100,
-                anonymous: false,
-                user_id: Some(UserId::from_static("mock-user-id")),
-                user_name: Some(UserName::from_static("mockuser")),
-                user_display_name: Some(DisplayName::from_static("Mock User")),
-                message: "test".to_string(),
+            TwitchEventShoutoutReceive {
+                viewer_count: 1,
+                user_id: UserId::from_static("mock-user-id"),
+                user_name: UserName::from_static("mockuser"),
+                user_display_name: DisplayName::from_static("Mock User"),
             },
         )
         .await
-        .expect("missing expected redeem event");
-
-        assert!(found_event.events.is_empty(), "should not match any events");
-    }
-
-    /// Tests that no events will be found if the required if "match_cheer_bits_event" has no
-    /// matching database events
-    #[tokio::test]
-    async fn test_match_cheer_bits_event_non_existent() {
-        let db = mock_database().await;
+        .unwrap();
 
-        let found_event = match_cheer_bits_event(
-            &db,
-            TwitchEventCheerBits {
-                bits: 100,
-                anonymous: false,
-                user_id: Some(UserId::from_static("mock-user-id")),
-                user_name: Some(UserName::from_static("mockuser")),
-                user_display_name: Some(DisplayName::from_static("Mock User")),
-                message: "test".to_string(),
-            },
-        )
-        .await
-        .expect("missing expected redeem event");
+        let event = found_event.events.first().expect("missing matching event");
 
-        assert!(found_event.events.is_empty(), "should not match any events");
+        // Expect found event to match created
+        assert_eq!(event.id, expected_event.id);
     }
 
     #[tokio::test]
-    async fn test_match_follow_event() {
+    async fn test_match_shoutout_receive_event_not_enough_viewers() {
         let db = mock_database().await;
 
-        let expected_event = EventModel::create(
+        EventModel::create(
             &db,
             CreateEvent {
                 enabled: true,
                 name: "Test Event".to_string(),
-                trigger: EventTrigger::Follow,
+                trigger: EventTrigger::ShoutoutReceive { min_viewers: 5 },
                 cooldown: Default::default(),
-                outcome: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
                     template: "test".to_string(),
-                }),
+                    ..Default::default()
+                })
+                .into(),
                 outcome_delay: Default::default(),
                 require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
             },
         )
         .await
         .unwrap();
 
-        let found_event = match_follow_event(
+        let found_event = match_shoutout_receive_event(
             &db,
-            TwitchEventFollow {
+            TwitchEventShoutoutReceive {
+                viewer_count: 1,
                 user_id: UserId::from_static("mock-user-id"),
                 user_name: UserName::from_static("mockuser"),
                 user_display_name: DisplayName::from_static("Mock User"),
@@ -802,19 +2869,17 @@ mod test {
         .await
         .unwrap();
 
-        let event = found_event.events.first().expect("missing matching event");
-
-        // Expect found event to match created
-        assert_eq!(event.id, expected_event.id);
+        assert!(found_event.events.is_empty(), "should not match any events");
     }
 
     #[tokio::test]
-    async fn test_match_follow_event_non_existent() {
+    async fn test_match_shoutout_receive_event_non_existent() {
         let db = mock_database().await;
 
-        let found_event = match_follow_event(
+        let found_event = match_shoutout_receive_event(
             &db,
-            TwitchEventFollow {
+            TwitchEventShoutoutReceive {
+                viewer_count: 1,
                 user_id: UserId::from_static("mock-user-id"),
                 user_name: UserName::from_static("mockuser"),
                 user_display_name: DisplayName::from_static("Mock User"),
@@ -826,8 +2891,20 @@ mod test {
         assert!(found_event.events.is_empty(), "should not match any events");
     }
 
+    fn mock_hype_train(level: i64) -> TwitchEventHypeTrain {
+        TwitchEventHypeTrain {
+            user_id: UserId::from_static("mock-user-id"),
+            user_name: UserName::from_static("mockuser"),
+            user_display_name: DisplayName::from_static("Mock User"),
+            level,
+            total: 1500,
+            progress: 300,
+            goal: 1800,
+        }
+    }
+
     #[tokio::test]
-    async fn test_match_subscription_event() {
+    async fn test_match_hype_train_begin_event() {
         let db = mock_database().await;
 
         let expected_event = EventModel::create(
@@ -835,31 +2912,86 @@ mod test {
             CreateEvent {
                 enabled: true,
                 name: "Test Event".to_string(),
-                trigger: EventTrigger::Subscription,
+                trigger: EventTrigger::HypeTrainBegin,
                 cooldown: Default::default(),
-                outcome: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
                     template: "test".to_string(),
-                }),
+                    ..Default::default()
+                })
+                .into(),
                 outcome_delay: Default::default(),
                 require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
             },
         )
         .await
         .unwrap();
 
-        let found_event = match_subscription_event(
+        let found_event = match_hype_train_begin_event(&db, mock_hype_train(1))
+            .await
+            .unwrap();
+
+        let event = found_event.events.first().expect("missing matching event");
+
+        // Expect found event to match created
+        assert_eq!(event.id, expected_event.id);
+
+        // Expect level and total to be available
+        assert!(matches!(
+            found_event.event_data.input_data,
+            EventInputData::HypeTrain {
+                level: 1,
+                total: 1500,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_match_hype_train_begin_event_non_existent() {
+        let db = mock_database().await;
+
+        let found_event = match_hype_train_begin_event(&db, mock_hype_train(1))
+            .await
+            .unwrap();
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+    }
+
+    #[tokio::test]
+    async fn test_match_hype_train_progress_event() {
+        let db = mock_database().await;
+
+        let expected_event = EventModel::create(
             &db,
-            TwitchEventSub {
-                is_gift: false,
-                tier: SubscriptionTier::Tier1,
-                user_id: UserId::from_static("mock-user-id"),
-                user_name: UserName::from_static("mockuser"),
-                user_display_name: DisplayName::from_static("Mock User"),
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::HypeTrainProgress { min_level: 2 },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
             },
         )
         .await
         .unwrap();
 
+        let found_event = match_hype_train_progress_event(&db, mock_hype_train(2))
+            .await
+            .unwrap();
+
         let event = found_event.events.first().expect("missing matching event");
 
         // Expect found event to match created
@@ -867,27 +2999,41 @@ mod test {
     }
 
     #[tokio::test]
-    async fn test_match_subscription_event_non_existent() {
+    async fn test_match_hype_train_progress_event_not_enough_level() {
         let db = mock_database().await;
 
-        let found_event = match_subscription_event(
+        EventModel::create(
             &db,
-            TwitchEventSub {
-                is_gift: false,
-                tier: SubscriptionTier::Tier1,
-                user_id: UserId::from_static("mock-user-id"),
-                user_name: UserName::from_static("mockuser"),
-                user_display_name: DisplayName::from_static("Mock User"),
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::HypeTrainProgress { min_level: 2 },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
             },
         )
         .await
         .unwrap();
 
+        let found_event = match_hype_train_progress_event(&db, mock_hype_train(1))
+            .await
+            .unwrap();
+
         assert!(found_event.events.is_empty(), "should not match any events");
     }
 
     #[tokio::test]
-    async fn test_match_gifted_subscription_event() {
+    async fn test_match_hype_train_end_event() {
         let db = mock_database().await;
 
         let expected_event = EventModel::create(
@@ -895,28 +3041,29 @@ mod test {
             CreateEvent {
                 enabled: true,
                 name: "Test Event".to_string(),
-                trigger: EventTrigger::GiftedSubscription,
+                trigger: EventTrigger::HypeTrainEnd { min_level: 0 },
                 cooldown: Default::default(),
-                outcome: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
                     template: "test".to_string(),
-                }),
+                    ..Default::default()
+                })
+                .into(),
                 outcome_delay: Default::default(),
                 require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
             },
         )
         .await
         .unwrap();
 
-        let found_event = match_gifted_subscription_event(
+        let found_event = match_hype_train_end_event(
             &db,
-            TwitchEventGiftSub {
-                anonymous: true,
-                total: 1,
-                cumulative_total: None,
-                tier: SubscriptionTier::Tier1,
-                user_id: Some(UserId::from_static("mock-user-id")),
-                user_name: Some(UserName::from_static("mockuser")),
-                user_display_name: Some(DisplayName::from_static("Mock User")),
+            TwitchEventHypeTrainEnd {
+                level: 3,
+                total: 4000,
             },
         )
         .await
@@ -929,19 +3076,14 @@ mod test {
     }
 
     #[tokio::test]
-    async fn test_match_gifted_subscription_event_non_existent() {
+    async fn test_match_hype_train_end_event_non_existent() {
         let db = mock_database().await;
 
-        let found_event = match_gifted_subscription_event(
+        let found_event = match_hype_train_end_event(
             &db,
-            TwitchEventGiftSub {
-                anonymous: true,
-                total: 1,
-                cumulative_total: None,
-                tier: SubscriptionTier::Tier1,
-                user_id: Some(UserId::from_static("mock-user-id")),
-                user_name: Some(UserName::from_static("mockuser")),
-                user_display_name: Some(DisplayName::from_static("Mock User")),
+            TwitchEventHypeTrainEnd {
+                level: 3,
+                total: 4000,
             },
         )
         .await
@@ -950,8 +3092,10 @@ mod test {
         assert!(found_event.events.is_empty(), "should not match any events");
     }
 
+    /// Tests that a stream online event starts a new stream session and matches
+    /// the stream online events
     #[tokio::test]
-    async fn test_match_re_subscription_event() {
+    async fn test_match_stream_online_event() {
         let db = mock_database().await;
 
         let expected_event = EventModel::create(
@@ -959,35 +3103,29 @@ mod test {
             CreateEvent {
                 enabled: true,
                 name: "Test Event".to_string(),
-                trigger: EventTrigger::Subscription,
+                trigger: EventTrigger::StreamOnline,
                 cooldown: Default::default(),
-                outcome: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
                     template: "test".to_string(),
-                }),
+                    ..Default::default()
+                })
+                .into(),
                 outcome_delay: Default::default(),
                 require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
             },
         )
         .await
         .unwrap();
 
-        let message = serde_json::json!({
-            "text": "",
-            "emotes": []
-        });
-        let message: SubscriptionMessage = serde_json::from_value(message).unwrap();
-
-        let found_event = match_re_subscription_event(
+        let found_event = match_stream_online_event(
             &db,
-            TwitchEventReSub {
-                cumulative_months: 1,
-                duration_months: 1,
-                message,
-                streak_months: Some(1),
-                tier: SubscriptionTier::Tier1,
-                user_id: UserId::from_static("mock-user-id"),
-                user_name: UserName::from_static("mockuser"),
-                user_display_name: DisplayName::from_static("Mock User"),
+            TwitchEventStreamOnline {
+                stream_id: "mock-stream-id".to_string(),
+                started_at: Timestamp::new("2025-02-07T02:14:08Z".to_string()).unwrap(),
             },
         )
         .await
@@ -997,39 +3135,156 @@ mod test {
 
         // Expect found event to match created
         assert_eq!(event.id, expected_event.id);
+
+        let session = StreamSessionModel::get_current(&db)
+            .await
+            .unwrap()
+            .expect("missing stream session");
+
+        assert_eq!(session.stream_id, "mock-stream-id");
     }
 
+    /// Tests that a stream offline event ends the current stream session
     #[tokio::test]
-    async fn test_match_re_subscription_event_non_existent() {
+    async fn test_match_stream_offline_event() {
         let db = mock_database().await;
 
-        let message = serde_json::json!({
-            "text": "",
-            "emotes": []
-        });
-        let message: SubscriptionMessage = serde_json::from_value(message).unwrap();
+        let expected_event = EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::StreamOffline,
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        match_stream_online_event(
+            &db,
+            TwitchEventStreamOnline {
+                stream_id: "mock-stream-id".to_string(),
+                started_at: Timestamp::new("2025-02-07T02:14:08Z".to_string()).unwrap(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let session = StreamSessionModel::get_current(&db)
+            .await
+            .unwrap()
+            .expect("missing stream session");
+
+        let found_event = match_stream_offline_event(&db).await.unwrap();
+
+        let event = found_event.events.first().expect("missing matching event");
+
+        // Expect found event to match created
+        assert_eq!(event.id, expected_event.id);
+
+        let current = StreamSessionModel::get_current(&db).await.unwrap();
+        assert!(current.is_none(), "stream session should be ended");
+
+        let session = StreamSessionModel::get_by_id(&db, session.id)
+            .await
+            .unwrap()
+            .expect("missing stream session");
+        assert!(session.ended_at.is_some());
+    }
+
+    /// Tests reconciling the stream sessions with the live stream state, a stale
+    /// session is replaced, a matching session is kept and sessions are ended
+    /// when the stream is offline
+    #[tokio::test]
+    async fn test_reconcile_stream_session() {
+        let db = mock_database().await;
 
-        let found_event = match_re_subscription_event(
+        match_stream_online_event(
             &db,
-            TwitchEventReSub {
-                cumulative_months: 1,
-                duration_months: 1,
-                message,
-                streak_months: Some(1),
-                tier: SubscriptionTier::Tier1,
-                user_id: UserId::from_static("mock-user-id"),
-                user_name: UserName::from_static("mockuser"),
-                user_display_name: DisplayName::from_static("Mock User"),
+            TwitchEventStreamOnline {
+                stream_id: "stale-stream-id".to_string(),
+                started_at: Timestamp::new("2025-02-07T02:14:08Z".to_string()).unwrap(),
             },
         )
         .await
         .unwrap();
 
-        assert!(found_event.events.is_empty(), "should not match any events");
+        let stale = StreamSessionModel::get_current(&db)
+            .await
+            .unwrap()
+            .expect("missing stream session");
+
+        // Live with a different stream, stale session should be replaced
+        let started_at = Utc::now();
+        reconcile_stream_session(&db, Some(("live-stream-id", started_at)))
+            .await
+            .unwrap();
+
+        let stale = StreamSessionModel::get_by_id(&db, stale.id)
+            .await
+            .unwrap()
+            .expect("missing stream session");
+        assert!(stale.ended_at.is_some());
+
+        let live = StreamSessionModel::get_current(&db)
+            .await
+            .unwrap()
+            .expect("missing stream session");
+        assert_eq!(live.stream_id, "live-stream-id");
+
+        // Same stream reported again, session should be kept
+        reconcile_stream_session(&db, Some(("live-stream-id", started_at)))
+            .await
+            .unwrap();
+
+        let current = StreamSessionModel::get_current(&db)
+            .await
+            .unwrap()
+            .expect("missing stream session");
+        assert_eq!(current.id, live.id);
+
+        // Stream is offline, session should be ended
+        reconcile_stream_session(&db, None).await.unwrap();
+
+        let current = StreamSessionModel::get_current(&db).await.unwrap();
+        assert!(current.is_none(), "stream session should be ended");
+    }
+
+    fn mock_chat_message(text: &str) -> TwitchEventChatMsg {
+        let message = serde_json::json!({
+            "text": text,
+            "fragments": []
+        });
+        let message: twitch_api::eventsub::channel::chat::Message =
+            serde_json::from_value(message).unwrap();
+
+        TwitchEventChatMsg {
+            message_id: uuid::Uuid::new_v4().to_string().into(),
+            message,
+            cheer: None,
+
+            user_id: UserId::from_static("mock-user-id"),
+            user_name: UserName::from_static("mockuser"),
+            user_display_name: DisplayName::from_static("Mock User"),
+        }
     }
 
+    /// Tests that the first message trigger only matches the first message
+    /// from a user in each stream
     #[tokio::test]
-    async fn test_match_chat_event() {
+    async fn test_match_first_message_event() {
         let db = mock_database().await;
 
         let expected_event = EventModel::create(
@@ -1037,50 +3292,64 @@ mod test {
             CreateEvent {
                 enabled: true,
                 name: "Test Event".to_string(),
-                trigger: EventTrigger::Command {
-                    message: "!test".to_string(),
+                trigger: EventTrigger::FirstMessage {
+                    first_time_ever: false,
                 },
                 cooldown: Default::default(),
-                outcome: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
                     template: "test".to_string(),
-                }),
+                    ..Default::default()
+                })
+                .into(),
                 outcome_delay: Default::default(),
                 require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
             },
         )
         .await
         .unwrap();
 
-        let message = serde_json::json!({
-            "text": "!test",
-            "fragments": []
-        });
-        let message: twitch_api::eventsub::channel::chat::Message =
-            serde_json::from_value(message).unwrap();
+        let found_event = match_chat_event(&db, mock_chat_message("hello"))
+            .await
+            .unwrap();
 
-        let found_event = match_chat_event(
-            &db,
-            TwitchEventChatMsg {
-                message_id: "mock-message".into(),
-                message,
-                cheer: None,
+        let event = found_event.events.first().expect("missing matching event");
 
-                user_id: UserId::from_static("mock-user-id"),
-                user_name: UserName::from_static("mockuser"),
-                user_display_name: DisplayName::from_static("Mock User"),
+        // Expect found event to match created
+        assert_eq!(event.id, expected_event.id);
+
+        // Second message in the same stream should not match
+        let found_event = match_chat_event(&db, mock_chat_message("hello again"))
+            .await
+            .unwrap();
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+
+        // First message in a new stream should match
+        match_stream_online_event(
+            &db,
+            TwitchEventStreamOnline {
+                stream_id: "mock-stream-id".to_string(),
+                started_at: Timestamp::new("2025-02-08T06:47:52Z".to_string()).unwrap(),
             },
         )
         .await
         .unwrap();
 
-        let event = found_event.events.first().expect("missing matching event");
+        let found_event = match_chat_event(&db, mock_chat_message("hello stream"))
+            .await
+            .unwrap();
 
-        // Expect found event to match created
-        assert_eq!(event.id, expected_event.id);
+        assert_eq!(found_event.events.len(), 1);
     }
 
+    /// Tests that the first time ever mode only matches the first message
+    /// the user has ever sent
     #[tokio::test]
-    async fn test_match_chat_event_not_matching() {
+    async fn test_match_first_message_event_first_time_ever() {
         let db = mock_database().await;
 
         EventModel::create(
@@ -1088,76 +3357,128 @@ mod test {
             CreateEvent {
                 enabled: true,
                 name: "Test Event".to_string(),
-                trigger: EventTrigger::Command {
-                    message: "!test".to_string(),
+                trigger: EventTrigger::FirstMessage {
+                    first_time_ever: true,
                 },
                 cooldown: Default::default(),
-                outcome: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
                     template: "test".to_string(),
-                }),
+                    ..Default::default()
+                })
+                .into(),
                 outcome_delay: Default::default(),
                 require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
             },
         )
         .await
         .unwrap();
 
-        let message = serde_json::json!({
-            "text": "",
-            "fragments": []
-        });
-        let message: twitch_api::eventsub::channel::chat::Message =
-            serde_json::from_value(message).unwrap();
+        let found_event = match_chat_event(&db, mock_chat_message("hello"))
+            .await
+            .unwrap();
 
-        let found_event = match_chat_event(
-            &db,
-            TwitchEventChatMsg {
-                message_id: "mock-message".into(),
-                message,
-                cheer: None,
+        assert_eq!(found_event.events.len(), 1);
 
-                user_id: UserId::from_static("mock-user-id"),
-                user_name: UserName::from_static("mockuser"),
-                user_display_name: DisplayName::from_static("Mock User"),
+        match_stream_online_event(
+            &db,
+            TwitchEventStreamOnline {
+                stream_id: "mock-stream-id".to_string(),
+                started_at: Timestamp::new("2025-02-08T06:47:52Z".to_string()).unwrap(),
             },
         )
         .await
         .unwrap();
 
+        // First message of the stream but not the first message ever
+        let found_event = match_chat_event(&db, mock_chat_message("hello stream"))
+            .await
+            .unwrap();
+
         assert!(found_event.events.is_empty(), "should not match any events");
     }
 
-    #[tokio::test]
-    async fn test_match_chat_event_non_existent() {
+    /// Tests that only one of many concurrent messages from a user is
+    /// the first message of the stream
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_store_chat_user_concurrent() {
         let db = mock_database().await;
+        let user_id = UserId::from_static("mock-user-id");
+
+        let first = store_chat_user(&db, &user_id, None).await.unwrap();
+        assert!(first.stream && first.ever);
+
+        // Recently chatted without a stream session
+        let second = store_chat_user(&db, &user_id, None).await.unwrap();
+        assert!(!second.stream && !second.ever);
+
+        let stream_session_id = Uuid::new_v4();
+        let tasks: Vec<_> = (0..20)
+            .map(|_| {
+                let db = db.clone();
+                let user_id = user_id.clone();
+                tokio::spawn(async move {
+                    store_chat_user(&db, &user_id, Some(stream_session_id))
+                        .await
+                        .unwrap()
+                })
+            })
+            .collect();
+
+        let mut first_count = 0;
+        for task in tasks {
+            let state = task.await.unwrap();
+            assert!(!state.ever);
+            if state.stream {
+                first_count += 1;
+            }
+        }
 
-        let message = serde_json::json!({
-            "text": "",
-            "fragments": []
-        });
-        let message: twitch_api::eventsub::channel::chat::Message =
-            serde_json::from_value(message).unwrap();
-
-        let found_event = match_chat_event(
-            &db,
-            TwitchEventChatMsg {
-                message_id: "mock-message".into(),
-                message,
-                cheer: None,
+        assert_eq!(first_count, 1);
+    }
 
-                user_id: UserId::from_static("mock-user-id"),
-                user_name: UserName::from_static("mockuser"),
-                user_display_name: DisplayName::from_static("Mock User"),
-            },
-        )
-        .await
-        .unwrap();
+    fn mock_poll() -> TwitchEventPoll {
+        TwitchEventPoll {
+            title: "Which item?".to_string(),
+            choices: vec![
+                TwitchPollChoice {
+                    title: "Brick".to_string(),
+                    votes: 3,
+                },
+                TwitchPollChoice {
+                    title: "Heart".to_string(),
+                    votes: 7,
+                },
+            ],
+        }
+    }
 
-        assert!(found_event.events.is_empty(), "should not match any events");
+    fn mock_prediction(winning_outcome_id: Option<&str>) -> TwitchEventPrediction {
+        TwitchEventPrediction {
+            title: "Will we win?".to_string(),
+            outcomes: vec![
+                TwitchPredictionOutcome {
+                    id: "mock-outcome-yes".to_string(),
+                    title: "Yes".to_string(),
+                    users: 2,
+                    channel_points: 500,
+                },
+                TwitchPredictionOutcome {
+                    id: "mock-outcome-no".to_string(),
+                    title: "No".to_string(),
+                    users: 1,
+                    channel_points: 1000,
+                },
+            ],
+            winning_outcome_id: winning_outcome_id.map(|value| value.to_string()),
+        }
     }
 
     #[tokio::test]
-    async fn test_match_raid_event() {
+    async fn test_match_poll_begin_event() {
         let db = mock_database().await;
 
         let expected_event = EventModel::create(
@@ -1165,93 +3486,116 @@ mod test {
             CreateEvent {
                 enabled: true,
                 name: "Test Event".to_string(),
-                trigger: EventTrigger::Raid { min_raiders: 1 },
+                trigger: EventTrigger::PollBegin,
                 cooldown: Default::default(),
-                outcome: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
                     template: "test".to_string(),
-                }),
+                    ..Default::default()
+                })
+                .into(),
                 outcome_delay: Default::default(),
                 require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
             },
         )
         .await
         .unwrap();
 
-        let found_event = match_raid_event(
-            &db,
-            TwitchEventRaid {
-                viewers: 1,
-                user_id: UserId::from_static("mock-user-id"),
-                user_name: UserName::from_static("mockuser"),
-                user_display_name: DisplayName::from_static("Mock User"),
-            },
-        )
-        .await
-        .unwrap();
+        let found_event = match_poll_event(&db, EventTriggerType::PollBegin, mock_poll())
+            .await
+            .unwrap();
 
         let event = found_event.events.first().expect("missing matching event");
 
         // Expect found event to match created
         assert_eq!(event.id, expected_event.id);
+
+        // Poll end events should not match
+        let found_event = match_poll_event(&db, EventTriggerType::PollEnd, mock_poll())
+            .await
+            .unwrap();
+
+        assert!(found_event.events.is_empty(), "should not match any events");
     }
 
+    /// Tests that poll end events only match when the required choice won
     #[tokio::test]
-    async fn test_match_raid_event_not_enough_raiders() {
+    async fn test_match_poll_end_event_winning_choice() {
         let db = mock_database().await;
 
-        EventModel::create(
+        let expected_event = EventModel::create(
             &db,
             CreateEvent {
                 enabled: true,
-                name: "Test Event".to_string(),
-                trigger: EventTrigger::Raid { min_raiders: 5 },
+                name: "Heart Event".to_string(),
+                trigger: EventTrigger::PollEnd {
+                    winning_choice: Some("heart".to_string()),
+                },
                 cooldown: Default::default(),
-                outcome: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
                     template: "test".to_string(),
-                }),
+                    ..Default::default()
+                })
+                .into(),
                 outcome_delay: Default::default(),
                 require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
             },
         )
         .await
         .unwrap();
 
-        let found_event = match_raid_event(
-            &db,
-            TwitchEventRaid {
-                viewers: 1,
-                user_id: UserId::from_static("mock-user-id"),
-                user_name: UserName::from_static("mockuser"),
-                user_display_name: DisplayName::from_static("Mock User"),
-            },
-        )
-        .await
-        .unwrap();
-
-        assert!(found_event.events.is_empty(), "should not match any events");
-    }
-
-    #[tokio::test]
-    async fn test_match_raid_event_non_existent() {
-        let db = mock_database().await;
-
-        let found_event = match_raid_event(
+        EventModel::create(
             &db,
-            TwitchEventRaid {
-                viewers: 1,
-                user_id: UserId::from_static("mock-user-id"),
-                user_name: UserName::from_static("mockuser"),
-                user_display_name: DisplayName::from_static("Mock User"),
+            CreateEvent {
+                enabled: true,
+                name: "Brick Event".to_string(),
+                trigger: EventTrigger::PollEnd {
+                    winning_choice: Some("Brick".to_string()),
+                },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
             },
         )
         .await
         .unwrap();
 
-        assert!(found_event.events.is_empty(), "should not match any events");
+        let found_event = match_poll_event(&db, EventTriggerType::PollEnd, mock_poll())
+            .await
+            .unwrap();
+
+        assert_eq!(found_event.events.len(), 1);
+        assert_eq!(found_event.events[0].id, expected_event.id);
+
+        assert!(matches!(
+            found_event.event_data.input_data,
+            EventInputData::Poll {
+                winning_choice: Some(ref winning_choice),
+                total_votes: 10,
+                ..
+            } if winning_choice == "Heart"
+        ));
     }
 
+    /// Tests that prediction end events only match when the required outcome won
     #[tokio::test]
-    async fn test_match_ad_break_event() {
+    async fn test_match_prediction_end_event_winning_outcome() {
         let db = mock_database().await;
 
         let expected_event = EventModel::create(
@@ -1259,23 +3603,30 @@ mod test {
             CreateEvent {
                 enabled: true,
                 name: "Test Event".to_string(),
-                trigger: EventTrigger::AdBreakBegin,
+                trigger: EventTrigger::PredictionEnd {
+                    winning_outcome: Some("Yes".to_string()),
+                },
                 cooldown: Default::default(),
-                outcome: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
                     template: "test".to_string(),
-                }),
+                    ..Default::default()
+                })
+                .into(),
                 outcome_delay: Default::default(),
                 require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
             },
         )
         .await
         .unwrap();
 
-        let found_event = match_ad_break_event(
+        let found_event = match_prediction_event(
             &db,
-            TwitchEventAdBreakBegin {
-                duration_seconds: 1,
-            },
+            EventTriggerType::PredictionEnd,
+            mock_prediction(Some("mock-outcome-yes")),
         )
         .await
         .unwrap();
@@ -1284,17 +3635,19 @@ mod test {
 
         // Expect found event to match created
         assert_eq!(event.id, expected_event.id);
-    }
 
-    #[tokio::test]
-    async fn test_match_ad_break_event_non_existent() {
-        let db = mock_database().await;
+        assert!(matches!(
+            found_event.event_data.input_data,
+            EventInputData::Prediction {
+                total_points: 1500,
+                ..
+            }
+        ));
 
-        let found_event = match_ad_break_event(
+        let found_event = match_prediction_event(
             &db,
-            TwitchEventAdBreakBegin {
-                duration_seconds: 1,
-            },
+            EventTriggerType::PredictionEnd,
+            mock_prediction(Some("mock-outcome-no")),
         )
         .await
         .unwrap();
@@ -1303,7 +3656,19 @@ mod test {
     }
 
     #[tokio::test]
-    async fn test_match_shoutout_receive_event() {
+    async fn test_match_prediction_lock_event_non_existent() {
+        let db = mock_database().await;
+
+        let found_event =
+            match_prediction_event(&db, EventTriggerType::PredictionLock, mock_prediction(None))
+                .await
+                .unwrap();
+
+        assert!(found_event.events.is_empty(), "should not match any events");
+    }
+
+    #[tokio::test]
+    async fn test_match_webhook_event() {
         let db = mock_database().await;
 
         let expected_event = EventModel::create(
@@ -1311,88 +3676,211 @@ mod test {
             CreateEvent {
                 enabled: true,
                 name: "Test Event".to_string(),
-                trigger: EventTrigger::ShoutoutReceive { min_viewers: 1 },
+                trigger: EventTrigger::Webhook {
+                    slug: Some("stream-deck".to_string()),
+                    secret: "secret".to_string(),
+                },
                 cooldown: Default::default(),
-                outcome: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
                     template: "test".to_string(),
-                }),
+                    ..Default::default()
+                })
+                .into(),
                 outcome_delay: Default::default(),
                 require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
             },
         )
         .await
         .unwrap();
 
-        let found_event = match_shoutout_receive_event(
+        // Expect the event to be found by its ID
+        let event = match_webhook_event(&db, &expected_event.id.to_string())
+            .await
+            .unwrap()
+            .expect("missing matching event");
+        assert_eq!(event.id, expected_event.id);
+
+        // Expect the event to be found by its slug
+        let event = match_webhook_event(&db, "Stream-Deck")
+            .await
+            .unwrap()
+            .expect("missing matching event");
+        assert_eq!(event.id, expected_event.id);
+    }
+
+    #[tokio::test]
+    async fn test_match_webhook_event_non_existent() {
+        let db = mock_database().await;
+
+        // Events with other trigger types should not be usable as webhooks
+        let event = EventModel::create(
             &db,
-            TwitchEventShoutoutReceive {
-                viewer_count: 1,
-                user_id: UserId::from_static("mock-user-id"),
-                user_name: UserName::from_static("mockuser"),
-                user_display_name: DisplayName::from_static("Mock User"),
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::Follow,
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
             },
         )
         .await
         .unwrap();
 
-        let event = found_event.events.first().expect("missing matching event");
+        let found_event = match_webhook_event(&db, &event.id.to_string())
+            .await
+            .unwrap();
+        assert!(found_event.is_none(), "should not match any events");
 
-        // Expect found event to match created
-        assert_eq!(event.id, expected_event.id);
+        let found_event = match_webhook_event(&db, "stream-deck").await.unwrap();
+        assert!(found_event.is_none(), "should not match any events");
     }
 
-    #[tokio::test]
-    async fn test_match_shoutout_receive_event_not_enough_viewers() {
-        let db = mock_database().await;
+    #[test]
+    fn test_webhook_secret_valid() {
+        assert!(is_webhook_secret_valid("secret", "secret"));
+        assert!(!is_webhook_secret_valid("secret", "Secret"));
+        assert!(!is_webhook_secret_valid("secret", "secret2"));
+        assert!(!is_webhook_secret_valid("secret", "secre"));
+        assert!(!is_webhook_secret_valid("secret", ""));
+        assert!(!is_webhook_secret_valid("", ""));
+    }
 
+    /// Creates a follow event for testing event groups
+    async fn create_follow_event(db: &sea_orm::DatabaseConnection) -> EventModel {
         EventModel::create(
-            &db,
+            db,
             CreateEvent {
                 enabled: true,
                 name: "Test Event".to_string(),
-                trigger: EventTrigger::ShoutoutReceive { min_viewers: 5 },
+                trigger: EventTrigger::Follow,
                 cooldown: Default::default(),
-                outcome: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
                     template: "test".to_string(),
-                }),
+                    ..Default::default()
+                })
+                .into(),
                 outcome_delay: Default::default(),
                 require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
             },
         )
         .await
-        .unwrap();
+        .unwrap()
+    }
 
-        let found_event = match_shoutout_receive_event(
+    fn mock_follow() -> TwitchEventFollow {
+        TwitchEventFollow {
+            user_id: UserId::from_static("mock-user-id"),
+            user_name: UserName::from_static("mockuser"),
+            user_display_name: DisplayName::from_static("Mock User"),
+        }
+    }
+
+    /// Tests that events in a disabled group are not matched and are
+    /// matched again once the group is enabled
+    #[tokio::test]
+    async fn test_match_event_group_disabled() {
+        let db = mock_database().await;
+
+        let event = create_follow_event(&db).await;
+        let ungrouped_event = create_follow_event(&db).await;
+
+        let group = EventGroupModel::create(
             &db,
-            TwitchEventShoutoutReceive {
-                viewer_count: 1,
-                user_id: UserId::from_static("mock-user-id"),
-                user_name: UserName::from_static("mockuser"),
-                user_display_name: DisplayName::from_static("Mock User"),
+            CreateEventGroup {
+                name: "Gaming".to_string(),
+                enabled: false,
             },
         )
         .await
         .unwrap();
+        group.set_event_ids(&db, &[event.id]).await.unwrap();
 
-        assert!(found_event.events.is_empty(), "should not match any events");
+        let found_event = match_follow_event(&db, mock_follow()).await.unwrap();
+
+        // Only the event outside of the group should match
+        let ids: Vec<_> = found_event.events.iter().map(|event| event.id).collect();
+        assert_eq!(ids, vec![ungrouped_event.id]);
+
+        group
+            .update(
+                &db,
+                UpdateEventGroup {
+                    enabled: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let found_event = match_follow_event(&db, mock_follow()).await.unwrap();
+        assert_eq!(found_event.events.len(), 2);
     }
 
+    /// Tests that events in multiple groups are matched when at least
+    /// one of their groups is enabled
     #[tokio::test]
-    async fn test_match_shoutout_receive_event_non_existent() {
+    async fn test_match_event_group_any_enabled() {
         let db = mock_database().await;
 
-        let found_event = match_shoutout_receive_event(
+        let event = create_follow_event(&db).await;
+
+        let disabled_group = EventGroupModel::create(
             &db,
-            TwitchEventShoutoutReceive {
-                viewer_count: 1,
-                user_id: UserId::from_static("mock-user-id"),
-                user_name: UserName::from_static("mockuser"),
-                user_display_name: DisplayName::from_static("Mock User"),
+            CreateEventGroup {
+                name: "Just Chatting".to_string(),
+                enabled: false,
+            },
+        )
+        .await
+        .unwrap();
+        disabled_group
+            .set_event_ids(&db, &[event.id])
+            .await
+            .unwrap();
+
+        let enabled_group = EventGroupModel::create(
+            &db,
+            CreateEventGroup {
+                name: "Gaming".to_string(),
+                enabled: true,
             },
         )
         .await
         .unwrap();
+        enabled_group.set_event_ids(&db, &[event.id]).await.unwrap();
+
+        let found_event = match_follow_event(&db, mock_follow()).await.unwrap();
+        let found = found_event.events.first().expect("missing matching event");
+        assert_eq!(found.id, event.id);
 
+        // Removing the event from the enabled group leaves only the disabled group
+        enabled_group.set_event_ids(&db, &[]).await.unwrap();
+
+        let found_event = match_follow_event(&db, mock_follow()).await.unwrap();
         assert!(found_event.events.is_empty(), "should not match any events");
+
+        assert_eq!(
+            disabled_group.get_event_ids(&db).await.unwrap(),
+            vec![event.id]
+        );
+        assert!(enabled_group.get_event_ids(&db).await.unwrap().is_empty());
     }
 }
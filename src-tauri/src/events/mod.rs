@@ -1,7 +1,12 @@
+pub mod feedback;
+pub mod groups;
 pub mod matching;
 pub mod outcome;
+pub mod pattern;
 pub mod processing;
+pub mod queue;
 pub mod scheduler;
+pub mod template;
 
 use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
@@ -94,6 +99,13 @@ pub enum EventMessage {
         configs: Vec<PartialSoundModel>,
     },
 
+    /// Outcome the overlay must report as complete once it has finished
+    /// producing the outcome `message`
+    Outcome {
+        outcome_id: Uuid,
+        message: Box<EventMessage>,
+    },
+
     /// Tell the overlay to reload the app data as it
     /// has changed
     AppDataUpdated {
@@ -101,19 +113,11 @@ pub enum EventMessage {
     },
 }
 
-pub struct EventRecvHandle(pub broadcast::Receiver<EventMessage>);
-
-impl Clone for EventRecvHandle {
-    fn clone(&self) -> Self {
-        Self(self.0.resubscribe())
-    }
-}
-
-pub type EventSendHandle = broadcast::Sender<EventMessage>;
-
-pub fn create_event_channel() -> (EventSendHandle, EventRecvHandle) {
-    let (event_tx, rx) = broadcast::channel(10);
-    let event_rx = EventRecvHandle(rx);
-
-    (event_tx, event_rx)
+/// Creates the channel for sending messages to the overlay
+///
+/// Overlays subscribe to the channel when they connect and no other receivers
+/// are kept, sending a message fails when no overlay is connected
+pub fn create_event_channel() -> EventMessageChannel {
+    let (event_tx, _) = broadcast::channel(10);
+    event_tx
 }
@@ -1,25 +1,34 @@
 use super::{
     matching::{EventData, EventInputData},
+    template::{render_template, TemplateContext},
     EventMessage, ItemWithSoundIds, ItemsWithSounds, ThrowItemConfig, ThrowItemMessage,
 };
 use crate::{
     database::entity::{
         events::{
-            EventModel, EventOutcome, EventOutcomeBits, EventOutcomeChannelEmotes,
-            EventOutcomePlaySound, EventOutcomeSendChat, EventOutcomeThrowable,
-            EventOutcomeTriggerHotkey, ThrowableAmountData,
+            CommandArgumentKind, EventOutcome, EventOutcomeAnnouncement, EventOutcomeBits,
+            EventOutcomeChannelEmotes, EventOutcomePlaySound, EventOutcomeSendChat,
+            EventOutcomeSetRewardState, EventOutcomeShoutout, EventOutcomeTargetUser,
+            EventOutcomeThrowable, EventOutcomeTimeoutUser, EventOutcomeTriggerHotkey,
+            EventTrigger, ThrowableAmountData,
         },
         items::{ItemConfig, ItemImageConfig, ItemModel},
         items_sounds::SoundType,
         sounds::{PartialSoundModel, SoundModel},
     },
-    twitch::manager::Twitch,
+    twitch::{
+        manager::Twitch, models::ChatIdentity, reward_revert::RewardState, rewards::UpdateReward,
+    },
 };
 use anyhow::{anyhow, Context};
 use chrono::Utc;
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    Rng,
+};
 use sea_orm::DatabaseConnection;
-use std::collections::HashSet;
-use twitch_api::types::SubscriptionTier;
+use std::{collections::HashSet, time::Duration};
+use twitch_api::types::{MsgIdRef, RewardIdRef, UserId};
 use uuid::Uuid;
 
 /// Produce a message for an outcome
@@ -27,12 +36,15 @@ pub async fn produce_outcome_message(
     db: &DatabaseConnection,
     twitch: &Twitch,
 
-    event: EventModel,
+    trigger: &EventTrigger,
+    outcome: EventOutcome,
     event_data: EventData,
 ) -> anyhow::Result<Option<EventMessage>> {
-    match event.outcome {
+    match outcome {
         EventOutcome::ThrowBits(data) => throw_bits_outcome(db, event_data, data).await.map(Some),
-        EventOutcome::Throwable(data) => throwable_outcome(db, event_data, data).await.map(Some),
+        EventOutcome::Throwable(data) => throwable_outcome(db, trigger, event_data, data)
+            .await
+            .map(Some),
         EventOutcome::TriggerHotkey(data) => trigger_hotkey_outcome(data).map(Some),
         EventOutcome::PlaySound(data) => play_sound_outcome(db, data).await.map(Some),
         EventOutcome::SendChatMessage(data) => {
@@ -42,17 +54,61 @@ pub async fn produce_outcome_message(
         EventOutcome::ChannelEmotes(data) => throw_channel_emotes_outcome(twitch, event_data, data)
             .await
             .map(Some),
+        EventOutcome::SetRewardState(data) => {
+            set_reward_state_outcome(twitch, event_data, data).await?;
+            Ok(None)
+        }
+        EventOutcome::TimeoutUser(data) => {
+            timeout_user_outcome(twitch, trigger, event_data, data).await?;
+            Ok(None)
+        }
+        EventOutcome::DeleteMessage => {
+            delete_message_outcome(twitch, event_data).await?;
+            Ok(None)
+        }
+        EventOutcome::Shoutout(data) => {
+            shoutout_outcome(twitch, trigger, event_data, data).await?;
+            Ok(None)
+        }
+        EventOutcome::Announcement(data) => {
+            announcement_outcome(twitch, event_data, data).await?;
+            Ok(None)
+        }
+        // Chained events are run through "execute_event_outcomes" as
+        // they require access to the event sender
+        EventOutcome::RunEvent(_) => Ok(None),
+        // Random outcomes are resolved before producing the message
+        EventOutcome::Random(_) => Err(anyhow!("random outcome was not resolved")),
     }
 }
 
-fn format_subscription_tier(tier: SubscriptionTier) -> &'static str {
-    match tier {
-        SubscriptionTier::Tier1 => "Tier 1",
-        SubscriptionTier::Tier2 => "Tier 2",
-        SubscriptionTier::Tier3 => "Tier 3",
-        SubscriptionTier::Prime => "Prime",
-        SubscriptionTier::Other(_) => "Other",
+/// Resolves a [EventOutcome::Random] outcome (Including any nested random outcomes)
+/// into the outcome that was picked, the index of each picked choice is pushed
+/// onto `choices`
+///
+/// Outcomes that aren't random are returned as-is
+pub fn resolve_random_outcome<R: Rng>(
+    mut outcome: EventOutcome,
+    rng: &mut R,
+    choices: &mut Vec<usize>,
+) -> anyhow::Result<EventOutcome> {
+    while let EventOutcome::Random(data) = outcome {
+        let weights = data.choices.iter().map(|choice| choice.weight);
+        let distribution =
+            WeightedIndex::new(weights).context("random outcome has no choices to pick")?;
+
+        let index = distribution.sample(rng);
+        choices.push(index);
+
+        outcome = data
+            .choices
+            .into_iter()
+            .nth(index)
+            .context("picked random choice is missing")?
+            .outcome;
     }
+
+    Ok(outcome)
 }
 
 async fn send_chat_message(
@@ -60,76 +116,232 @@ async fn send_chat_message(
     event_data: EventData,
     data: EventOutcomeSendChat,
 ) -> anyhow::Result<()> {
-    let mut message = data.template;
+    let reply_to = match &event_data.input_data {
+        EventInputData::Chat { message_id, .. } if data.reply => Some(message_id.clone()),
+        _ => None,
+    };
 
-    let user_name = event_data
-        .user
-        .map(|user| user.name.to_string())
-        .unwrap_or_else(|| "Anonymous".to_string());
+    let message = format_chat_template(data.template, event_data);
 
-    message = message.replace("$(user)", user_name.as_str());
+    if let Some(color) = data.announcement {
+        return twitch
+            .send_announcement(data.identity, &message, color)
+            .await;
+    }
 
-    match event_data.input_data {
-        EventInputData::Redeem {
-            reward_name,
-            cost,
-            user_input,
-            ..
-        } => {
-            message = message.replace("$(userInput)", user_input.as_str());
-            message = message.replace("$(rewardName)", reward_name.as_str());
-            message = message.replace("$(rewardCost)", cost.to_string().as_str());
-        }
-        EventInputData::Bits {
-            bits,
-            message: user_input,
-            ..
-        } => {
-            message = message.replace("$(userInput)", user_input.to_string().as_str());
-            message = message.replace("$(bits)", bits.to_string().as_str());
-        }
-        EventInputData::AdBreakBegin { duration_seconds } => {
-            message = message.replace("$(duration)", duration_seconds.to_string().as_str());
-        }
-        EventInputData::Subscription { tier, .. } => {
-            message = message.replace("$(tier)", format_subscription_tier(tier));
-        }
-        EventInputData::GiftedSubscription { total, tier, .. } => {
-            message = message.replace("$(tier)", format_subscription_tier(tier));
-            message = message.replace("$(total)", total.to_string().as_str());
-        }
-        EventInputData::ReSubscription {
-            cumulative_months,
-            duration_months,
-            message: user_input,
-            tier,
-            ..
-        } => {
-            message = message.replace("$(tier)", format_subscription_tier(tier));
-            message = message.replace("$(userInput)", user_input.as_str());
-            message = message.replace(
-                "$(cumulativeMonths)",
-                cumulative_months.to_string().as_str(),
-            );
-            message = message.replace("$(durationMonths)", duration_months.to_string().as_str());
+    send_chat_text_as(twitch, data.identity, &message, reply_to.as_deref()).await
+}
+
+/// Updates the paused and enabled state of rewards, when a duration
+/// is specified the previous state is restored in the background once
+/// elapsed
+async fn set_reward_state_outcome(
+    twitch: &Twitch,
+    event_data: EventData,
+    data: EventOutcomeSetRewardState,
+) -> anyhow::Result<()> {
+    let mut reward_ids = data.reward_ids;
+
+    if data.include_redeemed_reward {
+        if let EventInputData::Redeem { reward_id, .. } = event_data.input_data {
+            if !reward_ids.contains(&reward_id) {
+                reward_ids.push(reward_id);
+            }
         }
-        EventInputData::Chat {
-            message: user_input,
-            ..
-        } => {
-            message = message.replace("$(userInput)", user_input.as_str());
+    }
+
+    let state = RewardState {
+        paused: data.paused,
+        enabled: data.enabled,
+    };
+    let duration = Duration::from_secs(data.duration as u64);
+
+    // Update all the rewards, only successfully updated rewards are reverted
+    let mut last_error = None;
+
+    for reward_id in reward_ids {
+        if let Err(err) = set_reward_state(twitch, &reward_id, state, duration).await {
+            last_error = Some(err);
         }
-        EventInputData::Raid { viewers } => {
-            message = message.replace("$(viewers)", viewers.to_string().as_str());
+    }
+
+    match last_error {
+        Some(err) => Err(err.context("failed to update reward state")),
+        None => Ok(()),
+    }
+}
+
+/// Updates the state of a single reward, schedules restoring the state
+/// from before the update when the `duration` is not zero
+async fn set_reward_state(
+    twitch: &Twitch,
+    reward_id: &str,
+    state: RewardState,
+    duration: Duration,
+) -> anyhow::Result<()> {
+    let update = UpdateReward {
+        paused: state.paused,
+        enabled: state.enabled,
+        ..Default::default()
+    };
+
+    // Permanent change, nothing to restore
+    if duration.is_zero() {
+        twitch
+            .update_reward(RewardIdRef::from_str(reward_id), &update)
+            .await?;
+        twitch.cancel_reward_revert(reward_id, state);
+        return Ok(());
+    }
+
+    // Capture the state before the update to restore it afterwards
+    let reward = twitch
+        .get_reward(RewardIdRef::from_str(reward_id))
+        .await?
+        .context("unknown reward")?;
+
+    let previous = RewardState {
+        paused: state.paused.map(|_| reward.is_paused),
+        enabled: state.enabled.map(|_| reward.is_enabled),
+    };
+
+    twitch
+        .update_reward(RewardIdRef::from_str(reward_id), &update)
+        .await?;
+    twitch.schedule_reward_revert(reward_id.to_string(), previous, duration);
+
+    Ok(())
+}
+
+/// Puts the target user in a timeout
+async fn timeout_user_outcome(
+    twitch: &Twitch,
+    trigger: &EventTrigger,
+    event_data: EventData,
+    data: EventOutcomeTimeoutUser,
+) -> anyhow::Result<()> {
+    let user_id = resolve_target_user(twitch, trigger, &event_data, data.target).await?;
+    let reason = format_chat_template(data.reason, event_data);
+
+    twitch.timeout_user(&user_id, data.duration, &reason).await
+}
+
+/// Deletes the chat message that triggered the event
+async fn delete_message_outcome(twitch: &Twitch, event_data: EventData) -> anyhow::Result<()> {
+    let message_id = match event_data.input_data {
+        EventInputData::Chat { message_id, .. } => message_id,
+        _ => return Err(anyhow!("event was not triggered by a chat message")),
+    };
+
+    let user = event_data
+        .user
+        .context("chat message has no associated user")?;
+
+    twitch.delete_chat_message(&message_id, &user.id).await
+}
+
+/// Sends a shoutout to the target user
+async fn shoutout_outcome(
+    twitch: &Twitch,
+    trigger: &EventTrigger,
+    event_data: EventData,
+    data: EventOutcomeShoutout,
+) -> anyhow::Result<()> {
+    let user_id = resolve_target_user(twitch, trigger, &event_data, data.target).await?;
+    twitch.send_shoutout(&user_id).await
+}
+
+/// Sends an announcement to chat
+async fn announcement_outcome(
+    twitch: &Twitch,
+    event_data: EventData,
+    data: EventOutcomeAnnouncement,
+) -> anyhow::Result<()> {
+    let message = format_chat_template(data.template, event_data);
+    twitch
+        .send_announcement(data.identity, &message, data.color)
+        .await
+}
+
+/// Resolves the ID of the user targeted by an outcome
+async fn resolve_target_user(
+    twitch: &Twitch,
+    trigger: &EventTrigger,
+    event_data: &EventData,
+    target: EventOutcomeTargetUser,
+) -> anyhow::Result<UserId> {
+    match target {
+        EventOutcomeTargetUser::Trigger => event_data
+            .user
+            .as_ref()
+            .map(|user| user.id.clone())
+            .context("event has no triggering user"),
+        EventOutcomeTargetUser::Input => {
+            let login =
+                target_input_login(trigger, event_data).context("event has no user input")?;
+            let user = twitch
+                .get_user_by_login(&login)
+                .await?
+                .with_context(|| format!("user \"{login}\" not found"))?;
+
+            Ok(user.id)
         }
-        EventInputData::ShoutoutReceive { viewer_count } => {
-            message = message.replace("$(viewers)", viewer_count.to_string().as_str());
+    }
+}
+
+/// Gets the login name of the user named in the input of an event, from the
+/// first word of a redeem message or from the command arguments (The first
+/// user argument of the command or the first word when it has no user argument)
+fn target_input_login(trigger: &EventTrigger, event_data: &EventData) -> Option<String> {
+    let input = match &event_data.input_data {
+        EventInputData::Redeem { user_input, .. } => user_input.split_whitespace().next(),
+        EventInputData::Chat { args, .. } => {
+            // Arguments are in the declared order once parsed
+            let index = match trigger {
+                EventTrigger::Command { arguments, .. } => arguments
+                    .iter()
+                    .position(|argument| argument.kind == CommandArgumentKind::User)
+                    .unwrap_or_default(),
+                _ => 0,
+            };
+
+            args.get(index).map(String::as_str)
         }
-        EventInputData::None => {}
+        _ => None,
+    }?;
+
+    let login = input.trim_start_matches('@').to_lowercase();
+    if login.is_empty() {
+        return None;
     }
 
+    Some(login)
+}
+
+/// Renders a chat message `template` using the values from the `event_data`
+pub fn format_chat_template(template: String, event_data: EventData) -> String {
+    let ctx = TemplateContext::from_event_data(&event_data);
+    render_template(&template, &ctx)
+}
+
+/// Sends a chat message, messages longer than the chat message
+/// limit are split into multiple messages
+pub async fn send_chat_text(twitch: &Twitch, message: &str) -> anyhow::Result<()> {
+    send_chat_text_as(twitch, ChatIdentity::Broadcaster, message, None).await
+}
+
+/// Sends a chat message from the account of the `identity`, optionally
+/// replying to the `reply_to` message
+pub async fn send_chat_text_as(
+    twitch: &Twitch,
+    identity: ChatIdentity,
+    message: &str,
+    reply_to: Option<&MsgIdRef>,
+) -> anyhow::Result<()> {
     if message.len() < 500 {
-        twitch.send_chat_message(&message).await?;
+        twitch
+            .send_chat_message_as(identity, message, reply_to)
+            .await?;
     } else {
         let mut chars = message.chars();
 
@@ -139,7 +351,9 @@ async fn send_chat_message(
                 break;
             }
 
-            twitch.send_chat_message(&message).await?;
+            twitch
+                .send_chat_message_as(identity, &message, reply_to)
+                .await?;
         }
     }
 
@@ -246,7 +460,7 @@ async fn throw_channel_emotes_outcome(
     create_throwable_message(items, data.amount, None)
 }
 
-fn get_event_data_input_amount(event_data: &EventData) -> Option<i64> {
+fn get_event_data_input_amount(trigger: &EventTrigger, event_data: &EventData) -> Option<i64> {
     // Compute amount derived from input
     match &event_data.input_data {
         EventInputData::Bits { bits, .. } => Some(*bits),
@@ -255,8 +469,32 @@ fn get_event_data_input_amount(event_data: &EventData) -> Option<i64> {
         EventInputData::ReSubscription {
             cumulative_months, ..
         } => Some(*cumulative_months),
-        EventInputData::Chat { cheer, .. } => cheer.map(|value| value as i64),
+        EventInputData::Chat {
+            cheer,
+            captures,
+            args,
+            ..
+        } => {
+            // Use the first integer argument for commands
+            let argument_amount = match trigger {
+                EventTrigger::Command { arguments, .. } => arguments
+                    .iter()
+                    .position(|argument| argument.kind == CommandArgumentKind::Integer)
+                    .and_then(|index| args.get(index))
+                    .and_then(|value| value.parse().ok()),
+                _ => None,
+            };
+
+            captures
+                .get("amount")
+                .and_then(|value| value.parse().ok())
+                .or(argument_amount)
+                .or(cheer.map(|value| value as i64))
+        }
         EventInputData::Raid { viewers } => Some(*viewers),
+        EventInputData::HypeTrain { level, .. } => Some(*level),
+        // Whole currency units donated (i.e $5.50 USD throws 5)
+        EventInputData::CharityDonation { amount, .. } => Some(amount.floor() as i64),
 
         _ => None,
     }
@@ -328,12 +566,17 @@ fn create_throwable_message(
 // Produce a throwable message
 async fn throwable_outcome(
     db: &DatabaseConnection,
+    trigger: &EventTrigger,
     event_data: EventData,
     data: EventOutcomeThrowable,
 ) -> anyhow::Result<EventMessage> {
     let items = resolve_items(db, &data.throwable_ids).await?;
 
-    create_throwable_message(items, data.amount, get_event_data_input_amount(&event_data))
+    create_throwable_message(
+        items,
+        data.amount,
+        get_event_data_input_amount(trigger, &event_data),
+    )
 }
 
 /// Produce a hotkey trigger message
@@ -471,3 +714,209 @@ pub fn create_default_bit_throwable(amount: i64) -> ItemsWithSounds {
         sounds: impact_sounds,
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{resolve_random_outcome, target_input_login};
+    use crate::{
+        database::entity::events::{
+            CommandArgument, CommandArgumentKind, EventOutcome, EventOutcomeRandom,
+            EventOutcomeRandomChoice, EventOutcomeSendChat, EventTrigger,
+        },
+        events::matching::{EventData, EventInputData},
+        twitch::models::ChatIdentity,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::collections::HashMap;
+    use twitch_api::types::MsgId;
+
+    fn chat_outcome(template: &str) -> EventOutcome {
+        EventOutcome::SendChatMessage(EventOutcomeSendChat {
+            template: template.to_string(),
+            ..Default::default()
+        })
+    }
+
+    fn random_outcome(choices: Vec<(u32, EventOutcome)>) -> EventOutcome {
+        EventOutcome::Random(EventOutcomeRandom {
+            choices: choices
+                .into_iter()
+                .map(|(weight, outcome)| EventOutcomeRandomChoice { weight, outcome })
+                .collect(),
+        })
+    }
+
+    /// Tests that random choices are picked based on their weights
+    #[test]
+    fn test_random_outcome_weights() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let outcome = random_outcome(vec![
+            (1, chat_outcome("a")),
+            (0, chat_outcome("b")),
+            (3, chat_outcome("c")),
+        ]);
+
+        let mut counts = [0; 3];
+
+        for _ in 0..1000 {
+            let mut choices = Vec::new();
+            let picked = resolve_random_outcome(outcome.clone(), &mut rng, &mut choices).unwrap();
+
+            assert_eq!(choices.len(), 1);
+            let index = choices[0];
+            counts[index] += 1;
+
+            // Recorded choice should be the picked outcome
+            let expected = ["a", "b", "c"][index];
+            assert_eq!(picked, chat_outcome(expected));
+        }
+
+        // Zero weight choices are never picked
+        assert_eq!(counts[1], 0);
+        // Heavier choices are picked more often
+        assert!(counts[2] > counts[0] * 2);
+    }
+
+    /// Tests that the same seed picks the same choices
+    #[test]
+    fn test_random_outcome_seeded() {
+        let outcome = random_outcome(vec![
+            (1, chat_outcome("a")),
+            (1, chat_outcome("b")),
+            (1, chat_outcome("c")),
+        ]);
+
+        let pick = |seed: u64| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..20)
+                .map(|_| {
+                    let mut choices = Vec::new();
+                    resolve_random_outcome(outcome.clone(), &mut rng, &mut choices).unwrap();
+                    choices[0]
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(pick(42), pick(42));
+    }
+
+    /// Tests that nested random outcomes are resolved and each choice is recorded
+    #[test]
+    fn test_random_outcome_nested() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let outcome = random_outcome(vec![
+            (0, chat_outcome("a")),
+            (
+                1,
+                random_outcome(vec![(0, chat_outcome("b")), (1, chat_outcome("c"))]),
+            ),
+        ]);
+
+        let mut choices = Vec::new();
+        let picked = resolve_random_outcome(outcome, &mut rng, &mut choices).unwrap();
+
+        assert_eq!(picked, chat_outcome("c"));
+        assert_eq!(choices, vec![1, 1]);
+    }
+
+    /// Tests that non random outcomes are unchanged and random outcomes
+    /// without any pickable choices are errors
+    #[test]
+    fn test_random_outcome_invalid() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let mut choices = Vec::new();
+        let picked = resolve_random_outcome(chat_outcome("a"), &mut rng, &mut choices).unwrap();
+        assert_eq!(picked, chat_outcome("a"));
+        assert!(choices.is_empty());
+
+        let outcome = random_outcome(vec![]);
+        assert!(resolve_random_outcome(outcome, &mut rng, &mut choices).is_err());
+
+        let outcome = random_outcome(vec![(0, chat_outcome("a"))]);
+        assert!(resolve_random_outcome(outcome, &mut rng, &mut choices).is_err());
+    }
+
+    /// Tests getting the targeted user login from the event input
+    #[test]
+    fn test_target_input_login() {
+        let redeem = |user_input: &str| EventData {
+            input_data: EventInputData::Redeem {
+                redemption_id: "redemption".to_string(),
+                reward_id: "reward".to_string(),
+                reward_name: "Timeout a friend".to_string(),
+                cost: 100,
+                user_input: user_input.to_string(),
+            },
+            ..Default::default()
+        };
+
+        let trigger = EventTrigger::Follow;
+
+        assert_eq!(
+            target_input_login(&trigger, &redeem("@SomeUser because reasons")).as_deref(),
+            Some("someuser")
+        );
+        assert_eq!(target_input_login(&trigger, &redeem("   ")), None);
+        assert_eq!(target_input_login(&trigger, &redeem("@")), None);
+        assert_eq!(target_input_login(&trigger, &EventData::default()), None);
+
+        // Parsed command arguments in the declared order
+        let chat = |args: &[&str]| EventData {
+            input_data: EventInputData::Chat {
+                message_id: MsgId::from_static("message"),
+                message: "!timeout".to_string(),
+                fragments: Vec::new(),
+                cheer: None,
+                captures: HashMap::new(),
+                args: args.iter().map(|arg| arg.to_string()).collect(),
+            },
+            ..Default::default()
+        };
+        let command = |arguments: &[CommandArgumentKind]| EventTrigger::Command {
+            message: "!timeout".to_string(),
+            aliases: Vec::new(),
+            arguments: arguments
+                .iter()
+                .map(|kind| CommandArgument {
+                    name: "argument".to_string(),
+                    kind: *kind,
+                    optional: false,
+                })
+                .collect(),
+            reply_usage: false,
+        };
+
+        // User argument after an integer argument
+        let trigger = command(&[CommandArgumentKind::Integer, CommandArgumentKind::User]);
+        assert_eq!(
+            target_input_login(&trigger, &chat(&["60", "Friend"])).as_deref(),
+            Some("friend")
+        );
+
+        // First word when no user argument is declared
+        let trigger = command(&[]);
+        assert_eq!(
+            target_input_login(&trigger, &chat(&["@Friend", "60"])).as_deref(),
+            Some("friend")
+        );
+    }
+
+    /// Tests that chat outcomes stored before the reply, announcement and
+    /// identity options were added keep sending plain broadcaster messages
+    #[test]
+    fn test_send_chat_outcome_defaults() {
+        let outcome: EventOutcome =
+            serde_json::from_str(r#"{"type":"SendChatMessage","template":"Hello"}"#).unwrap();
+
+        assert_eq!(
+            outcome,
+            EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                template: "Hello".to_string(),
+                reply: false,
+                announcement: None,
+                identity: ChatIdentity::Broadcaster,
+            })
+        );
+    }
+}
@@ -0,0 +1,59 @@
+//! # Pattern
+//!
+//! Compiled regex cache for user provided patterns (Chat pattern triggers
+//! and message matches conditions), patterns are matched against every
+//! chat message so they are compiled once and reused
+
+use regex::Regex;
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+};
+
+/// Maximum number of compiled patterns kept, the cache is cleared
+/// when full (Only reached when patterns are frequently edited)
+const MAX_CACHED_PATTERNS: usize = 256;
+
+static PATTERNS: LazyLock<Mutex<HashMap<String, Regex>>> = LazyLock::new(Default::default);
+
+/// Gets the compiled regex for `pattern`, compiling and caching
+/// the pattern if it has not been compiled yet
+pub fn compile_pattern(pattern: &str) -> Result<Regex, regex::Error> {
+    let patterns = &mut *PATTERNS.lock().expect("pattern cache lock poisoned");
+
+    if let Some(regex) = patterns.get(pattern) {
+        return Ok(regex.clone());
+    }
+
+    let regex = Regex::new(pattern)?;
+
+    if patterns.len() >= MAX_CACHED_PATTERNS {
+        patterns.clear();
+    }
+
+    patterns.insert(pattern.to_string(), regex.clone());
+
+    Ok(regex)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{compile_pattern, PATTERNS};
+
+    /// Tests that valid patterns are cached and invalid patterns are rejected
+    #[test]
+    fn test_compile_pattern() {
+        let regex = compile_pattern(r"^!cached (?P<amount>\d+)$").unwrap();
+        assert!(regex.is_match("!cached 5"));
+        assert!(PATTERNS
+            .lock()
+            .unwrap()
+            .contains_key(r"^!cached (?P<amount>\d+)$"));
+
+        let regex = compile_pattern(r"^!cached (?P<amount>\d+)$").unwrap();
+        assert!(regex.is_match("!cached 10"));
+
+        assert!(compile_pattern(r"^!invalid (").is_err());
+        assert!(!PATTERNS.lock().unwrap().contains_key(r"^!invalid ("));
+    }
+}
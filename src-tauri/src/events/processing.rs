@@ -1,17 +1,30 @@
 use crate::{
     database::entity::{
+        app_data::AppDataModel,
         event_executions::{CreateEventExecution, EventExecutionMetadata, EventExecutionModel},
-        events::EventModel,
-        shared::MinimumRequireRole,
+        events::{
+            command_usage, CommandArgument, CommandArgumentKind, EventCondition, EventModel,
+            EventOutcome, EventOutcomeRunEvent, EventTrigger, EventTriggerType,
+        },
+        items::ItemModel,
+        shared::{MinimumRequireRole, RoleRequirements},
+        stream_sessions::StreamSessionModel,
     },
     events::{
+        feedback::{send_feedback_reply, send_usage_reply},
+        groups::handle_event_group_command,
         matching::{
-            match_ad_break_event, match_chat_event, match_cheer_bits_event, match_follow_event,
-            match_gifted_subscription_event, match_raid_event, match_re_subscription_event,
-            match_redeem_event, match_shoutout_receive_event, match_subscription_event, EventData,
-            EventMatchingData,
+            match_ad_break_event, match_charity_donation_event, match_chat_event,
+            match_cheer_bits_event, match_follow_event, match_gifted_subscription_event,
+            match_hype_train_begin_event, match_hype_train_end_event,
+            match_hype_train_progress_event, match_poll_event, match_prediction_event,
+            match_raid_event, match_re_subscription_event, match_redeem_event,
+            match_shoutout_receive_event, match_stream_offline_event, match_stream_online_event,
+            match_subscription_event, EventData, EventInputData, EventMatchingData,
         },
-        outcome::produce_outcome_message,
+        outcome::{produce_outcome_message, resolve_random_outcome},
+        pattern::compile_pattern,
+        queue::{OutcomeKind, OutcomeQueue},
         EventMessage,
     },
     twitch::{
@@ -19,19 +32,52 @@ use crate::{
         models::{TwitchEvent, TwitchEventUser},
     },
 };
-use anyhow::Context;
-use chrono::TimeDelta;
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, TimeDelta};
 use futures::{future::BoxFuture, stream::FuturesUnordered};
 use log::{debug, error};
 use sea_orm::{prelude::DateTimeUtc, sqlx::types::chrono::Utc, DatabaseConnection};
+use serde::Serialize;
 use std::time::Duration;
+use tauri::AppHandle;
 use tokio::{sync::broadcast, try_join};
-use twitch_api::types::UserId;
+use twitch_api::{
+    helix::points::CustomRewardRedemptionStatus,
+    types::{RedemptionIdRef, RewardIdRef, SubscriptionTier, UserId},
+};
+use uuid::Uuid;
+
+/// Maximum number of events that can be chained together
+/// through [EventOutcome::RunEvent] outcomes
+const MAX_EVENT_CHAIN_DEPTH: usize = 8;
+
+/// Options for executing an event
+#[derive(Debug, Clone)]
+pub struct ExecuteEventOptions {
+    /// Whether to check the user has the required role
+    pub check_role: bool,
+    /// Whether to check the event cooldown has elapsed
+    pub check_cooldown: bool,
+    /// IDs of the events that chained into this event
+    pub chain: Vec<Uuid>,
+}
+
+impl Default for ExecuteEventOptions {
+    fn default() -> Self {
+        Self {
+            check_role: true,
+            check_cooldown: true,
+            chain: Vec::new(),
+        }
+    }
+}
 
 pub async fn process_twitch_events(
+    app: AppHandle,
     db: DatabaseConnection,
     twitch: Twitch,
     event_sender: broadcast::Sender<EventMessage>,
+    outcome_queue: OutcomeQueue,
 
     mut twitch_event_rx: broadcast::Receiver<TwitchEvent>,
 ) {
@@ -39,12 +85,15 @@ pub async fn process_twitch_events(
         debug!("twitch event received: {:?}", event);
 
         tokio::spawn({
+            let app = app.clone();
             let db = db.clone();
             let twitch = twitch.clone();
             let event_sender = event_sender.clone();
+            let outcome_queue = outcome_queue.clone();
 
             async move {
-                let result = process_twitch_event(db, twitch, event_sender, event).await;
+                let result =
+                    process_twitch_event(app, db, twitch, event_sender, outcome_queue, event).await;
 
                 if let Err(err) = result {
                     debug!("failed to process twitch event: {err:?}",);
@@ -55,23 +104,69 @@ pub async fn process_twitch_events(
 }
 
 async fn process_twitch_event(
+    app: AppHandle,
     db: DatabaseConnection,
     twitch: Twitch,
     event_sender: broadcast::Sender<EventMessage>,
+    outcome_queue: OutcomeQueue,
     event: TwitchEvent,
 ) -> anyhow::Result<()> {
     let match_data: EventMatchingData = match event {
         // Matchable events
         TwitchEvent::Redeem(event) => match_redeem_event(&db, event).await?,
         TwitchEvent::CheerBits(event) => match_cheer_bits_event(&db, event).await?,
-        TwitchEvent::Follow(event) => match_follow_event(&db, event).await?,
-        TwitchEvent::Sub(event) => match_subscription_event(&db, event).await?,
+        TwitchEvent::Follow(event) => {
+            twitch.clear_cached_follower(&event.user_id);
+            match_follow_event(&db, event).await?
+        }
+        TwitchEvent::Sub(event) => {
+            twitch.clear_cached_subscription(&event.user_id);
+            match_subscription_event(&db, event).await?
+        }
         TwitchEvent::GiftSub(event) => match_gifted_subscription_event(&db, event).await?,
-        TwitchEvent::ResubMsg(event) => match_re_subscription_event(&db, event).await?,
-        TwitchEvent::ChatMsg(event) => match_chat_event(&db, event).await?,
+        TwitchEvent::ResubMsg(event) => {
+            twitch.clear_cached_subscription(&event.user_id);
+            match_re_subscription_event(&db, event).await?
+        }
+        TwitchEvent::ChatMsg(event) => {
+            if let Err(err) = handle_event_group_command(&app, &db, &twitch, &event).await {
+                error!("failed to handle event group command: {err:?}");
+            }
+
+            match_chat_event(&db, event).await?
+        }
         TwitchEvent::Raid(event) => match_raid_event(&db, event).await?,
         TwitchEvent::AdBreakBegin(event) => match_ad_break_event(&db, event).await?,
         TwitchEvent::ShoutoutReceive(event) => match_shoutout_receive_event(&db, event).await?,
+        TwitchEvent::HypeTrainBegin(event) => match_hype_train_begin_event(&db, event).await?,
+        TwitchEvent::HypeTrainProgress(event) => {
+            match_hype_train_progress_event(&db, event).await?
+        }
+        TwitchEvent::HypeTrainEnd(event) => match_hype_train_end_event(&db, event).await?,
+        TwitchEvent::StreamOnline(event) => match_stream_online_event(&db, event).await?,
+        TwitchEvent::StreamOffline => match_stream_offline_event(&db).await?,
+        TwitchEvent::CharityDonation(event) => match_charity_donation_event(&db, event).await?,
+        TwitchEvent::PollBegin(event) => {
+            match_poll_event(&db, EventTriggerType::PollBegin, event).await?
+        }
+        TwitchEvent::PollProgress(event) => {
+            match_poll_event(&db, EventTriggerType::PollProgress, event).await?
+        }
+        TwitchEvent::PollEnd(event) => {
+            match_poll_event(&db, EventTriggerType::PollEnd, event).await?
+        }
+        TwitchEvent::PredictionBegin(event) => {
+            match_prediction_event(&db, EventTriggerType::PredictionBegin, event).await?
+        }
+        TwitchEvent::PredictionProgress(event) => {
+            match_prediction_event(&db, EventTriggerType::PredictionProgress, event).await?
+        }
+        TwitchEvent::PredictionLock(event) => {
+            match_prediction_event(&db, EventTriggerType::PredictionLock, event).await?
+        }
+        TwitchEvent::PredictionEnd(event) => {
+            match_prediction_event(&db, EventTriggerType::PredictionEnd, event).await?
+        }
 
         // Internal events
         TwitchEvent::ModeratorsChanged => {
@@ -99,183 +194,842 @@ async fn process_twitch_event(
     let event_futures =
         match_data
             .events
-            .into_iter()
-            .map(|event| -> BoxFuture<'_, anyhow::Result<()>> {
-                Box::pin(execute_event(
-                    &db,
-                    &twitch,
-                    &event_sender,
-                    event,
-                    match_data.event_data.clone(),
-                ))
+            .iter()
+            .map(|event| -> BoxFuture<'_, (&EventTrigger, bool)> {
+                Box::pin(async {
+                    let result = execute_event(
+                        &db,
+                        &twitch,
+                        &event_sender,
+                        &outcome_queue,
+                        event.clone(),
+                        match_data.event_data_for(event),
+                    )
+                    .await;
+
+                    let success = result.unwrap_or_else(|err| {
+                        error!("error while executing event outcome: {err:?}");
+                        false
+                    });
+
+                    (&event.trigger, success)
+                })
             });
 
     let mut futures =
-        event_futures.collect::<FuturesUnordered<BoxFuture<'_, anyhow::Result<()>>>>();
+        event_futures.collect::<FuturesUnordered<BoxFuture<'_, (&EventTrigger, bool)>>>();
 
     use futures::StreamExt;
 
+    let mut results = Vec::with_capacity(match_data.events.len());
     while let Some(result) = futures.next().await {
-        if let Err(err) = result {
-            error!("error while executing event outcome: {err:?}");
-        }
+        results.push(result);
     }
 
+    // Redemptions are only updated once all the matched events have finished
+    update_redemption(&twitch, &match_data.event_data, &results).await;
+
     Ok(())
 }
 
-pub fn is_cooldown_elapsed(
+/// Determines the time remaining on the `cooldown` after an execution at
+/// `execution_time`, produces [None] if the cooldown has elapsed
+pub fn get_cooldown_remaining(
     execution_time: DateTimeUtc,
     current_time: DateTimeUtc,
     cooldown: u32,
-) -> anyhow::Result<bool> {
+) -> anyhow::Result<Option<TimeDelta>> {
     let cooldown_end_time = execution_time
         .checked_add_signed(TimeDelta::milliseconds(cooldown as i64))
         .context("cooldown finishes too far in the future to compute")?;
 
-    Ok(current_time > cooldown_end_time)
+    if current_time > cooldown_end_time {
+        return Ok(None);
+    }
+
+    Ok(Some(cooldown_end_time - current_time))
 }
 
-pub async fn is_event_cooldown_elapsed(
+/// Determines the time remaining on the cooldown of the `event` for the
+/// `user`, produces [None] if the cooldown has elapsed
+pub async fn get_event_cooldown_remaining(
     db: &DatabaseConnection,
     event: &EventModel,
     user: Option<&TwitchEventUser>,
     current_time: DateTimeUtc,
-) -> anyhow::Result<bool> {
+) -> anyhow::Result<Option<TimeDelta>> {
     let cooldown = &event.cooldown;
 
     // No cooldown enabled
     if !cooldown.enabled {
-        return Ok(true);
+        return Ok(None);
     }
 
     // Handle global cooldown (Check last execution)
     if !cooldown.per_user {
         let last_execution = event
-            .last_execution(db, 0)
+            .last_execution(db)
             .await
             .context("failed to request last execution for event")?;
 
         let last_execution = match last_execution {
             Some(value) => value,
-            None => return Ok(true),
+            None => return Ok(None),
         };
 
-        return is_cooldown_elapsed(last_execution.created_at, current_time, cooldown.duration);
+        return get_cooldown_remaining(last_execution.created_at, current_time, cooldown.duration);
     }
 
     let user = match user {
         Some(user) => user,
         // Anonymous users bypass the cooldown
-        None => return Ok(true),
+        None => return Ok(None),
     };
 
-    let mut offset = 0;
+    let last_execution = event
+        .last_user_execution(db, user.id.as_str())
+        .await
+        .context("failed to request last user execution for event")?;
 
-    loop {
-        let last_execution = event
-            .last_execution(db, offset)
-            .await
-            .context("failed to request last execution for event")?;
+    let last_execution = match last_execution {
+        Some(value) => value,
+        None => return Ok(None),
+    };
 
-        // Cooldown elapsed if no more to check
-        let last_execution = match last_execution {
-            Some(value) => value,
-            None => return Ok(true),
-        };
+    get_cooldown_remaining(last_execution.created_at, current_time, cooldown.duration)
+}
 
-        // Check if the execution has elapsed the cooldown
-        let last_execution_elapsed =
-            is_cooldown_elapsed(last_execution.created_at, current_time, cooldown.duration)?;
-
-        // Execution was not from the target user
-        if last_execution
-            .metadata
-            .user
-            .is_none_or(|event_user| event_user.id != user.id)
-        {
-            offset += 1;
-
-            // If the execution had elapsed the cooldown we can treat the rest as elapsed too (We can skip checking the rest)
-            if last_execution_elapsed {
-                return Ok(true);
-            }
+/// Determines the number of remaining uses of the `event` for the `user` based
+/// on the usage limits of the event, produces [None] when none of the usage
+/// limits apply
+///
+/// Per stream limits only apply while live and per user limits don't
+/// apply to anonymous users
+pub async fn get_event_remaining_uses(
+    db: &DatabaseConnection,
+    event: &EventModel,
+    user: Option<&TwitchEventUser>,
+    stream_session_id: Option<Uuid>,
+    current_time: DateTimeUtc,
+) -> anyhow::Result<Option<u32>> {
+    let limits = &event.usage_limits;
+    let user_id = user.map(|user| user.id.as_str());
+
+    let mut remaining_uses = None;
+
+    if let Some(stream_session_id) = stream_session_id {
+        if let Some(max) = limits.per_stream {
+            let count = event
+                .count_executions(db, Some(stream_session_id), None, None)
+                .await
+                .context("failed to count stream executions")?;
+            remaining_uses = min_remaining_uses(remaining_uses, max, count);
+        }
 
-            continue;
+        if let (Some(max), Some(user_id)) = (limits.per_user_per_stream, user_id) {
+            let count = event
+                .count_executions(db, Some(stream_session_id), Some(user_id), None)
+                .await
+                .context("failed to count user stream executions")?;
+            remaining_uses = min_remaining_uses(remaining_uses, max, count);
         }
+    }
 
-        // Found an execution
-        return Ok(last_execution_elapsed);
+    if let Some(window) = &limits.window {
+        let window_user_id = if window.per_user { user_id } else { None };
+
+        // Anonymous users bypass per user limits
+        if !window.per_user || window_user_id.is_some() {
+            let since = current_time
+                .checked_sub_signed(TimeDelta::seconds(window.duration as i64))
+                .context("usage window starts too far in the past to compute")?;
+            let count = event
+                .count_executions(db, None, window_user_id, Some(since))
+                .await
+                .context("failed to count window executions")?;
+            remaining_uses = min_remaining_uses(remaining_uses, window.max, count);
+        }
     }
+
+    Ok(remaining_uses)
+}
+
+/// Combines the `remaining_uses` with the remaining uses of a limit
+/// of `max` uses with `count` existing uses, keeping the lowest
+fn min_remaining_uses(remaining_uses: Option<u32>, max: u32, count: u64) -> Option<u32> {
+    let limit_remaining = (max as u64).saturating_sub(count) as u32;
+    Some(remaining_uses.map_or(limit_remaining, |value| value.min(limit_remaining)))
+}
+
+/// Lock held while checking the cooldown and usage limits of an event and storing
+/// its execution, prevents concurrent triggers from all passing the checks before
+/// any of their executions are stored
+static EXECUTION_RESERVE_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Result of attempting to reserve an execution of an event
+#[derive(Debug)]
+enum ExecutionReservation {
+    /// Execution was stored
+    Reserved {
+        /// Stored execution
+        execution: EventExecutionModel,
+        /// Remaining uses of the event after the execution
+        remaining_uses: Option<u32>,
+    },
+    /// Event is on cooldown for the remaining duration
+    Cooldown(TimeDelta),
+    /// Usage limit of the event has been reached
+    UsageLimitReached,
 }
 
+/// Checks the cooldown (When `check_cooldown` is set) and usage limits of the
+/// `event` storing the execution if they pass
+///
+/// Executions are stored before the outcomes are produced so that they count
+/// towards the cooldown and limits for triggers received while the outcomes
+/// are still being produced
+async fn reserve_event_execution(
+    db: &DatabaseConnection,
+    event: &EventModel,
+    event_data: &EventData,
+    check_cooldown: bool,
+) -> anyhow::Result<ExecutionReservation> {
+    let _guard = EXECUTION_RESERVE_LOCK.lock().await;
+    let current_time = Utc::now();
+
+    if check_cooldown {
+        let remaining =
+            get_event_cooldown_remaining(db, event, event_data.user.as_ref(), current_time).await?;
+
+        if let Some(remaining) = remaining {
+            return Ok(ExecutionReservation::Cooldown(remaining));
+        }
+    }
+
+    let stream_session_id = StreamSessionModel::get_current(db)
+        .await
+        .context("failed to get current stream session")?
+        .map(|session| session.id);
+
+    let remaining_uses = get_event_remaining_uses(
+        db,
+        event,
+        event_data.user.as_ref(),
+        stream_session_id,
+        current_time,
+    )
+    .await?;
+
+    if remaining_uses == Some(0) {
+        return Ok(ExecutionReservation::UsageLimitReached);
+    }
+
+    let metadata = EventExecutionMetadata {
+        user: event_data.user.clone(),
+        data: vec![(
+            "input_data".to_string(),
+            serde_json::to_value(&event_data.input_data)
+                .context("failed to serialize event metadata")?,
+        )],
+    };
+
+    let execution = EventExecutionModel::create(
+        db,
+        CreateEventExecution {
+            event_id: event.id,
+            created_at: current_time,
+            metadata,
+            stream_session_id,
+        },
+    )
+    .await
+    .context("failed to store event execution")?;
+
+    Ok(ExecutionReservation::Reserved {
+        execution,
+        // Remaining uses after this execution
+        remaining_uses: remaining_uses.map(|value| value - 1),
+    })
+}
+
+/// Executes the `event`, produces whether the event was executed
+/// and all of its outcomes succeeded
 pub async fn execute_event(
     db: &DatabaseConnection,
     twitch: &Twitch,
 
     event_sender: &broadcast::Sender<EventMessage>,
+    outcome_queue: &OutcomeQueue,
     event: EventModel,
     event_data: EventData,
-) -> anyhow::Result<()> {
-    // Ensure required role is present
-    if !has_required_role(
+) -> anyhow::Result<bool> {
+    execute_event_with_options(
+        db,
         twitch,
-        event_data.user.as_ref().map(|value| value.id.clone()),
-        &event.require_role,
+        event_sender,
+        outcome_queue,
+        event,
+        event_data,
+        ExecuteEventOptions::default(),
     )
     .await
+}
+
+pub async fn execute_event_with_options(
+    db: &DatabaseConnection,
+    twitch: &Twitch,
+
+    event_sender: &broadcast::Sender<EventMessage>,
+    outcome_queue: &OutcomeQueue,
+    event: EventModel,
+    mut event_data: EventData,
+    options: ExecuteEventOptions,
+) -> anyhow::Result<bool> {
+    // Ensure the event chain isn't looping
+    if !is_event_chain_allowed(&options.chain, event.id) {
+        error!(
+            "skipping event: event chain cycle or maximum depth reached ({:?} -> {})",
+            options.chain, event.id
+        );
+        return Ok(false);
+    }
+
+    // Ensure all conditions are met
+    if !is_conditions_met(&event.conditions.0, &event_data) {
+        debug!("skipping event: conditions not met");
+        return Ok(false);
+    }
+
+    // Ensure required role is present
+    if options.check_role
+        && !has_required_role(
+            twitch,
+            event_data.user.as_ref().map(|value| value.id.clone()),
+            &event.require_role,
+            &event.role_requirements,
+        )
+        .await
     {
         debug!("skipping event: missing required role");
-        return Ok(());
+
+        send_feedback_reply(
+            twitch,
+            &event,
+            event.feedback.role_reply.as_deref(),
+            &event_data,
+            None,
+        )
+        .await?;
+
+        return Ok(false);
     }
 
-    let current_time = Utc::now();
+    // Ensure the command arguments are valid
+    if let (
+        EventTrigger::Command {
+            message,
+            arguments,
+            reply_usage,
+            ..
+        },
+        EventInputData::Chat { args, .. },
+    ) = (&event.trigger, &mut event_data.input_data)
+    {
+        match parse_command_arguments(db, arguments, args).await? {
+            Some(values) => *args = values,
+            None => {
+                debug!("skipping event: invalid command arguments");
 
-    // Ensure cooldown is not active
-    if !is_event_cooldown_elapsed(db, &event, event_data.user.as_ref(), current_time).await? {
-        debug!("skipping event: cooldown");
-        return Ok(());
+                if *reply_usage {
+                    let usage = command_usage(message, arguments);
+                    send_usage_reply(twitch, &event, &usage, &event_data).await?;
+                }
+
+                return Ok(false);
+            }
+        }
     }
 
-    // Create metadata for storage
-    let metadata = EventExecutionMetadata {
-        user: event_data.user.clone(),
-        data: vec![(
-            "input_data".to_string(),
-            serde_json::to_value(&event_data.input_data)
-                .context("failed to serialize event metadata")?,
-        )],
+    // Ensure cooldown and usage limits allow the execution
+    let reservation =
+        reserve_event_execution(db, &event, &event_data, options.check_cooldown).await?;
+
+    let execution = match reservation {
+        ExecutionReservation::Reserved {
+            execution,
+            remaining_uses,
+        } => {
+            event_data.remaining_uses = remaining_uses;
+            execution
+        }
+        ExecutionReservation::Cooldown(remaining) => {
+            debug!("skipping event: cooldown");
+
+            send_feedback_reply(
+                twitch,
+                &event,
+                event.feedback.cooldown_reply.as_deref(),
+                &event_data,
+                Some(remaining),
+            )
+            .await?;
+
+            return Ok(false);
+        }
+        ExecutionReservation::UsageLimitReached => {
+            debug!("skipping event: usage limit reached");
+            return Ok(false);
+        }
     };
 
     // Wait for outcome delay
     tokio::time::sleep(Duration::from_millis(event.outcome_delay as u64)).await;
 
-    let event_id = event.id;
+    // Produce the event outcomes
+    let result = execute_event_outcomes(
+        db,
+        twitch,
+        event_sender,
+        outcome_queue,
+        &event,
+        &event_data,
+        &options.chain,
+    )
+    .await;
 
-    // Produce outcome message and send it
-    if let Some(msg) = produce_outcome_message(db, twitch, event, event_data).await? {
-        _ = event_sender.send(msg);
+    // Failing to store the results doesn't change the outcome of the execution
+    if let Err(err) = store_execution_results(db, execution, &result).await {
+        error!("{err:?}");
     }
 
-    // Store event execution
-    EventExecutionModel::create(
-        db,
-        CreateEventExecution {
-            event_id,
-            created_at: current_time,
-            metadata,
-        },
-    )
-    .await
-    .context("failed to store last event execution")?;
+    Ok(result.errors.is_empty())
+}
+
+/// Stores the failed outcome steps and random choices from the `result`
+/// of the execution
+async fn store_execution_results(
+    db: &DatabaseConnection,
+    execution: EventExecutionModel,
+    result: &EventOutcomesResult,
+) -> anyhow::Result<()> {
+    let mut metadata = execution.metadata.clone();
+
+    // Record any failed outcome steps
+    if !result.errors.is_empty() {
+        metadata.data.push((
+            "outcome_errors".to_string(),
+            serde_json::to_value(&result.errors).context("failed to serialize outcome errors")?,
+        ));
+    }
+
+    // Record the choices picked by random outcomes
+    if !result.random_choices.is_empty() {
+        metadata.data.push((
+            "random_choices".to_string(),
+            serde_json::to_value(&result.random_choices)
+                .context("failed to serialize random choices")?,
+        ));
+    }
+
+    // Store the outcome results with the execution
+    if metadata != execution.metadata {
+        execution
+            .set_metadata(db, metadata)
+            .await
+            .context("failed to store event execution metadata")?;
+    }
+
+    Ok(())
+}
+
+/// Updates the status of the redemption that triggered the matched events based
+/// on the `results` of every event, the redemption is fulfilled when any of the
+/// events succeeded otherwise the redemption is cancelled refunding the points
+async fn update_redemption(
+    twitch: &Twitch,
+    event_data: &EventData,
+    results: &[(&EventTrigger, bool)],
+) {
+    let status = match redemption_status(results) {
+        Some(value) => value,
+        None => return,
+    };
+
+    let (reward_id, redemption_id) = match &event_data.input_data {
+        EventInputData::Redeem {
+            reward_id,
+            redemption_id,
+            ..
+        } => (reward_id, redemption_id),
+        _ => return,
+    };
+
+    if let Err(err) = twitch
+        .update_redemption_status(
+            RewardIdRef::from_str(reward_id),
+            RedemptionIdRef::from_str(redemption_id),
+            status,
+        )
+        .await
+    {
+        error!("failed to update redemption status: {err:?}");
+    }
+}
+
+/// Status to update a redemption to based on the `results` of the events it
+/// triggered and the redemption options of the event triggers, [None] when
+/// the redemption should be left unfulfilled
+///
+/// When any event succeeded the redemption is fulfilled if one of the successful
+/// events fulfils redemptions, otherwise it is cancelled if one of the events
+/// refunds redemptions
+fn redemption_status(results: &[(&EventTrigger, bool)]) -> Option<CustomRewardRedemptionStatus> {
+    let succeeded = results.iter().any(|(_, success)| *success);
+
+    let matches_option = |trigger: &EventTrigger| match trigger {
+        EventTrigger::Redeem {
+            fulfil_redemption,
+            refund_redemption,
+            ..
+        } => {
+            if succeeded {
+                *fulfil_redemption
+            } else {
+                *refund_redemption
+            }
+        }
+        _ => false,
+    };
+
+    let has_option = results
+        .iter()
+        .any(|(trigger, success)| *success == succeeded && matches_option(trigger));
+
+    match (has_option, succeeded) {
+        (false, _) => None,
+        (true, true) => Some(CustomRewardRedemptionStatus::Fulfilled),
+        (true, false) => Some(CustomRewardRedemptionStatus::Canceled),
+    }
+}
+
+/// Error from a failed outcome step
+#[derive(Debug, Serialize)]
+pub struct EventOutcomeStepError {
+    /// Index of the step that failed
+    pub step: usize,
+    /// Message describing the error
+    pub error: String,
+}
+
+/// Choices picked by a random outcome step
+#[derive(Debug, Serialize)]
+pub struct EventOutcomeStepChoices {
+    /// Index of the step
+    pub step: usize,
+    /// Index of the picked choice for the random outcome and
+    /// any nested random outcomes
+    pub choices: Vec<usize>,
+}
+
+/// Result of executing the outcome steps of an event
+#[derive(Debug, Default)]
+pub struct EventOutcomesResult {
+    /// Errors from any of the steps that failed
+    pub errors: Vec<EventOutcomeStepError>,
+    /// Choices picked by any random outcome steps
+    pub random_choices: Vec<EventOutcomeStepChoices>,
+}
+
+/// Executes the outcome steps of the event in order, a failing step does not
+/// prevent the following steps from executing. Produces the errors for any
+/// of the steps that failed along with the picked random choices
+///
+/// `chain` contains the IDs of the events that chained into this event
+pub async fn execute_event_outcomes(
+    db: &DatabaseConnection,
+    twitch: &Twitch,
+    event_sender: &broadcast::Sender<EventMessage>,
+    outcome_queue: &OutcomeQueue,
+    event: &EventModel,
+    event_data: &EventData,
+    chain: &[Uuid],
+) -> EventOutcomesResult {
+    let mut result = EventOutcomesResult::default();
+
+    for (step, outcome_step) in event.outcomes.0.iter().enumerate() {
+        // Wait for the step delay
+        if outcome_step.delay > 0 {
+            tokio::time::sleep(Duration::from_millis(outcome_step.delay as u64)).await;
+        }
+
+        // Pick the outcome for random steps
+        let mut choices = Vec::new();
+        let outcome = resolve_random_outcome(
+            outcome_step.outcome.clone(),
+            &mut rand::thread_rng(),
+            &mut choices,
+        );
+
+        if !choices.is_empty() {
+            result
+                .random_choices
+                .push(EventOutcomeStepChoices { step, choices });
+        }
+
+        let outcome_result = match outcome {
+            Ok(outcome) => {
+                execute_event_outcome(
+                    db,
+                    twitch,
+                    event_sender,
+                    outcome_queue,
+                    event,
+                    outcome,
+                    event_data.clone(),
+                    chain,
+                )
+                .await
+            }
+            Err(err) => Err(err),
+        };
+
+        if let Err(err) = outcome_result {
+            error!("error while executing event outcome step {step}: {err:?}");
+            result.errors.push(EventOutcomeStepError {
+                step,
+                error: format!("{err:#}"),
+            });
+        }
+    }
+
+    result
+}
+
+/// Produces a single outcome of the event and sends the outcome message,
+/// outcomes wait for their turn in the outcome queue before being produced
+#[allow(clippy::too_many_arguments)]
+async fn execute_event_outcome(
+    db: &DatabaseConnection,
+    twitch: &Twitch,
+    event_sender: &broadcast::Sender<EventMessage>,
+    outcome_queue: &OutcomeQueue,
+    event: &EventModel,
+    outcome: EventOutcome,
+    event_data: EventData,
+    chain: &[Uuid],
+) -> anyhow::Result<()> {
+    if let EventOutcome::RunEvent(data) = outcome {
+        let mut chain = chain.to_vec();
+        chain.push(event.id);
 
+        run_chained_events(
+            db.clone(),
+            twitch.clone(),
+            event_sender.clone(),
+            outcome_queue.clone(),
+            data,
+            event_data,
+            chain,
+        );
+        return Ok(());
+    }
+
+    let kind = OutcomeKind::from_outcome(&outcome);
+    let produce = async {
+        let msg =
+            match produce_outcome_message(db, twitch, &event.trigger, outcome, event_data).await? {
+                Some(value) => value,
+                None => return Ok(()),
+            };
+
+        // Queued outcomes run until the overlay reports them as complete
+        if kind.is_none() {
+            return send_outcome_message(event_sender, msg);
+        }
+
+        let completion = outcome_queue.start_completion();
+        send_outcome_message(
+            event_sender,
+            EventMessage::Outcome {
+                outcome_id: completion.id(),
+                message: Box::new(msg),
+            },
+        )?;
+        completion.wait().await;
+
+        Ok(())
+    };
+
+    let kind = match kind {
+        Some(value) => value,
+        None => return produce.await,
+    };
+
+    let config = AppDataModel::get_outcome_queue_config(db)
+        .await
+        .context("failed to load outcome queue config")?;
+
+    outcome_queue
+        .run(kind.config(&config), kind, event, produce)
+        .await?
+}
+
+/// Sends an outcome message to the connected overlays, fails when there is no
+/// overlay connected to receive the outcome
+fn send_outcome_message(
+    event_sender: &broadcast::Sender<EventMessage>,
+    msg: EventMessage,
+) -> anyhow::Result<()> {
+    event_sender
+        .send(msg)
+        .map_err(|_| anyhow!("no overlay connected to receive the outcome"))?;
     Ok(())
 }
 
+/// Runs the events from a [EventOutcome::RunEvent] outcome in the background
+/// passing through the original event data
+fn run_chained_events(
+    db: DatabaseConnection,
+    twitch: Twitch,
+    event_sender: broadcast::Sender<EventMessage>,
+    outcome_queue: OutcomeQueue,
+    data: EventOutcomeRunEvent,
+    event_data: EventData,
+    chain: Vec<Uuid>,
+) {
+    tokio::spawn(async move {
+        // Wait for the chain delay
+        tokio::time::sleep(Duration::from_millis(data.delay as u64)).await;
+
+        let mut futures = FuturesUnordered::<BoxFuture<'_, anyhow::Result<bool>>>::new();
+
+        for event_id in data.event_ids {
+            // Disabled events and events in disabled groups are not loaded
+            let event = match EventModel::get_runnable_by_id(&db, event_id).await {
+                Ok(Some(value)) => value,
+                Ok(None) => {
+                    debug!("skipping chained event: event {event_id} is unknown or disabled");
+                    continue;
+                }
+                Err(err) => {
+                    error!("failed to load event in event chain: {err:?}");
+                    continue;
+                }
+            };
+
+            let options = ExecuteEventOptions {
+                check_role: data.check_role,
+                check_cooldown: data.check_cooldown,
+                chain: chain.clone(),
+            };
+
+            futures.push(Box::pin(execute_event_with_options(
+                &db,
+                &twitch,
+                &event_sender,
+                &outcome_queue,
+                event,
+                event_data.clone(),
+                options,
+            )));
+        }
+
+        use futures::StreamExt;
+
+        while let Some(result) = futures.next().await {
+            if let Err(err) = result {
+                error!("error while executing chained event outcome: {err:?}");
+            }
+        }
+    });
+}
+
+/// Checks that the event can be executed after the events in the `chain`,
+/// events cannot appear in a chain twice and chains have a maximum depth
+pub fn is_event_chain_allowed(chain: &[Uuid], event_id: Uuid) -> bool {
+    chain.len() < MAX_EVENT_CHAIN_DEPTH && !chain.contains(&event_id)
+}
+
+/// Validates the provided conditions, ensuring the message patterns
+/// are valid regular expressions
+pub fn validate_event_conditions(conditions: &[EventCondition]) -> anyhow::Result<()> {
+    for (index, condition) in conditions.iter().enumerate() {
+        if let EventCondition::MessageMatches { pattern } = condition {
+            compile_pattern(pattern)
+                .with_context(|| format!("invalid pattern in condition {}", index + 1))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks if all the provided conditions are met by the event data
+pub fn is_conditions_met(conditions: &[EventCondition], event_data: &EventData) -> bool {
+    conditions
+        .iter()
+        .all(|condition| is_condition_met(condition, event_data))
+}
+
+fn is_condition_met(condition: &EventCondition, event_data: &EventData) -> bool {
+    let input_data = &event_data.input_data;
+
+    match condition {
+        EventCondition::Numeric { comparison } => input_data
+            .numeric_value()
+            .is_some_and(|value| comparison.is_match(value)),
+
+        EventCondition::MessageContains { value, ignore_case } => {
+            input_data.message().is_some_and(|message| {
+                if *ignore_case {
+                    message.to_lowercase().contains(&value.to_lowercase())
+                } else {
+                    message.contains(value.as_str())
+                }
+            })
+        }
+
+        EventCondition::MessageMatches { pattern } => {
+            let message = match input_data.message() {
+                Some(value) => value,
+                None => return false,
+            };
+
+            match compile_pattern(pattern) {
+                Ok(regex) => regex.is_match(message),
+                Err(err) => {
+                    error!("invalid condition pattern {pattern:?}: {err}");
+                    false
+                }
+            }
+        }
+
+        EventCondition::UserInList { users } => event_data.user.as_ref().is_some_and(|user| {
+            users.iter().any(|value| {
+                let value = value.trim().trim_start_matches('@');
+                value.eq_ignore_ascii_case(user.name.as_str()) || value == user.id.as_str()
+            })
+        }),
+
+        EventCondition::SubscriptionTier { tier } => match input_data {
+            EventInputData::Subscription { tier: value, .. }
+            | EventInputData::GiftedSubscription { tier: value, .. }
+            | EventInputData::ReSubscription { tier: value, .. } => value == tier,
+            _ => false,
+        },
+
+        EventCondition::IsGift { value } => match input_data {
+            EventInputData::Subscription { is_gift, .. } => is_gift == value,
+            EventInputData::GiftedSubscription { .. } => *value,
+            _ => false,
+        },
+    }
+}
+
 pub async fn has_required_role(
     twitch: &Twitch,
     user_id: Option<UserId>,
     required_role: &MinimumRequireRole,
+    requirements: &RoleRequirements,
 ) -> bool {
     // No role required (Initial level)
     if let MinimumRequireRole::None = required_role {
@@ -297,31 +1051,972 @@ pub async fn has_required_role(
         return true;
     }
 
-    // Check VIP and moderator lists
-    if let MinimumRequireRole::Vip = required_role {
-        return try_join!(twitch.get_vip_list(), twitch.get_moderator_list()).is_ok_and(
-            |(vips, mods)| {
-                vips.iter().any(|vip| vip.user_id == user)
-                    || mods.iter().any(|mods| mods.user_id == user)
+    match required_role {
+        // Check the user is a follower for long enough
+        MinimumRequireRole::Follower => is_follower(twitch, &user, requirements).await,
+        // Check the user is subscribed with a high enough tier
+        MinimumRequireRole::Subscriber => is_subscriber(twitch, &user, requirements).await,
+        // Check VIP and moderator lists
+        MinimumRequireRole::Vip => is_vip_or_moderator(twitch, &user).await,
+        // Check just moderator list
+        MinimumRequireRole::Mod => twitch
+            .get_moderator_list()
+            .await
+            .is_ok_and(|mods| mods.iter().any(|mods| mods.user_id == user)),
+        MinimumRequireRole::None | MinimumRequireRole::Broadcaster => false,
+    }
+}
+
+/// Checks if the user has been following for long enough
+async fn is_follower(twitch: &Twitch, user: &UserId, requirements: &RoleRequirements) -> bool {
+    twitch
+        .get_follower_by_id(user.clone())
+        .await
+        .is_ok_and(|value| {
+            value.is_some_and(|follower| {
+                is_follow_age_met(
+                    follower.followed_at.as_str(),
+                    requirements.min_follow_days,
+                    Utc::now(),
+                )
+            })
+        })
+}
+
+/// Checks if the user is subscribed with a high enough tier
+async fn is_subscriber(twitch: &Twitch, user: &UserId, requirements: &RoleRequirements) -> bool {
+    twitch
+        .get_subscription_tier(user.clone())
+        .await
+        .is_ok_and(|value| {
+            value.is_some_and(|tier| {
+                is_subscription_tier_met(&tier, requirements.min_subscriber_tier.as_ref())
+            })
+        })
+}
+
+/// Checks if the user is a VIP or a moderator
+async fn is_vip_or_moderator(twitch: &Twitch, user: &UserId) -> bool {
+    try_join!(twitch.get_vip_list(), twitch.get_moderator_list()).is_ok_and(|(vips, mods)| {
+        vips.iter().any(|vip| vip.user_id == *user) || mods.iter().any(|mods| mods.user_id == *user)
+    })
+}
+
+/// Checks if a follow at `followed_at` has lasted at least `min_days`
+pub fn is_follow_age_met(followed_at: &str, min_days: u32, current_time: DateTimeUtc) -> bool {
+    if min_days == 0 {
+        return true;
+    }
+
+    let followed_at = match DateTime::parse_from_rfc3339(followed_at) {
+        Ok(value) => value.to_utc(),
+        Err(err) => {
+            error!("failed to parse follow date: {err:?}");
+            return false;
+        }
+    };
+
+    current_time.signed_duration_since(followed_at) >= TimeDelta::days(min_days as i64)
+}
+
+/// Checks if the subscription `tier` is at least the `min_tier`, any
+/// tier is accepted when no minimum is provided
+pub fn is_subscription_tier_met(
+    tier: &SubscriptionTier,
+    min_tier: Option<&SubscriptionTier>,
+) -> bool {
+    let min_tier = match min_tier {
+        Some(value) => value,
+        None => return true,
+    };
+
+    subscription_tier_level(tier) >= subscription_tier_level(min_tier)
+}
+
+/// Numeric level of a subscription tier for comparison, prime
+/// subscriptions are equivalent to tier 1
+fn subscription_tier_level(tier: &SubscriptionTier) -> u8 {
+    match tier {
+        SubscriptionTier::Tier1 | SubscriptionTier::Prime => 1,
+        SubscriptionTier::Tier2 => 2,
+        SubscriptionTier::Tier3 => 3,
+        SubscriptionTier::Other(_) => 0,
+    }
+}
+
+/// Parses the chat `args` against the `arguments` accepted by a command, produces
+/// the normalized argument values or [None] if the arguments are invalid
+///
+/// Commands without any arguments accept any arguments as-is, otherwise
+/// any extra arguments that aren't consumed are rejected
+pub async fn parse_command_arguments(
+    db: &DatabaseConnection,
+    arguments: &[CommandArgument],
+    args: &[String],
+) -> anyhow::Result<Option<Vec<String>>> {
+    if arguments.is_empty() {
+        return Ok(Some(args.to_vec()));
+    }
+
+    let mut args = args.iter();
+    let mut values = Vec::with_capacity(arguments.len());
+
+    for argument in arguments {
+        let value = match argument.kind {
+            // Text consumes the remaining arguments
+            CommandArgumentKind::Text => {
+                let value = args.by_ref().cloned().collect::<Vec<String>>().join(" ");
+                Some(value).filter(|value| !value.is_empty())
+            }
+            _ => args.next().cloned(),
+        };
+
+        let value = match value {
+            Some(value) => value,
+            // Missing optional arguments can only be at the end
+            None if argument.optional => continue,
+            None => return Ok(None),
+        };
+
+        let value = match argument.kind {
+            CommandArgumentKind::Integer => match value.parse::<i64>() {
+                Ok(value) => value.to_string(),
+                Err(_) => return Ok(None),
+            },
+            CommandArgumentKind::User => {
+                let value = value.trim_start_matches('@');
+                let is_valid = (1..=25).contains(&value.len())
+                    && value
+                        .chars()
+                        .all(|char| char.is_ascii_alphanumeric() || char == '_');
+
+                if !is_valid {
+                    return Ok(None);
+                }
+
+                value.to_string()
+            }
+            CommandArgumentKind::Item => match ItemModel::get_by_name(db, &value).await? {
+                Some(item) => item.name,
+                None => return Ok(None),
+            },
+            CommandArgumentKind::Text => value,
+        };
+
+        values.push(value);
+    }
+
+    // Extra arguments that don't match any command argument
+    if args.next().is_some() {
+        return Ok(None);
+    }
+
+    Ok(Some(values))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        get_event_cooldown_remaining, get_event_remaining_uses, is_conditions_met,
+        is_event_chain_allowed, is_follow_age_met, is_subscription_tier_met,
+        parse_command_arguments, redemption_status, reserve_event_execution, send_outcome_message,
+        validate_event_conditions, ExecutionReservation, MAX_EVENT_CHAIN_DEPTH,
+    };
+    use crate::{
+        database::{
+            entity::{
+                event_executions::{
+                    CreateEventExecution, EventExecutionMetadata, EventExecutionModel,
+                },
+                event_groups::{CreateEventGroup, EventGroupModel, UpdateEventGroup},
+                events::{
+                    CommandArgument, CommandArgumentKind, CreateEvent, EventCondition,
+                    EventCooldown, EventModel, EventOutcome, EventOutcomeSendChat, EventTrigger,
+                    EventUsageLimits, EventUsageWindow, NumericComparison, UpdateEvent,
+                },
+                items::{CreateItem, ItemConfig, ItemImageConfig, ItemModel},
+            },
+            mock_database,
+        },
+        events::{
+            create_event_channel,
+            matching::{EventData, EventInputData},
+            EventMessage,
+        },
+        twitch::models::TwitchEventUser,
+    };
+    use chrono::{DateTime, TimeDelta, Utc};
+    use sea_orm::{ConnectionTrait, QueryTrait, Statement};
+    use twitch_api::{
+        helix::points::CustomRewardRedemptionStatus,
+        types::{DisplayName, SubscriptionTier, UserId, UserName},
+    };
+    use uuid::Uuid;
+
+    fn mock_user(id: &str) -> TwitchEventUser {
+        TwitchEventUser {
+            id: UserId::new(id.to_string()),
+            name: UserName::new(id.to_string()),
+            display_name: DisplayName::new(id.to_string()),
+        }
+    }
+
+    fn bits_event_data(bits: i64, message: &str) -> EventData {
+        EventData {
+            user: Some(TwitchEventUser {
+                id: UserId::from_static("mock-user-id"),
+                name: UserName::from_static("mockuser"),
+                display_name: DisplayName::from_static("Mock User"),
+            }),
+            input_data: EventInputData::Bits {
+                bits,
+                anonymous: false,
+                message: message.to_string(),
+            },
+            remaining_uses: None,
+        }
+    }
+
+    /// Tests that numeric range and exact conditions are checked against the event value
+    #[test]
+    fn test_numeric_conditions() {
+        let conditions = [EventCondition::Numeric {
+            comparison: NumericComparison::Range {
+                min: Some(100),
+                max: Some(499),
             },
+        }];
+
+        assert!(is_conditions_met(&conditions, &bits_event_data(100, "")));
+        assert!(is_conditions_met(&conditions, &bits_event_data(499, "")));
+        assert!(!is_conditions_met(&conditions, &bits_event_data(99, "")));
+        assert!(!is_conditions_met(&conditions, &bits_event_data(500, "")));
+
+        let conditions = [EventCondition::Numeric {
+            comparison: NumericComparison::Equal { value: 420 },
+        }];
+
+        assert!(is_conditions_met(&conditions, &bits_event_data(420, "")));
+        assert!(!is_conditions_met(&conditions, &bits_event_data(421, "")));
+    }
+
+    /// Tests that all conditions must be met
+    #[test]
+    fn test_message_and_user_conditions() {
+        let conditions = [
+            EventCondition::MessageContains {
+                value: "BONK".to_string(),
+                ignore_case: true,
+            },
+            EventCondition::MessageMatches {
+                pattern: r"\d+ times".to_string(),
+            },
+            EventCondition::UserInList {
+                users: vec!["@MockUser".to_string()],
+            },
+        ];
+
+        assert!(is_conditions_met(
+            &conditions,
+            &bits_event_data(1, "bonk 5 times")
+        ));
+        assert!(!is_conditions_met(
+            &conditions,
+            &bits_event_data(1, "bonk many times")
+        ));
+
+        let mut event_data = bits_event_data(1, "bonk 5 times");
+        event_data.user = None;
+        assert!(!is_conditions_met(&conditions, &event_data));
+    }
+
+    /// Tests that invalid message patterns are rejected
+    #[test]
+    fn test_validate_event_conditions() {
+        let conditions = [EventCondition::MessageMatches {
+            pattern: r"\d+ times".to_string(),
+        }];
+        validate_event_conditions(&conditions).unwrap();
+
+        let conditions = [
+            EventCondition::IsGift { value: true },
+            EventCondition::MessageMatches {
+                pattern: r"(\d+ times".to_string(),
+            },
+        ];
+        let err = validate_event_conditions(&conditions).unwrap_err();
+        assert!(format!("{err:#}").contains("invalid pattern in condition 2"));
+    }
+
+    /// Tests the subscription tier and gift conditions
+    #[test]
+    fn test_subscription_conditions() {
+        let conditions = [
+            EventCondition::SubscriptionTier {
+                tier: SubscriptionTier::Tier3,
+            },
+            EventCondition::IsGift { value: true },
+        ];
+
+        let event_data = EventData {
+            user: None,
+            input_data: EventInputData::Subscription {
+                tier: SubscriptionTier::Tier3,
+                is_gift: true,
+            },
+            remaining_uses: None,
+        };
+        assert!(is_conditions_met(&conditions, &event_data));
+
+        let event_data = EventData {
+            user: None,
+            input_data: EventInputData::Subscription {
+                tier: SubscriptionTier::Tier1,
+                is_gift: true,
+            },
+            remaining_uses: None,
+        };
+        assert!(!is_conditions_met(&conditions, &event_data));
+
+        // Conditions that don't apply to the event are not met
+        assert!(!is_conditions_met(&conditions, &bits_event_data(1, "")));
+    }
+
+    fn argument(name: &str, kind: CommandArgumentKind, optional: bool) -> CommandArgument {
+        CommandArgument {
+            name: name.to_string(),
+            kind,
+            optional,
+        }
+    }
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    /// Tests that arguments are parsed and normalized against the command arguments
+    #[tokio::test]
+    async fn test_parse_command_arguments() {
+        let db = mock_database().await;
+
+        let arguments = [
+            argument("amount", CommandArgumentKind::Integer, false),
+            argument("user", CommandArgumentKind::User, false),
+            argument("reason", CommandArgumentKind::Text, true),
+        ];
+
+        let values = parse_command_arguments(
+            &db,
+            &arguments,
+            &args(&["5", "@mockuser", "for", "being", "silly"]),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            values,
+            Some(args(&["5", "mockuser", "for being silly"])),
+            "arguments should be normalized"
         );
+
+        // Optional argument can be omitted
+        let values = parse_command_arguments(&db, &arguments, &args(&["5", "mockuser"]))
+            .await
+            .unwrap();
+        assert_eq!(values, Some(args(&["5", "mockuser"])));
     }
 
-    // Check just moderator list
-    if let MinimumRequireRole::Mod = required_role {
-        return twitch
-            .get_moderator_list()
+    /// Tests that invalid or missing arguments are rejected
+    #[tokio::test]
+    async fn test_parse_command_arguments_invalid() {
+        let db = mock_database().await;
+
+        let arguments = [
+            argument("amount", CommandArgumentKind::Integer, false),
+            argument("user", CommandArgumentKind::User, false),
+        ];
+
+        // Not a number
+        let values = parse_command_arguments(&db, &arguments, &args(&["five", "mockuser"]))
+            .await
+            .unwrap();
+        assert_eq!(values, None);
+
+        // Invalid username
+        let values = parse_command_arguments(&db, &arguments, &args(&["5", "mock-user!"]))
+            .await
+            .unwrap();
+        assert_eq!(values, None);
+
+        // Missing required argument
+        let values = parse_command_arguments(&db, &arguments, &args(&["5"]))
+            .await
+            .unwrap();
+        assert_eq!(values, None);
+
+        // Extra positional argument
+        let values = parse_command_arguments(&db, &arguments, &args(&["5", "mockuser", "extra"]))
+            .await
+            .unwrap();
+        assert_eq!(values, None);
+    }
+
+    /// Tests that item arguments must reference an existing item
+    #[tokio::test]
+    async fn test_parse_command_arguments_item() {
+        let db = mock_database().await;
+
+        ItemModel::create(
+            &db,
+            CreateItem {
+                name: "Brick".to_string(),
+                config: ItemConfig {
+                    image: ItemImageConfig {
+                        src: "test".to_string(),
+                        weight: 1.,
+                        scale: 1.,
+                        pixelate: false,
+                    },
+                    windup: Default::default(),
+                },
+                impact_sounds: Vec::new(),
+                windup_sounds: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let arguments = [argument("item", CommandArgumentKind::Item, false)];
+
+        let values = parse_command_arguments(&db, &arguments, &args(&["unknown"]))
+            .await
+            .unwrap();
+        assert_eq!(values, None);
+
+        // Item names are matched case insensitive
+        let values = parse_command_arguments(&db, &arguments, &args(&["brick"]))
+            .await
+            .unwrap();
+        assert_eq!(values, Some(args(&["Brick"])));
+    }
+
+    /// Tests that chained events are only loaded when the event is
+    /// enabled and is not only in disabled groups
+    #[tokio::test]
+    async fn test_chained_event_runnable() {
+        let db = mock_database().await;
+
+        let event = EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Chained Event".to_string(),
+                trigger: EventTrigger::Follow,
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let group = EventGroupModel::create(
+            &db,
+            CreateEventGroup {
+                name: "Gaming".to_string(),
+                enabled: false,
+            },
+        )
+        .await
+        .unwrap();
+        group.set_event_ids(&db, &[event.id]).await.unwrap();
+
+        // Event is only in a disabled group
+        let runnable = EventModel::get_runnable_by_id(&db, event.id).await.unwrap();
+        assert!(runnable.is_none());
+
+        group
+            .update(
+                &db,
+                UpdateEventGroup {
+                    enabled: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let runnable = EventModel::get_runnable_by_id(&db, event.id).await.unwrap();
+        assert!(runnable.is_some());
+
+        // Disabled events are not runnable even in an enabled group
+        let event = event
+            .update(
+                &db,
+                UpdateEvent {
+                    enabled: Some(false),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let runnable = EventModel::get_runnable_by_id(&db, event.id).await.unwrap();
+        assert!(runnable.is_none());
+    }
+
+    #[test]
+    fn test_event_chain_allowed() {
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        let third = Uuid::new_v4();
+
+        // Events outside of a chain are always allowed
+        assert!(is_event_chain_allowed(&[], first));
+
+        assert!(is_event_chain_allowed(&[first], second));
+        assert!(is_event_chain_allowed(&[first, second], third));
+
+        // Events cannot loop back into the chain
+        assert!(!is_event_chain_allowed(&[first], first));
+        assert!(!is_event_chain_allowed(&[first, second], first));
+        assert!(!is_event_chain_allowed(&[first, second, third], second));
+
+        // Chains cannot go past the maximum depth
+        let chain: Vec<Uuid> = (0..MAX_EVENT_CHAIN_DEPTH).map(|_| Uuid::new_v4()).collect();
+        assert!(!is_event_chain_allowed(&chain, first));
+        assert!(is_event_chain_allowed(&chain[1..], first));
+    }
+
+    /// Tests the per user cooldown check against an event with many
+    /// executions from other users, the lookup for the user should be
+    /// a single indexed query rather than paging through the executions
+    #[tokio::test]
+    async fn test_per_user_cooldown_many_executions() {
+        const EXECUTION_COUNT: usize = 500;
+
+        let db = mock_database().await;
+
+        let event = EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::Follow,
+                cooldown: EventCooldown {
+                    enabled: true,
+                    // 1 hour cooldown
+                    duration: 60 * 60 * 1000,
+                    per_user: true,
+                },
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let current_time = Utc::now();
+        let target_user = mock_user("target-user");
+
+        // Oldest execution is from the target user, still within the cooldown
+        EventExecutionModel::create(
+            &db,
+            CreateEventExecution {
+                event_id: event.id,
+                metadata: EventExecutionMetadata {
+                    user: Some(target_user.clone()),
+                    data: Vec::new(),
+                },
+                created_at: current_time - TimeDelta::minutes(30),
+                stream_session_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        // Newer executions from other users
+        for index in 0..EXECUTION_COUNT {
+            EventExecutionModel::create(
+                &db,
+                CreateEventExecution {
+                    event_id: event.id,
+                    metadata: EventExecutionMetadata {
+                        user: Some(mock_user(&format!("user-{}", index % 50))),
+                        data: Vec::new(),
+                    },
+                    created_at: current_time - TimeDelta::milliseconds(index as i64),
+                    stream_session_id: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let other_user = mock_user("other-user");
+
+        // Target user is still on cooldown
+        let remaining = get_event_cooldown_remaining(&db, &event, Some(&target_user), current_time)
+            .await
+            .unwrap();
+        assert!(remaining
+            .is_some_and(|remaining| remaining > TimeDelta::minutes(29)
+                && remaining <= TimeDelta::minutes(30)));
+
+        // User without any executions is not on cooldown
+        let remaining = get_event_cooldown_remaining(&db, &event, Some(&other_user), current_time)
+            .await
+            .unwrap();
+        assert!(remaining.is_none());
+
+        // Lookup is filtered by the user ID column using the execution index
+        let statement = event
+            .last_user_execution_query(target_user.id.as_str())
+            .build(db.get_database_backend());
+        assert!(statement.sql.contains(r#""user_id" = ?"#));
+
+        let plan = db
+            .query_all(Statement {
+                sql: format!("EXPLAIN QUERY PLAN {}", statement.sql),
+                ..statement
+            })
             .await
-            .is_ok_and(|mods| mods.iter().any(|mods| mods.user_id == user));
+            .unwrap();
+        assert!(plan.iter().any(|row| row
+            .try_get::<String>("", "detail")
+            .is_ok_and(|detail| detail.contains("idx-event-executions-event-user-created"))));
     }
 
-    // Check the user is a follower
-    if let MinimumRequireRole::Follower = required_role {
-        return twitch
-            .get_follower_by_id(user)
+    /// Tests the per stream, per user per stream and rolling window usage limits
+    #[tokio::test]
+    async fn test_event_remaining_uses() {
+        let db = mock_database().await;
+
+        let event = EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::Follow,
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: EventUsageLimits {
+                    per_stream: Some(5),
+                    per_user_per_stream: Some(3),
+                    window: Some(EventUsageWindow {
+                        max: 2,
+                        duration: 60,
+                        per_user: true,
+                    }),
+                },
+            },
+        )
+        .await
+        .unwrap();
+
+        let current_time = Utc::now();
+        let stream_session_id = Some(Uuid::new_v4());
+        let user = mock_user("target-user");
+        let other_user = mock_user("other-user");
+
+        let create_execution = |user: &TwitchEventUser, minutes_ago: i64| CreateEventExecution {
+            event_id: event.id,
+            metadata: EventExecutionMetadata {
+                user: Some(user.clone()),
+                data: Vec::new(),
+            },
+            created_at: current_time - TimeDelta::minutes(minutes_ago),
+            stream_session_id,
+        };
+
+        // No executions, limited by the window
+        let remaining =
+            get_event_remaining_uses(&db, &event, Some(&user), stream_session_id, current_time)
+                .await
+                .unwrap();
+        assert_eq!(remaining, Some(2));
+
+        // Executions outside the window only count towards the stream limits
+        for _ in 0..2 {
+            EventExecutionModel::create(&db, create_execution(&user, 10))
+                .await
+                .unwrap();
+        }
+
+        let remaining =
+            get_event_remaining_uses(&db, &event, Some(&user), stream_session_id, current_time)
+                .await
+                .unwrap();
+        assert_eq!(remaining, Some(1));
+
+        // Other users executions count towards the per stream limit
+        for _ in 0..2 {
+            EventExecutionModel::create(&db, create_execution(&other_user, 10))
+                .await
+                .unwrap();
+        }
+
+        let remaining = get_event_remaining_uses(
+            &db,
+            &event,
+            Some(&other_user),
+            stream_session_id,
+            current_time,
+        )
+        .await
+        .unwrap();
+        assert_eq!(remaining, Some(1));
+
+        EventExecutionModel::create(&db, create_execution(&user, 0))
+            .await
+            .unwrap();
+
+        // Per stream limit has been reached
+        let remaining = get_event_remaining_uses(
+            &db,
+            &event,
+            Some(&other_user),
+            stream_session_id,
+            current_time,
+        )
+        .await
+        .unwrap();
+        assert_eq!(remaining, Some(0));
+
+        // Stream limits don't apply while offline
+        let remaining =
+            get_event_remaining_uses(&db, &event, Some(&other_user), None, current_time)
+                .await
+                .unwrap();
+        assert_eq!(remaining, Some(2));
+
+        // Anonymous users bypass per user limits
+        let remaining = get_event_remaining_uses(&db, &event, None, None, current_time)
             .await
-            .is_ok_and(|value| value.is_some());
+            .unwrap();
+        assert_eq!(remaining, None);
+    }
+
+    /// Tests the follow age requirement
+    #[test]
+    fn test_follow_age_met() {
+        let current_time = DateTime::parse_from_rfc3339("2025-02-14T00:00:00Z")
+            .unwrap()
+            .to_utc();
+
+        // No requirement
+        assert!(is_follow_age_met("2025-02-14T00:00:00Z", 0, current_time));
+
+        assert!(is_follow_age_met("2025-02-07T00:00:00Z", 7, current_time));
+        assert!(!is_follow_age_met("2025-02-07T00:00:01Z", 7, current_time));
+
+        // Invalid dates don't meet the requirement
+        assert!(!is_follow_age_met("invalid", 7, current_time));
+    }
+
+    /// Tests the minimum subscription tier requirement
+    #[test]
+    fn test_subscription_tier_met() {
+        assert!(is_subscription_tier_met(&SubscriptionTier::Tier1, None));
+        assert!(is_subscription_tier_met(
+            &SubscriptionTier::Prime,
+            Some(&SubscriptionTier::Tier1)
+        ));
+        assert!(is_subscription_tier_met(
+            &SubscriptionTier::Tier3,
+            Some(&SubscriptionTier::Tier2)
+        ));
+        assert!(!is_subscription_tier_met(
+            &SubscriptionTier::Tier1,
+            Some(&SubscriptionTier::Tier2)
+        ));
     }
 
-    false
+    /// Tests that outcomes fail without a connected overlay so the
+    /// redemption is refunded instead of fulfilled
+    #[test]
+    fn test_no_overlay_refunds_redemption() {
+        let event_sender = create_event_channel();
+        let trigger = EventTrigger::Redeem {
+            reward_id: "mock-reward-id".to_string(),
+            fulfil_redemption: true,
+            refund_redemption: true,
+        };
+
+        // No overlay connected
+        let success = send_outcome_message(&event_sender, EventMessage::UpdateHotkeys).is_ok();
+        assert!(!success);
+        assert_eq!(
+            redemption_status(&[(&trigger, success)]),
+            Some(CustomRewardRedemptionStatus::Canceled)
+        );
+
+        // Overlay connected
+        let overlay = event_sender.subscribe();
+        let success = send_outcome_message(&event_sender, EventMessage::UpdateHotkeys).is_ok();
+        assert!(success);
+        assert_eq!(
+            redemption_status(&[(&trigger, success)]),
+            Some(CustomRewardRedemptionStatus::Fulfilled)
+        );
+
+        // Overlay disconnected
+        drop(overlay);
+        assert!(send_outcome_message(&event_sender, EventMessage::UpdateHotkeys).is_err());
+
+        // Refunds are only made when enabled
+        let trigger = EventTrigger::Redeem {
+            reward_id: "mock-reward-id".to_string(),
+            fulfil_redemption: true,
+            refund_redemption: false,
+        };
+        assert_eq!(redemption_status(&[(&trigger, false)]), None);
+    }
+
+    /// Tests that a redemption triggering multiple events is fulfilled when
+    /// any of the events succeeded and only refunded when all of them failed
+    #[test]
+    fn test_redemption_status_multiple_events() {
+        let fulfil = EventTrigger::Redeem {
+            reward_id: "mock-reward-id".to_string(),
+            fulfil_redemption: true,
+            refund_redemption: false,
+        };
+        let refund = EventTrigger::Redeem {
+            reward_id: "mock-reward-id".to_string(),
+            fulfil_redemption: false,
+            refund_redemption: true,
+        };
+
+        // Successful event fulfils even when another event failed
+        assert_eq!(
+            redemption_status(&[(&refund, false), (&fulfil, true)]),
+            Some(CustomRewardRedemptionStatus::Fulfilled)
+        );
+
+        // Successful event without fulfilment prevents the refund
+        assert_eq!(
+            redemption_status(&[(&refund, true), (&fulfil, false)]),
+            None
+        );
+
+        // All events failed
+        assert_eq!(
+            redemption_status(&[(&fulfil, false), (&refund, false)]),
+            Some(CustomRewardRedemptionStatus::Canceled)
+        );
+
+        assert_eq!(redemption_status(&[]), None);
+    }
+
+    /// Tests that concurrent triggers cannot bypass the usage limits
+    /// and cooldown of an event
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_concurrent_execution_limits() {
+        let db = mock_database().await;
+
+        let create_event = |cooldown: EventCooldown, usage_limits: EventUsageLimits| CreateEvent {
+            enabled: true,
+            name: "Test Event".to_string(),
+            trigger: EventTrigger::Follow,
+            cooldown,
+            outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                template: "test".to_string(),
+                ..Default::default()
+            })
+            .into(),
+            outcome_delay: Default::default(),
+            require_role: Default::default(),
+            role_requirements: Default::default(),
+            conditions: Default::default(),
+            feedback: Default::default(),
+            usage_limits,
+        };
+
+        let limited_event = EventModel::create(
+            &db,
+            create_event(
+                Default::default(),
+                EventUsageLimits {
+                    window: Some(EventUsageWindow {
+                        max: 3,
+                        duration: 60,
+                        per_user: false,
+                    }),
+                    ..Default::default()
+                },
+            ),
+        )
+        .await
+        .unwrap();
+
+        let cooldown_event = EventModel::create(
+            &db,
+            create_event(
+                EventCooldown {
+                    enabled: true,
+                    duration: 60 * 1000,
+                    per_user: false,
+                },
+                Default::default(),
+            ),
+        )
+        .await
+        .unwrap();
+
+        for (event, expected) in [(limited_event, 3), (cooldown_event, 1)] {
+            let event_data = bits_event_data(100, "");
+            let tasks = (0..20).map(|_| {
+                let db = db.clone();
+                let event = event.clone();
+                let event_data = event_data.clone();
+
+                tokio::spawn(async move {
+                    reserve_event_execution(&db, &event, &event_data, true)
+                        .await
+                        .unwrap()
+                })
+            });
+
+            let reserved = futures::future::join_all(tasks)
+                .await
+                .into_iter()
+                .filter(|result| {
+                    matches!(
+                        result.as_ref().unwrap(),
+                        ExecutionReservation::Reserved { .. }
+                    )
+                })
+                .count();
+
+            assert_eq!(reserved, expected);
+        }
+    }
 }
@@ -0,0 +1,591 @@
+//! # Outcome Queue
+//!
+//! Central queue that outcomes pass through before being produced, each kind
+//! of outcome has its own queue which limits the number of outcomes running
+//! at once, spaces out the outcomes and drops outcomes when full
+//!
+//! Outcomes produced by the overlay (Throws, sounds and hotkeys) are only
+//! complete once the overlay reports them as complete, see [OutcomeCompletion]
+
+use crate::database::entity::{
+    app_data::{OutcomeQueueConfig, OutcomeQueueDropPolicy, OutcomeQueueKindConfig},
+    events::{EventModel, EventOutcome},
+};
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::{
+    collections::{HashMap, VecDeque},
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    sync::{oneshot, Notify},
+    time::{sleep_until, timeout, Instant},
+};
+use uuid::Uuid;
+
+/// Maximum time to wait for the overlay to report an outcome as complete,
+/// prevents outcomes the overlay never reports (i.e the overlay was closed)
+/// from holding a slot in the queue
+const OVERLAY_COMPLETION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Kind of outcome, outcomes of the same kind share a queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum OutcomeKind {
+    Throw,
+    Sound,
+    Hotkey,
+    Chat,
+}
+
+impl OutcomeKind {
+    /// Get the kind of an outcome, outcomes that don't produce
+    /// anything themselves are not queued
+    pub fn from_outcome(outcome: &EventOutcome) -> Option<Self> {
+        match outcome {
+            EventOutcome::ThrowBits(_)
+            | EventOutcome::Throwable(_)
+            | EventOutcome::ChannelEmotes(_) => Some(Self::Throw),
+            EventOutcome::PlaySound(_) => Some(Self::Sound),
+            EventOutcome::TriggerHotkey(_) => Some(Self::Hotkey),
+            EventOutcome::SendChatMessage(_) | EventOutcome::Announcement(_) => Some(Self::Chat),
+            EventOutcome::RunEvent(_)
+            | EventOutcome::Random(_)
+            | EventOutcome::SetRewardState(_)
+            | EventOutcome::TimeoutUser(_)
+            | EventOutcome::DeleteMessage
+            | EventOutcome::Shoutout(_) => None,
+        }
+    }
+
+    /// Get the queue configuration for this kind of outcome
+    pub fn config(self, config: &OutcomeQueueConfig) -> &OutcomeQueueKindConfig {
+        match self {
+            Self::Throw => &config.throw,
+            Self::Sound => &config.sound,
+            Self::Hotkey => &config.hotkey,
+            Self::Chat => &config.chat,
+        }
+    }
+}
+
+/// Outcome within the queue
+#[derive(Debug, Clone, Serialize)]
+pub struct OutcomeQueueEntry {
+    /// Unique ID of the queue entry
+    pub id: Uuid,
+    /// Kind of outcome
+    pub kind: OutcomeKind,
+    /// ID of the event the outcome is for
+    pub event_id: Uuid,
+    /// Name of the event the outcome is for
+    pub event_name: String,
+    /// When the outcome was added to the queue
+    pub queued_at: DateTime<Utc>,
+    /// Whether the outcome is currently running
+    pub running: bool,
+}
+
+#[derive(Clone, Default)]
+pub struct OutcomeQueue {
+    inner: Arc<OutcomeQueueInner>,
+}
+
+#[derive(Default)]
+struct OutcomeQueueInner {
+    /// Queue state for each kind of outcome
+    queues: Mutex<HashMap<OutcomeKind, KindQueue>>,
+    /// Notifier for changes to the queues
+    notify: Notify,
+    /// Outcomes waiting for the overlay to report them as complete
+    completions: Mutex<HashMap<Uuid, oneshot::Sender<()>>>,
+}
+
+#[derive(Default)]
+struct KindQueue {
+    /// Outcomes waiting to run
+    pending: VecDeque<OutcomeQueueEntry>,
+    /// Outcomes that are running
+    running: Vec<OutcomeQueueEntry>,
+    /// When the last outcome was started
+    last_started: Option<Instant>,
+}
+
+/// Next step for an outcome waiting in the queue
+enum WaitState {
+    /// Outcome can start running
+    Ready,
+    /// Outcome must wait until the provided instant for the spacing
+    Until(Instant),
+    /// Outcome must wait for the queue to change
+    Changed,
+}
+
+impl OutcomeQueue {
+    /// Get all the outcomes within the queue
+    pub fn entries(&self) -> Vec<OutcomeQueueEntry> {
+        let queues = self.inner.queues.lock().expect("queue lock poisoned");
+        let mut entries: Vec<OutcomeQueueEntry> = queues
+            .values()
+            .flat_map(|queue| queue.running.iter().chain(queue.pending.iter()))
+            .cloned()
+            .collect();
+
+        entries.sort_by_key(|entry| entry.queued_at);
+        entries
+    }
+
+    /// Waits for a turn in the queue for the `kind` of outcome then runs the
+    /// outcome `task`. Produces an error if the outcome was dropped from the queue
+    pub async fn run<F>(
+        &self,
+        config: &OutcomeQueueKindConfig,
+        kind: OutcomeKind,
+        event: &EventModel,
+        task: F,
+    ) -> anyhow::Result<F::Output>
+    where
+        F: Future,
+    {
+        let id = Uuid::new_v4();
+
+        self.push(
+            config,
+            OutcomeQueueEntry {
+                id,
+                kind,
+                event_id: event.id,
+                event_name: event.name.clone(),
+                queued_at: Utc::now(),
+                running: false,
+            },
+        )?;
+
+        // Ensures the outcome is removed from the queue when completed or cancelled
+        let _guard = EntryGuard {
+            queue: self,
+            kind,
+            id,
+        };
+
+        self.wait_for_turn(config, kind, id).await?;
+
+        Ok(task.await)
+    }
+
+    /// Adds a new entry to the queue, applies the drop policy when full
+    fn push(
+        &self,
+        config: &OutcomeQueueKindConfig,
+        entry: OutcomeQueueEntry,
+    ) -> anyhow::Result<()> {
+        {
+            let mut queues = self.inner.queues.lock().expect("queue lock poisoned");
+            let queue = queues.entry(entry.kind).or_default();
+
+            let max_length = config.max_length as usize;
+            if max_length > 0 && queue.pending.len() >= max_length {
+                match config.drop_policy {
+                    OutcomeQueueDropPolicy::DropNewest => {
+                        return Err(anyhow!("outcome queue is full, outcome was dropped"));
+                    }
+                    OutcomeQueueDropPolicy::DropOldest => {
+                        // Waiting outcome will notice its been removed
+                        queue.pending.pop_front();
+                    }
+                }
+            }
+
+            queue.pending.push_back(entry);
+        }
+
+        self.inner.notify.notify_waiters();
+        Ok(())
+    }
+
+    /// Waits until the outcome is at the front of the queue, a slot is free
+    /// and the spacing since the last outcome has passed
+    async fn wait_for_turn(
+        &self,
+        config: &OutcomeQueueKindConfig,
+        kind: OutcomeKind,
+        id: Uuid,
+    ) -> anyhow::Result<()> {
+        let spacing = Duration::from_millis(config.spacing as u64);
+        let max_concurrent = config.max_concurrent as usize;
+
+        loop {
+            // Register for notifications before checking the state to
+            // prevent missing changes
+            let notified = self.inner.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            let state = {
+                let mut queues = self.inner.queues.lock().expect("queue lock poisoned");
+                let queue = queues.entry(kind).or_default();
+
+                let position = queue
+                    .pending
+                    .iter()
+                    .position(|entry| entry.id == id)
+                    .ok_or_else(|| anyhow!("outcome was dropped from the full outcome queue"))?;
+
+                let now = Instant::now();
+
+                if position != 0 || (max_concurrent > 0 && queue.running.len() >= max_concurrent) {
+                    WaitState::Changed
+                } else {
+                    match queue.last_started.map(|value| value + spacing) {
+                        Some(ready_at) if ready_at > now => WaitState::Until(ready_at),
+                        _ => {
+                            if let Some(mut entry) = queue.pending.pop_front() {
+                                entry.running = true;
+                                queue.running.push(entry);
+                            }
+
+                            queue.last_started = Some(now);
+                            WaitState::Ready
+                        }
+                    }
+                }
+            };
+
+            match state {
+                WaitState::Ready => break,
+                WaitState::Until(ready_at) => {
+                    tokio::select! {
+                        _ = sleep_until(ready_at) => {}
+                        _ = notified => {}
+                    }
+                }
+                WaitState::Changed => notified.await,
+            }
+        }
+
+        // Entry moving from pending may allow others to progress
+        self.inner.notify.notify_waiters();
+        Ok(())
+    }
+
+    /// Starts tracking the completion of an outcome sent to the overlay,
+    /// the ID of the completion must be sent along with the outcome
+    pub fn start_completion(&self) -> OutcomeCompletion<'_> {
+        let id = Uuid::new_v4();
+        let (tx, rx) = oneshot::channel();
+
+        self.inner
+            .completions
+            .lock()
+            .expect("completions lock poisoned")
+            .insert(id, tx);
+
+        OutcomeCompletion {
+            queue: self,
+            id,
+            rx,
+        }
+    }
+
+    /// Marks the outcome with the provided completion `id` as complete,
+    /// returns false if the outcome is unknown or already complete
+    pub fn complete(&self, id: Uuid) -> bool {
+        let tx = self
+            .inner
+            .completions
+            .lock()
+            .expect("completions lock poisoned")
+            .remove(&id);
+
+        tx.is_some_and(|tx| tx.send(()).is_ok())
+    }
+
+    /// Removes an entry from the queue
+    fn remove(&self, kind: OutcomeKind, id: Uuid) {
+        {
+            let mut queues = self.inner.queues.lock().expect("queue lock poisoned");
+            if let Some(queue) = queues.get_mut(&kind) {
+                queue.pending.retain(|entry| entry.id != id);
+                queue.running.retain(|entry| entry.id != id);
+            }
+        }
+
+        self.inner.notify.notify_waiters();
+    }
+}
+
+/// Guard that removes an entry from the queue when dropped
+struct EntryGuard<'a> {
+    queue: &'a OutcomeQueue,
+    kind: OutcomeKind,
+    id: Uuid,
+}
+
+impl Drop for EntryGuard<'_> {
+    fn drop(&mut self) {
+        self.queue.remove(self.kind, self.id);
+    }
+}
+
+/// Pending completion of an outcome sent to the overlay
+pub struct OutcomeCompletion<'a> {
+    queue: &'a OutcomeQueue,
+    id: Uuid,
+    rx: oneshot::Receiver<()>,
+}
+
+impl OutcomeCompletion<'_> {
+    /// ID the overlay uses to report the outcome as complete
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Waits for the overlay to report the outcome as complete, gives up
+    /// after [OVERLAY_COMPLETION_TIMEOUT]
+    pub async fn wait(mut self) {
+        if timeout(OVERLAY_COMPLETION_TIMEOUT, &mut self.rx)
+            .await
+            .is_err()
+        {
+            log::warn!("overlay did not report outcome {} as complete", self.id);
+        }
+    }
+}
+
+impl Drop for OutcomeCompletion<'_> {
+    fn drop(&mut self) {
+        self.queue
+            .inner
+            .completions
+            .lock()
+            .expect("completions lock poisoned")
+            .remove(&self.id);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{OutcomeKind, OutcomeQueue};
+    use crate::database::entity::{
+        app_data::{OutcomeQueueDropPolicy, OutcomeQueueKindConfig},
+        events::{EventModel, EventOutcome, EventOutcomeSendChat, EventTrigger, EventTriggerType},
+    };
+    use chrono::Utc;
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+    use tokio::{
+        sync::oneshot,
+        time::{sleep, Instant},
+    };
+    use uuid::Uuid;
+
+    fn mock_event() -> EventModel {
+        EventModel {
+            id: Uuid::new_v4(),
+            enabled: true,
+            name: "Test Event".to_string(),
+            trigger_type: EventTriggerType::Follow,
+            trigger: EventTrigger::Follow,
+            outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                template: "test".to_string(),
+                ..Default::default()
+            })
+            .into(),
+            cooldown: Default::default(),
+            require_role: Default::default(),
+            role_requirements: Default::default(),
+            outcome_delay: 0,
+            conditions: Default::default(),
+            feedback: Default::default(),
+            usage_limits: Default::default(),
+            order: 0,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn config(
+        max_concurrent: u32,
+        spacing: u32,
+        max_length: u32,
+        drop_policy: OutcomeQueueDropPolicy,
+    ) -> OutcomeQueueKindConfig {
+        OutcomeQueueKindConfig {
+            max_concurrent,
+            spacing,
+            max_length,
+            drop_policy,
+        }
+    }
+
+    /// Tests that no more than the maximum concurrent outcomes run at once
+    #[tokio::test]
+    async fn test_queue_max_concurrent() {
+        let queue = OutcomeQueue::default();
+        let config = config(2, 0, 0, OutcomeQueueDropPolicy::DropNewest);
+        let event = mock_event();
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+
+        let tasks = (0..6).map(|_| {
+            let running = running.clone();
+            let max_running = max_running.clone();
+
+            queue.run(&config, OutcomeKind::Throw, &event, async move {
+                let value = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(value, Ordering::SeqCst);
+                sleep(Duration::from_millis(20)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+            })
+        });
+
+        let results = futures::future::join_all(tasks).await;
+        assert!(results.iter().all(|result| result.is_ok()));
+        assert_eq!(max_running.load(Ordering::SeqCst), 2);
+
+        // Queue should be empty once everything has run
+        assert!(queue.entries().is_empty());
+    }
+
+    /// Tests that outcomes are spaced out by the configured spacing
+    #[tokio::test]
+    async fn test_queue_spacing() {
+        let queue = OutcomeQueue::default();
+        let config = config(0, 50, 0, OutcomeQueueDropPolicy::DropNewest);
+        let event = mock_event();
+
+        let start = Instant::now();
+        let tasks = (0..3).map(|_| {
+            queue.run(&config, OutcomeKind::Sound, &event, async move {
+                Instant::now()
+            })
+        });
+
+        let mut started: Vec<Instant> = futures::future::join_all(tasks)
+            .await
+            .into_iter()
+            .map(|result| result.unwrap())
+            .collect();
+        started.sort();
+
+        assert!(started[0] - start < Duration::from_millis(50));
+        assert!(started[1] - started[0] >= Duration::from_millis(50));
+        assert!(started[2] - started[1] >= Duration::from_millis(50));
+    }
+
+    /// Tests that new outcomes are dropped when the queue is full
+    #[tokio::test]
+    async fn test_queue_drop_newest() {
+        let queue = OutcomeQueue::default();
+        let config = config(1, 0, 1, OutcomeQueueDropPolicy::DropNewest);
+        let event = mock_event();
+
+        let tasks = (0..3).map(|index| {
+            queue.run(&config, OutcomeKind::Chat, &event, async move {
+                sleep(Duration::from_millis(20)).await;
+                index
+            })
+        });
+
+        let results = futures::future::join_all(tasks).await;
+
+        // First is running, second is waiting and third is dropped
+        assert_eq!(results[0].as_ref().ok(), Some(&0));
+        assert_eq!(results[1].as_ref().ok(), Some(&1));
+        assert!(results[2].is_err());
+    }
+
+    /// Tests that the oldest waiting outcome is dropped when the queue is full
+    #[tokio::test]
+    async fn test_queue_drop_oldest() {
+        let queue = OutcomeQueue::default();
+        let config = config(1, 0, 1, OutcomeQueueDropPolicy::DropOldest);
+        let event = mock_event();
+
+        let first = queue.run(&config, OutcomeKind::Hotkey, &event, async move {
+            sleep(Duration::from_millis(50)).await;
+            0
+        });
+        let second = queue.run(&config, OutcomeKind::Hotkey, &event, async move { 1 });
+        let third = async {
+            // Queue up after the others
+            sleep(Duration::from_millis(10)).await;
+
+            // Both outcomes should be visible in the queue
+            let entries = queue.entries();
+            assert_eq!(entries.len(), 2);
+            assert!(entries[0].running);
+            assert!(!entries[1].running);
+
+            queue
+                .run(&config, OutcomeKind::Hotkey, &event, async move { 2 })
+                .await
+        };
+
+        let (first, second, third) = tokio::join!(first, second, third);
+
+        assert_eq!(first.ok(), Some(0));
+        assert!(second.is_err());
+        assert_eq!(third.ok(), Some(2));
+    }
+
+    /// Tests that separate kinds of outcomes don't wait for each other
+    #[tokio::test]
+    async fn test_queue_separate_kinds() {
+        let queue = OutcomeQueue::default();
+        let config = config(1, 0, 0, OutcomeQueueDropPolicy::DropNewest);
+        let event = mock_event();
+
+        let start = Instant::now();
+        let throw = queue.run(&config, OutcomeKind::Throw, &event, async move {
+            sleep(Duration::from_millis(50)).await;
+        });
+        let sound = queue.run(&config, OutcomeKind::Sound, &event, async move {
+            Instant::now()
+        });
+
+        let (_, sound) = tokio::join!(throw, sound);
+        assert!(sound.unwrap() - start < Duration::from_millis(50));
+    }
+
+    /// Tests that overlay outcomes hold their slot until the overlay
+    /// reports them as complete
+    #[tokio::test]
+    async fn test_queue_overlay_completion() {
+        let queue = OutcomeQueue::default();
+        let config = config(1, 0, 0, OutcomeQueueDropPolicy::DropNewest);
+        let event = mock_event();
+        let (id_tx, id_rx) = oneshot::channel();
+
+        let first = queue.run(&config, OutcomeKind::Throw, &event, async {
+            let completion = queue.start_completion();
+            id_tx.send(completion.id()).unwrap();
+            completion.wait().await;
+        });
+        let second = queue.run(&config, OutcomeKind::Throw, &event, async {
+            Instant::now()
+        });
+        let complete = async {
+            let id = id_rx.await.unwrap();
+            sleep(Duration::from_millis(50)).await;
+
+            let completed_at = Instant::now();
+            assert!(queue.complete(id));
+
+            // Completions are only reported once
+            assert!(!queue.complete(id));
+            completed_at
+        };
+
+        let (first, second, completed_at) = tokio::join!(first, second, complete);
+        assert!(first.is_ok());
+        assert!(second.unwrap() >= completed_at);
+    }
+}
@@ -1,21 +1,26 @@
 use crate::{
     database::entity::{
         chat_history::ChatHistoryModel,
-        events::{EventModel, EventTrigger, EventTriggerType},
+        events::{EventModel, EventTrigger, EventTriggerType, TimerActiveWindow},
+        shared::MinMax,
+        stream_sessions::StreamSessionModel,
     },
     events::{
         matching::{EventData, EventInputData},
         processing::execute_event,
+        queue::OutcomeQueue,
         EventMessage,
     },
     twitch::manager::Twitch,
 };
 use anyhow::Context;
-use chrono::Local;
+use chrono::{DateTime, Local, TimeDelta};
+use cron::Schedule;
 use futures::future::BoxFuture;
 use log::{debug, error};
+use rand::Rng;
 use sea_orm::DatabaseConnection;
-use std::{collections::BinaryHeap, future::Future, pin::Pin, task::Poll, time::Duration};
+use std::{collections::BinaryHeap, future::Future, pin::Pin, str::FromStr, task::Poll};
 use tokio::{
     sync::{broadcast, mpsc},
     time::{sleep_until, Instant},
@@ -27,6 +32,9 @@ pub struct ScheduledEvent {
     pub event: EventModel,
     /// Next instance the
     pub next_run: Instant,
+    /// Scheduled time of the next run before the jitter was applied, the
+    /// run after this one is scheduled from this time
+    pub base: DateTime<Local>,
 }
 
 impl Eq for ScheduledEvent {}
@@ -58,12 +66,20 @@ impl SchedulerHandle {
     pub async fn update_events(&self, events: Vec<EventModel>) -> anyhow::Result<()> {
         self.0.send(events).await.context("failed to send event")
     }
+
+    /// Reloads the timer events from the database
+    pub async fn reload_events(&self, db: &DatabaseConnection) {
+        if let Ok(events) = EventModel::get_by_trigger_type(db, EventTriggerType::Timer).await {
+            _ = self.update_events(events).await;
+        }
+    }
 }
 
 pub fn create_scheduler(
     db: DatabaseConnection,
     twitch: Twitch,
     event_sender: broadcast::Sender<EventMessage>,
+    outcome_queue: OutcomeQueue,
 ) -> SchedulerHandle {
     let (tx, rx) = mpsc::channel(5);
 
@@ -89,6 +105,7 @@ pub fn create_scheduler(
         db,
         twitch,
         event_sender,
+        outcome_queue,
     });
 
     SchedulerHandle(tx)
@@ -108,18 +125,22 @@ struct SchedulerEventLoop {
     db: DatabaseConnection,
     twitch: Twitch,
     event_sender: broadcast::Sender<EventMessage>,
+    outcome_queue: OutcomeQueue,
 }
 
 async fn execute_scheduled_event(
     db: DatabaseConnection,
     twitch: Twitch,
     event_sender: EventMessageChannel,
+    outcome_queue: OutcomeQueue,
     event: EventModel,
 ) -> anyhow::Result<()> {
-    let min_chat_messages = match &event.trigger {
+    let (min_chat_messages, only_while_live) = match &event.trigger {
         EventTrigger::Timer {
-            min_chat_messages, ..
-        } => *min_chat_messages,
+            min_chat_messages,
+            only_while_live,
+            ..
+        } => (*min_chat_messages, *only_while_live),
         _ => {
             return Err(anyhow::anyhow!(
                 "attempted to execute timer event that was not a timer event"
@@ -127,12 +148,24 @@ async fn execute_scheduled_event(
         }
     };
 
+    // Ensure the stream is live
+    if only_while_live {
+        let stream_session = StreamSessionModel::get_current(&db)
+            .await
+            .context("failed to get current stream session")?;
+
+        if stream_session.is_none() {
+            debug!("skipping timer execution, stream is not live");
+            return Ok(());
+        }
+    }
+
     let user_id = twitch.get_user_id().await;
 
     // Ensure minimum chat messages has been reached
     if min_chat_messages > 0 {
         let last_execution = event
-            .last_execution(&db, 0)
+            .last_execution(&db)
             .await
             .context("failed to get last execution")?;
 
@@ -151,10 +184,12 @@ async fn execute_scheduled_event(
         &db,
         &twitch,
         &event_sender,
+        &outcome_queue,
         event,
         EventData {
             user: None,
             input_data: EventInputData::None,
+            remaining_uses: None,
         },
     )
     .await?;
@@ -169,7 +204,7 @@ impl SchedulerEventLoop {
             // Create the scheduled events
             self.events = events
                 .into_iter()
-                .filter_map(create_scheduled_event)
+                .filter_map(|event| create_scheduled_event(event, None))
                 .collect();
 
             // Clear sleep state
@@ -197,16 +232,19 @@ impl SchedulerEventLoop {
                 let db = self.db.clone();
                 let twitch = self.twitch.clone();
                 let event_sender = self.event_sender.clone();
+                let outcome_queue = self.outcome_queue.clone();
 
                 async move {
-                    if let Err(err) = execute_scheduled_event(db, twitch, event_sender, event).await
+                    if let Err(err) =
+                        execute_scheduled_event(db, twitch, event_sender, outcome_queue, event)
+                            .await
                     {
                         error!("error while executing event outcome (in timer): {err:?}");
                     }
                 }
             });
 
-            if let Some(event) = create_scheduled_event(event.event) {
+            if let Some(event) = create_scheduled_event(event.event, Some(event.base)) {
                 self.events.push(event);
             }
 
@@ -244,20 +282,399 @@ impl Future for SchedulerEventLoop {
     }
 }
 
-fn create_scheduled_event(event: EventModel) -> Option<ScheduledEvent> {
-    let interval = match &event.trigger {
-        EventTrigger::Timer { interval, .. } => *interval,
-        _ => return None,
+/// Maximum number of schedule candidates to check when searching
+/// for a run that falls within the active window
+const MAX_SCHEDULE_ATTEMPTS: usize = 100;
+
+/// Schedules the next run of the timer `event`, `previous_base` is the
+/// un-jittered time of the previous run when the event has already run
+fn create_scheduled_event(
+    event: EventModel,
+    previous_base: Option<DateTime<Local>>,
+) -> Option<ScheduledEvent> {
+    let now = Local::now();
+    let next_run = match get_next_run(&event.trigger, previous_base, now, &mut rand::thread_rng()) {
+        Ok(Some(value)) => value,
+        Ok(None) => return None,
+        Err(err) => {
+            error!("failed to schedule timer event {}: {err:?}", event.id);
+            return None;
+        }
     };
 
-    let next_run = get_next_interval_instant(interval);
-    Some(ScheduledEvent { event, next_run })
+    let delay = (next_run.run - now).to_std().unwrap_or_default();
+    Some(ScheduledEvent {
+        event,
+        next_run: Instant::now() + delay,
+        base: next_run.base,
+    })
 }
 
-/// Gets the next instant for a fixed interval
-fn get_next_interval_instant(interval: u64) -> Instant {
-    let now = Local::now();
-    let seconds_since_epoch = now.timestamp() as u64;
-    let next = (seconds_since_epoch / interval + 1) * interval;
-    Instant::now() + Duration::from_secs(next - seconds_since_epoch)
+/// Next run of a timer trigger
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ScheduledRun {
+    /// Interval or cron schedule time of the run
+    base: DateTime<Local>,
+    /// Time to run at, the base time with the jitter applied
+    run: DateTime<Local>,
+}
+
+/// Gets the next time a timer trigger should run, applies the trigger jitter
+/// and skips any runs outside of the active window
+///
+/// The run is scheduled after the `previous_base` time of the previous run (Or
+/// `now` when there is no previous run) so that runs moved early by the jitter
+/// don't schedule the same interval or cron time again
+fn get_next_run<R: Rng>(
+    trigger: &EventTrigger,
+    previous_base: Option<DateTime<Local>>,
+    now: DateTime<Local>,
+    rng: &mut R,
+) -> anyhow::Result<Option<ScheduledRun>> {
+    let (interval, cron, jitter, active_window) = match trigger {
+        EventTrigger::Timer {
+            interval,
+            cron,
+            jitter,
+            active_window,
+            ..
+        } => (*interval, cron, jitter, active_window),
+        _ => return Ok(None),
+    };
+
+    let schedule = match cron {
+        Some(cron) => Some(parse_cron_schedule(cron)?),
+        None => None,
+    };
+
+    let mut after = previous_base.map_or(now, |previous_base| previous_base.max(now));
+
+    for _ in 0..MAX_SCHEDULE_ATTEMPTS {
+        let next = match &schedule {
+            Some(schedule) => schedule.after(&after).next(),
+            None => get_next_interval_time(interval, after),
+        };
+
+        let base = match next {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        // Apply the random offset without running in the past
+        let run = match jitter {
+            Some(jitter) => apply_jitter(base, jitter, rng).max(now),
+            None => base,
+        };
+
+        match active_window {
+            // Continue searching from the start of the next window
+            Some(active_window) if !active_window.contains(run.time()) => {
+                after = get_next_window_start(active_window, run) - TimeDelta::seconds(1);
+            }
+            _ => return Ok(Some(ScheduledRun { base, run })),
+        }
+    }
+
+    Ok(None)
+}
+
+/// Parses a cron expression, standard 5 field expressions are
+/// accepted and treated as running at the start of the minute
+pub fn parse_cron_schedule(cron: &str) -> anyhow::Result<Schedule> {
+    let cron = cron.trim();
+    let cron = if cron.split_whitespace().count() == 5 {
+        format!("0 {cron}")
+    } else {
+        cron.to_string()
+    };
+
+    Schedule::from_str(&cron).with_context(|| format!("invalid cron expression {cron:?}"))
+}
+
+/// Validates the schedule of a timer `trigger`, ensuring the cron
+/// expression is valid and the jitter range is not inverted
+pub fn validate_timer_trigger(trigger: &EventTrigger) -> anyhow::Result<()> {
+    let EventTrigger::Timer { cron, jitter, .. } = trigger else {
+        return Ok(());
+    };
+
+    if let Some(cron) = cron {
+        parse_cron_schedule(cron)?;
+    }
+
+    if let Some(jitter) = jitter {
+        if jitter.min > jitter.max {
+            anyhow::bail!("timer jitter minimum must not be greater than the maximum");
+        }
+    }
+
+    Ok(())
+}
+
+/// Gets the next time for a fixed interval aligned to the epoch
+fn get_next_interval_time(interval: u64, after: DateTime<Local>) -> Option<DateTime<Local>> {
+    if interval == 0 {
+        return None;
+    }
+
+    let interval = interval as i64;
+    let next = (after.timestamp().div_euclid(interval) + 1) * interval;
+    DateTime::from_timestamp(next, 0).map(|value| value.with_timezone(&Local))
+}
+
+/// Offsets the time by a random amount of seconds within the jitter range
+fn apply_jitter<R: Rng>(
+    time: DateTime<Local>,
+    jitter: &MinMax<i64>,
+    rng: &mut R,
+) -> DateTime<Local> {
+    let offset = if jitter.min < jitter.max {
+        rng.gen_range(jitter.min..=jitter.max)
+    } else {
+        jitter.min
+    };
+
+    time + TimeDelta::seconds(offset)
+}
+
+/// Gets the start of the next active window after the provided time
+fn get_next_window_start(
+    active_window: &TimerActiveWindow,
+    time: DateTime<Local>,
+) -> DateTime<Local> {
+    let mut date = time.date_naive();
+    if time.time() >= active_window.start {
+        date = date.succ_opt().unwrap_or(date);
+    }
+
+    date.and_time(active_window.start)
+        .and_local_timezone(Local)
+        .earliest()
+        // Window start doesn't exist locally (Daylight savings), try again an hour later
+        .unwrap_or_else(|| time + TimeDelta::hours(1))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{get_next_run, validate_timer_trigger, ScheduledRun};
+    use crate::database::entity::{
+        events::{EventTrigger, TimerActiveWindow},
+        shared::MinMax,
+    };
+    use chrono::{DateTime, Local, NaiveTime, TimeZone};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn local_time(hour: u32, min: u32, sec: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2025, 2, 10, hour, min, sec)
+            .single()
+            .unwrap()
+    }
+
+    /// Gets the time of the first run of the `trigger` after `now`
+    fn next_run_time(
+        trigger: &EventTrigger,
+        now: DateTime<Local>,
+        rng: &mut StdRng,
+    ) -> Option<DateTime<Local>> {
+        get_next_run(trigger, None, now, rng)
+            .unwrap()
+            .map(|next| next.run)
+    }
+
+    fn timer(interval: u64, cron: Option<&str>) -> EventTrigger {
+        EventTrigger::Timer {
+            interval,
+            cron: cron.map(|value| value.to_string()),
+            jitter: None,
+            active_window: None,
+            only_while_live: false,
+            min_chat_messages: 0,
+        }
+    }
+
+    /// Tests that invalid cron expressions and inverted jitter ranges are rejected
+    #[test]
+    fn test_validate_timer_trigger() {
+        assert!(validate_timer_trigger(&timer(60, None)).is_ok());
+        assert!(validate_timer_trigger(&timer(0, Some("30 18 * * *"))).is_ok());
+        assert!(validate_timer_trigger(&timer(0, Some("not cron"))).is_err());
+
+        let jitter_timer = |min: i64, max: i64| EventTrigger::Timer {
+            interval: 60,
+            cron: None,
+            jitter: Some(MinMax { min, max }),
+            active_window: None,
+            only_while_live: false,
+            min_chat_messages: 0,
+        };
+
+        assert!(validate_timer_trigger(&jitter_timer(-10, 10)).is_ok());
+        assert!(validate_timer_trigger(&jitter_timer(5, 5)).is_ok());
+        assert!(validate_timer_trigger(&jitter_timer(10, -10)).is_err());
+
+        // Other triggers have no schedule to validate
+        assert!(validate_timer_trigger(&EventTrigger::Follow).is_ok());
+    }
+
+    #[test]
+    fn test_next_run_interval() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let now = local_time(12, 0, 30);
+
+        let next = next_run_time(&timer(60, None), now, &mut rng);
+        assert_eq!(next, Some(local_time(12, 1, 0)));
+
+        // Zero interval timers should never run
+        let next = next_run_time(&timer(0, None), now, &mut rng);
+        assert_eq!(next, None);
+    }
+
+    #[test]
+    fn test_next_run_cron() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let now = local_time(12, 0, 30);
+
+        // Standard 5 field expression
+        let next = next_run_time(&timer(0, Some("30 18 * * *")), now, &mut rng);
+        assert_eq!(next, Some(local_time(18, 30, 0)));
+
+        // Expression including seconds
+        let next = next_run_time(&timer(0, Some("15 */5 * * * *")), now, &mut rng);
+        assert_eq!(next, Some(local_time(12, 5, 15)));
+
+        // Cron takes priority over the interval
+        let next = next_run_time(&timer(60, Some("0 13 * * *")), now, &mut rng);
+        assert_eq!(next, Some(local_time(13, 0, 0)));
+
+        assert!(get_next_run(&timer(0, Some("not cron")), None, now, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_next_run_jitter() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let now = local_time(12, 0, 30);
+
+        let trigger = EventTrigger::Timer {
+            interval: 3600,
+            cron: None,
+            jitter: Some(MinMax {
+                min: -120,
+                max: 300,
+            }),
+            active_window: None,
+            only_while_live: false,
+            min_chat_messages: 0,
+        };
+
+        for _ in 0..50 {
+            let next = get_next_run(&trigger, None, now, &mut rng)
+                .unwrap()
+                .unwrap()
+                .run;
+            assert!(next >= local_time(12, 58, 0));
+            assert!(next <= local_time(13, 5, 0));
+        }
+    }
+
+    /// Tests that runs moved early by the jitter don't schedule the same
+    /// interval time again when scheduling the following run
+    #[test]
+    fn test_next_run_jitter_consecutive() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let trigger = EventTrigger::Timer {
+            interval: 3600,
+            cron: None,
+            jitter: Some(MinMax {
+                min: -600,
+                max: -60,
+            }),
+            active_window: None,
+            only_while_live: false,
+            min_chat_messages: 0,
+        };
+
+        let mut now = local_time(12, 0, 30);
+        let mut previous: Option<ScheduledRun> = None;
+
+        for _ in 0..20 {
+            let next = get_next_run(&trigger, previous.map(|run| run.base), now, &mut rng)
+                .unwrap()
+                .unwrap();
+
+            // Runs before the interval time but never before the current time
+            assert!(next.run < next.base);
+            assert!(next.run >= now);
+
+            // Each run is for the following interval
+            if let Some(previous) = previous {
+                assert_eq!(next.base - previous.base, chrono::TimeDelta::hours(1));
+            }
+
+            // Run the event at the scheduled time
+            now = next.run;
+            previous = Some(next);
+        }
+    }
+
+    #[test]
+    fn test_next_run_active_window() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let trigger = EventTrigger::Timer {
+            interval: 3600,
+            cron: None,
+            jitter: None,
+            active_window: Some(TimerActiveWindow {
+                start: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+                end: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+            }),
+            only_while_live: false,
+            min_chat_messages: 0,
+        };
+
+        // Before the window should wait for the window start
+        let next = next_run_time(&trigger, local_time(12, 0, 30), &mut rng);
+        assert_eq!(next, Some(local_time(18, 0, 0)));
+
+        // Within the window should run at the next interval
+        let next = next_run_time(&trigger, local_time(19, 0, 30), &mut rng);
+        assert_eq!(next, Some(local_time(20, 0, 0)));
+
+        // After the window should wait for the next day
+        let next = next_run_time(&trigger, local_time(22, 30, 0), &mut rng);
+        assert_eq!(
+            next,
+            Some(local_time(18, 0, 0) + chrono::TimeDelta::days(1))
+        );
+    }
+
+    #[test]
+    fn test_active_window_wraps_midnight() {
+        let active_window = TimerActiveWindow {
+            start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(2, 0, 0).unwrap(),
+        };
+
+        assert!(active_window.contains(NaiveTime::from_hms_opt(23, 0, 0).unwrap()));
+        assert!(active_window.contains(NaiveTime::from_hms_opt(1, 0, 0).unwrap()));
+        assert!(!active_window.contains(NaiveTime::from_hms_opt(2, 0, 0).unwrap()));
+        assert!(!active_window.contains(NaiveTime::from_hms_opt(12, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_active_window_deserialize() {
+        let active_window: TimerActiveWindow =
+            serde_json::from_str(r#"{"start":"18:00","end":"23:00"}"#).unwrap();
+
+        assert_eq!(
+            active_window.start,
+            NaiveTime::from_hms_opt(18, 0, 0).unwrap()
+        );
+        assert_eq!(
+            active_window.end,
+            NaiveTime::from_hms_opt(23, 0, 0).unwrap()
+        );
+    }
 }
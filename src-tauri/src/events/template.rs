@@ -0,0 +1,1595 @@
+//! # Template
+//!
+//! Template language used by chat messages and replies. Tags are wrapped
+//! in `$( )`, a literal `$(` can be written as `$$(`
+//!
+//! - Variables: `$(user)`
+//! - Filters: `$(bits | number)`, `$(viewers | pluralize:"viewer")`, `$(userInput | default:"nothing")`
+//! - Conditionals: `$(if bits >= 100)big$(elif bits > 10)medium$(else)small$(end)`
+//! - Random choices: `$(random)Hello$(or)Hi$(or)Hey$(end)`
+
+use super::{
+    matching::{EventData, EventInputData},
+    pattern::compile_pattern,
+};
+use crate::database::entity::events::{
+    EventFeedback, EventModel, EventOutcome, EventOutcomes, EventTrigger, EventTriggerType,
+};
+use crate::twitch::models::{TwitchEventUser, TwitchPollChoice, TwitchPredictionOutcome};
+use anyhow::{bail, Context};
+use rand::Rng;
+use sea_orm::{DatabaseConnection, Iterable};
+use std::{cmp::Ordering, collections::HashMap, fmt::Display};
+use twitch_api::{
+    eventsub::channel::chat::Fragment,
+    types::{DisplayName, MsgId, SubscriptionTier, UserId, UserName},
+};
+
+/// Variables available to all templates
+const VARIABLES: &[&str] = &["user", "userId", "userDisplayName", "remainingUses"];
+
+/// Variables available to the templates of events with the `trigger_type`, these
+/// are the variables set for the input data the trigger produces (Webhook input
+/// data is provided by the caller so any of the variables may be set)
+fn trigger_variables(trigger_type: &EventTriggerType) -> Vec<&'static str> {
+    let variables: &[&str] = match trigger_type {
+        EventTriggerType::Redeem => &[
+            "rewardName",
+            "rewardCost",
+            "rewardId",
+            "redemptionId",
+            "userInput",
+        ],
+        EventTriggerType::Command
+        | EventTriggerType::ChatPattern
+        | EventTriggerType::FirstMessage => &["userInput", "args", "messageId", "cheer"],
+        EventTriggerType::Subscription => &["tier", "isGift"],
+        EventTriggerType::GiftedSubscription => &["tier", "cumulativeTotal", "anonymous", "total"],
+        EventTriggerType::ReSubscription => &[
+            "tier",
+            "cumulativeMonths",
+            "durationMonths",
+            "streakMonths",
+            "userInput",
+        ],
+        EventTriggerType::Bits => &["bits", "anonymous", "userInput"],
+        EventTriggerType::Raid | EventTriggerType::ShoutoutReceive => &["viewers"],
+        EventTriggerType::AdBreakBegin => &["duration"],
+        EventTriggerType::HypeTrainBegin
+        | EventTriggerType::HypeTrainProgress
+        | EventTriggerType::HypeTrainEnd => &["level", "total", "progress", "goal"],
+        EventTriggerType::PollBegin
+        | EventTriggerType::PollProgress
+        | EventTriggerType::PollEnd => &["title", "choices", "winner", "totalVotes"],
+        EventTriggerType::PredictionBegin
+        | EventTriggerType::PredictionProgress
+        | EventTriggerType::PredictionLock
+        | EventTriggerType::PredictionEnd => &["title", "outcomes", "winner", "totalPoints"],
+        EventTriggerType::CharityDonation => &["charity", "amount", "currency"],
+        EventTriggerType::Follow
+        | EventTriggerType::Timer
+        | EventTriggerType::StreamOnline
+        | EventTriggerType::StreamOffline => &[],
+        EventTriggerType::Webhook => {
+            let mut variables: Vec<&str> = EventTriggerType::iter()
+                .filter(|trigger_type| *trigger_type != EventTriggerType::Webhook)
+                .flat_map(|trigger_type| trigger_variables(&trigger_type))
+                .collect();
+            variables.sort_unstable();
+            variables.dedup();
+            return variables;
+        }
+    };
+
+    variables.to_vec()
+}
+
+/// Additional variables available to feedback reply templates
+const FEEDBACK_VARIABLES: &[&str] = &["remaining"];
+
+/// Value of a template variable
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateValue {
+    Text(String),
+    Number(f64),
+    Bool(bool),
+}
+
+impl TemplateValue {
+    /// Numeric value, text is parsed as a number
+    fn as_number(&self) -> Option<f64> {
+        match self {
+            TemplateValue::Text(value) => value.trim().parse().ok(),
+            TemplateValue::Number(value) => Some(*value),
+            TemplateValue::Bool(_) => None,
+        }
+    }
+
+    fn is_truthy(&self) -> bool {
+        match self {
+            TemplateValue::Text(value) => !value.is_empty(),
+            TemplateValue::Number(value) => *value != 0.0,
+            TemplateValue::Bool(value) => *value,
+        }
+    }
+}
+
+impl Display for TemplateValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateValue::Text(value) => f.write_str(value),
+            // Whole numbers are shown without a decimal point
+            TemplateValue::Number(value) if value.fract() == 0.0 && value.abs() < 1e15 => {
+                write!(f, "{}", *value as i64)
+            }
+            TemplateValue::Number(value) => write!(f, "{value}"),
+            TemplateValue::Bool(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl From<String> for TemplateValue {
+    fn from(value: String) -> Self {
+        TemplateValue::Text(value)
+    }
+}
+
+impl From<&str> for TemplateValue {
+    fn from(value: &str) -> Self {
+        TemplateValue::Text(value.to_string())
+    }
+}
+
+impl From<i64> for TemplateValue {
+    fn from(value: i64) -> Self {
+        TemplateValue::Number(value as f64)
+    }
+}
+
+impl From<bool> for TemplateValue {
+    fn from(value: bool) -> Self {
+        TemplateValue::Bool(value)
+    }
+}
+
+/// Variables available when rendering a template
+#[derive(Debug, Default, Clone)]
+pub struct TemplateContext {
+    variables: HashMap<String, TemplateValue>,
+}
+
+impl TemplateContext {
+    /// Creates a context with the variables from the `event_data`
+    pub fn from_event_data(event_data: &EventData) -> Self {
+        let mut ctx = Self::default();
+
+        match &event_data.user {
+            Some(user) => {
+                ctx.set("user", user.name.to_string());
+                ctx.set("userId", user.id.to_string());
+                ctx.set("userDisplayName", user.display_name.to_string());
+            }
+            None => ctx.set("user", "Anonymous"),
+        }
+
+        if let Some(remaining_uses) = event_data.remaining_uses {
+            ctx.set("remainingUses", remaining_uses as i64);
+        }
+
+        match &event_data.input_data {
+            EventInputData::Redeem {
+                redemption_id,
+                reward_id,
+                reward_name,
+                cost,
+                user_input,
+            } => {
+                ctx.set("redemptionId", redemption_id.as_str());
+                ctx.set("rewardId", reward_id.as_str());
+                ctx.set("rewardName", reward_name.as_str());
+                ctx.set("rewardCost", *cost);
+                ctx.set("userInput", user_input.as_str());
+            }
+            EventInputData::Bits {
+                bits,
+                anonymous,
+                message,
+            } => {
+                ctx.set("bits", *bits);
+                ctx.set("anonymous", *anonymous);
+                ctx.set("userInput", message.as_str());
+            }
+            EventInputData::Subscription { tier, is_gift } => {
+                ctx.set("tier", format_subscription_tier(tier));
+                ctx.set("isGift", *is_gift);
+            }
+            EventInputData::GiftedSubscription {
+                tier,
+                cumulative_total,
+                anonymous,
+                total,
+            } => {
+                ctx.set("tier", format_subscription_tier(tier));
+                ctx.set_optional("cumulativeTotal", *cumulative_total);
+                ctx.set("anonymous", *anonymous);
+                ctx.set("total", *total);
+            }
+            EventInputData::ReSubscription {
+                cumulative_months,
+                duration_months,
+                message,
+                streak_months,
+                tier,
+            } => {
+                ctx.set("cumulativeMonths", *cumulative_months);
+                ctx.set("durationMonths", *duration_months);
+                ctx.set("userInput", message.as_str());
+                ctx.set_optional("streakMonths", *streak_months);
+                ctx.set("tier", format_subscription_tier(tier));
+            }
+            EventInputData::Chat {
+                message_id,
+                message,
+                cheer,
+                captures,
+                args,
+                ..
+            } => {
+                ctx.set("messageId", message_id.to_string());
+                ctx.set("userInput", message.as_str());
+                ctx.set_optional("cheer", cheer.map(|value| value as i64));
+                ctx.set("args", args.join(" "));
+
+                for (index, value) in args.iter().enumerate() {
+                    ctx.set(format!("arg{}", index + 1), value.as_str());
+                }
+
+                for (name, value) in captures {
+                    ctx.set(name.as_str(), value.as_str());
+                }
+            }
+            EventInputData::Raid { viewers } => {
+                ctx.set("viewers", *viewers);
+            }
+            EventInputData::AdBreakBegin { duration_seconds } => {
+                ctx.set("duration", *duration_seconds as i64);
+            }
+            EventInputData::ShoutoutReceive { viewer_count } => {
+                ctx.set("viewers", *viewer_count);
+            }
+            EventInputData::HypeTrain {
+                level,
+                total,
+                progress,
+                goal,
+            } => {
+                ctx.set("level", *level);
+                ctx.set("total", *total);
+                ctx.set_optional("progress", *progress);
+                ctx.set_optional("goal", *goal);
+            }
+            EventInputData::Poll {
+                title,
+                choices,
+                winning_choice,
+                total_votes,
+            } => {
+                let choices: Vec<&str> = choices.iter().map(|value| value.title.as_str()).collect();
+
+                ctx.set("title", title.as_str());
+                ctx.set("choices", choices.join(", "));
+                ctx.set_optional("winner", winning_choice.clone());
+                ctx.set("totalVotes", *total_votes);
+            }
+            EventInputData::Prediction {
+                title,
+                outcomes,
+                winning_outcome,
+                total_points,
+            } => {
+                let outcomes: Vec<&str> =
+                    outcomes.iter().map(|value| value.title.as_str()).collect();
+
+                ctx.set("title", title.as_str());
+                ctx.set("outcomes", outcomes.join(", "));
+                ctx.set_optional("winner", winning_outcome.clone());
+                ctx.set("totalPoints", *total_points);
+            }
+            EventInputData::CharityDonation {
+                charity_name,
+                amount,
+                decimal_places,
+                currency,
+            } => {
+                let decimal_places = (*decimal_places).max(0) as usize;
+
+                ctx.set("charity", charity_name.as_str());
+                ctx.set("amount", format!("{amount:.decimal_places$}"));
+                ctx.set("currency", currency.as_str());
+            }
+            EventInputData::None => {}
+        }
+
+        ctx
+    }
+
+    pub fn set(&mut self, name: impl Into<String>, value: impl Into<TemplateValue>) {
+        self.variables.insert(name.into(), value.into());
+    }
+
+    fn set_optional(&mut self, name: &str, value: Option<impl Into<TemplateValue>>) {
+        if let Some(value) = value {
+            self.set(name, value);
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&TemplateValue> {
+        self.variables.get(name)
+    }
+}
+
+fn format_subscription_tier(tier: &SubscriptionTier) -> &'static str {
+    match tier {
+        SubscriptionTier::Tier1 => "Tier 1",
+        SubscriptionTier::Tier2 => "Tier 2",
+        SubscriptionTier::Tier3 => "Tier 3",
+        SubscriptionTier::Prime => "Prime",
+        SubscriptionTier::Other(_) => "Other",
+    }
+}
+
+/// Parsed template
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    /// Literal text
+    Text(String),
+    /// Variable with filters applied
+    Expr(Expr),
+    /// Conditional branches, the first branch with a matching
+    /// condition is rendered otherwise the fallback is rendered
+    If {
+        branches: Vec<(Condition, Vec<Node>)>,
+        fallback: Vec<Node>,
+    },
+    /// One of the choices picked at random
+    Random(Vec<Vec<Node>>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Expr {
+    variable: String,
+    filters: Vec<Filter>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+    Upper,
+    Lower,
+    Capitalize,
+    Trim,
+    /// Number with thousands separators and optional decimal places
+    Number(Option<usize>),
+    /// Singular word when the value is 1 otherwise the plural word
+    Pluralize {
+        singular: String,
+        plural: String,
+    },
+    /// Value to use when the variable is missing or empty
+    Default(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Condition {
+    negate: bool,
+    left: Operand,
+    comparison: Option<(Operator, Operand)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Variable(String),
+    Literal(TemplateValue),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+/// Parsed content of a `$( )` tag
+enum Tag {
+    Expr(Expr),
+    If(Condition),
+    Elif(Condition),
+    Else,
+    Random,
+    Or,
+    End,
+}
+
+/// Tag ending a block of nodes
+enum Terminator {
+    Elif(Condition),
+    Else,
+    Or,
+    End,
+}
+
+impl Terminator {
+    fn name(&self) -> &'static str {
+        match self {
+            Terminator::Elif(_) => "elif",
+            Terminator::Else => "else",
+            Terminator::Or => "or",
+            Terminator::End => "end",
+        }
+    }
+}
+
+/// Token within a `$( )` tag
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Str(String),
+    Pipe,
+    Colon,
+    Not,
+    Operator(Operator),
+}
+
+impl Template {
+    /// Parses a template
+    pub fn parse(template: &str) -> anyhow::Result<Template> {
+        let mut parser = Parser {
+            input: template,
+            position: 0,
+        };
+
+        let (nodes, terminator) = parser.parse_nodes()?;
+        if let Some(terminator) = terminator {
+            bail!(
+                "unexpected $({}) without a matching $(if) or $(random)",
+                terminator.name()
+            );
+        }
+
+        Ok(Template { nodes })
+    }
+
+    /// Names of all the variables referenced by the template
+    pub fn variables(&self) -> Vec<&str> {
+        let mut variables = Vec::new();
+        collect_variables(&self.nodes, &mut variables);
+        variables
+    }
+
+    /// Renders the template using the variables from the `ctx`, missing
+    /// variables are rendered as empty text
+    pub fn render(&self, ctx: &TemplateContext, rng: &mut impl Rng) -> String {
+        let mut output = String::new();
+        render_nodes(&self.nodes, ctx, rng, &mut output);
+        output
+    }
+}
+
+fn collect_variables<'a>(nodes: &'a [Node], variables: &mut Vec<&'a str>) {
+    for node in nodes {
+        match node {
+            Node::Text(_) => {}
+            Node::Expr(expr) => variables.push(&expr.variable),
+            Node::If { branches, fallback } => {
+                for (condition, nodes) in branches {
+                    for operand in std::iter::once(&condition.left)
+                        .chain(condition.comparison.as_ref().map(|(_, right)| right))
+                    {
+                        if let Operand::Variable(name) = operand {
+                            variables.push(name);
+                        }
+                    }
+
+                    collect_variables(nodes, variables);
+                }
+
+                collect_variables(fallback, variables);
+            }
+            Node::Random(choices) => {
+                for nodes in choices {
+                    collect_variables(nodes, variables);
+                }
+            }
+        }
+    }
+}
+
+fn render_nodes(nodes: &[Node], ctx: &TemplateContext, rng: &mut impl Rng, output: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Expr(expr) => {
+                if let Some(value) = expr.evaluate(ctx) {
+                    output.push_str(&value.to_string());
+                }
+            }
+            Node::If { branches, fallback } => {
+                let nodes = branches
+                    .iter()
+                    .find(|(condition, _)| condition.evaluate(ctx))
+                    .map(|(_, nodes)| nodes)
+                    .unwrap_or(fallback);
+
+                render_nodes(nodes, ctx, rng, output);
+            }
+            Node::Random(choices) => {
+                if choices.is_empty() {
+                    continue;
+                }
+
+                let index = rng.gen_range(0..choices.len());
+                render_nodes(&choices[index], ctx, rng, output);
+            }
+        }
+    }
+}
+
+impl Expr {
+    fn evaluate(&self, ctx: &TemplateContext) -> Option<TemplateValue> {
+        let value = ctx.get(&self.variable).cloned();
+        self.filters
+            .iter()
+            .fold(value, |value, filter| filter.apply(value))
+    }
+}
+
+impl Filter {
+    fn apply(&self, value: Option<TemplateValue>) -> Option<TemplateValue> {
+        if let Filter::Default(default) = self {
+            return match value {
+                Some(value) if !value.to_string().is_empty() => Some(value),
+                _ => Some(TemplateValue::Text(default.clone())),
+            };
+        }
+
+        let value = value?;
+
+        let value = match self {
+            Filter::Upper => TemplateValue::Text(value.to_string().to_uppercase()),
+            Filter::Lower => TemplateValue::Text(value.to_string().to_lowercase()),
+            Filter::Capitalize => {
+                let text = value.to_string();
+                let mut chars = text.chars();
+                let text = match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => text,
+                };
+
+                TemplateValue::Text(text)
+            }
+            Filter::Trim => TemplateValue::Text(value.to_string().trim().to_string()),
+            Filter::Number(decimal_places) => match value.as_number() {
+                Some(number) => TemplateValue::Text(format_number(number, *decimal_places)),
+                None => value,
+            },
+            Filter::Pluralize { singular, plural } => {
+                let word = if value.as_number() == Some(1.0) {
+                    singular
+                } else {
+                    plural
+                };
+
+                TemplateValue::Text(word.clone())
+            }
+            Filter::Default(_) => value,
+        };
+
+        Some(value)
+    }
+}
+
+/// Formats a number with thousands separators, when `decimal_places` is not
+/// specified whole numbers are formatted without decimals
+fn format_number(number: f64, decimal_places: Option<usize>) -> String {
+    let decimal_places = match decimal_places {
+        Some(value) => value,
+        None if number.fract() == 0.0 => 0,
+        None => 2,
+    };
+
+    let formatted = format!("{:.decimal_places$}", number.abs());
+    let (whole, fraction) = match formatted.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (formatted.as_str(), None),
+    };
+
+    let mut output = String::new();
+    if number < 0.0 {
+        output.push('-');
+    }
+
+    for (index, digit) in whole.chars().enumerate() {
+        if index > 0 && (whole.len() - index) % 3 == 0 {
+            output.push(',');
+        }
+        output.push(digit);
+    }
+
+    if let Some(fraction) = fraction {
+        output.push('.');
+        output.push_str(fraction);
+    }
+
+    output
+}
+
+impl Condition {
+    fn evaluate(&self, ctx: &TemplateContext) -> bool {
+        let left = self.left.evaluate(ctx);
+
+        let result = match &self.comparison {
+            None => left.is_some_and(|value| value.is_truthy()),
+            Some((operator, right)) => {
+                let right = right.evaluate(ctx);
+                compare_values(left.as_ref(), right.as_ref(), *operator)
+            }
+        };
+
+        result != self.negate
+    }
+}
+
+impl Operand {
+    fn evaluate(&self, ctx: &TemplateContext) -> Option<TemplateValue> {
+        match self {
+            Operand::Variable(name) => ctx.get(name).cloned(),
+            Operand::Literal(value) => Some(value.clone()),
+        }
+    }
+}
+
+/// Compares two values, values are compared as numbers when both values
+/// are numeric otherwise they are compared as text (Ignoring case)
+fn compare_values(
+    left: Option<&TemplateValue>,
+    right: Option<&TemplateValue>,
+    operator: Operator,
+) -> bool {
+    let numbers = (
+        left.and_then(TemplateValue::as_number),
+        right.and_then(TemplateValue::as_number),
+    );
+
+    let ordering = match numbers {
+        (Some(left), Some(right)) => left.partial_cmp(&right),
+        _ => {
+            let left = left.map(|value| value.to_string().to_lowercase());
+            let right = right.map(|value| value.to_string().to_lowercase());
+
+            match operator {
+                // Missing values are equal to empty text
+                Operator::Eq | Operator::Ne => {
+                    Some(left.unwrap_or_default().cmp(&right.unwrap_or_default()))
+                }
+                // Missing values cannot be ordered
+                _ => left.zip(right).map(|(left, right)| left.cmp(&right)),
+            }
+        }
+    };
+
+    let ordering = match ordering {
+        Some(value) => value,
+        None => return false,
+    };
+
+    match operator {
+        Operator::Eq => ordering == Ordering::Equal,
+        Operator::Ne => ordering != Ordering::Equal,
+        Operator::Gt => ordering == Ordering::Greater,
+        Operator::Ge => ordering != Ordering::Less,
+        Operator::Lt => ordering == Ordering::Less,
+        Operator::Le => ordering != Ordering::Greater,
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    /// Parses nodes until the end of the input or a block terminating tag,
+    /// the terminating tag is returned when found
+    fn parse_nodes(&mut self) -> anyhow::Result<(Vec<Node>, Option<Terminator>)> {
+        let mut nodes = Vec::new();
+        let mut text = String::new();
+
+        loop {
+            let rest = &self.input[self.position..];
+
+            let start = match rest.find("$(") {
+                Some(value) => value,
+                None => {
+                    text.push_str(rest);
+                    self.position = self.input.len();
+                    break;
+                }
+            };
+
+            // Escaped "$$(" is written as a literal "$("
+            if rest[..start].ends_with('$') {
+                text.push_str(&rest[..start - 1]);
+                text.push_str("$(");
+                self.position += start + 2;
+                continue;
+            }
+
+            text.push_str(&rest[..start]);
+
+            let tag_start = self.position + start;
+            self.position = tag_start + 2;
+
+            let tag = self.parse_tag().with_context(|| {
+                format!(
+                    "invalid tag at character {}",
+                    self.input[..tag_start].chars().count() + 1
+                )
+            })?;
+
+            if !text.is_empty() {
+                nodes.push(Node::Text(std::mem::take(&mut text)));
+            }
+
+            let terminator = match tag {
+                Tag::Expr(expr) => {
+                    nodes.push(Node::Expr(expr));
+                    continue;
+                }
+                Tag::If(condition) => {
+                    nodes.push(self.parse_if(condition)?);
+                    continue;
+                }
+                Tag::Random => {
+                    nodes.push(self.parse_random()?);
+                    continue;
+                }
+                Tag::Elif(condition) => Terminator::Elif(condition),
+                Tag::Else => Terminator::Else,
+                Tag::Or => Terminator::Or,
+                Tag::End => Terminator::End,
+            };
+
+            return Ok((nodes, Some(terminator)));
+        }
+
+        if !text.is_empty() {
+            nodes.push(Node::Text(text));
+        }
+
+        Ok((nodes, None))
+    }
+
+    fn parse_if(&mut self, condition: Condition) -> anyhow::Result<Node> {
+        let mut branches = Vec::new();
+        let mut condition = condition;
+
+        loop {
+            let (nodes, terminator) = self.parse_nodes()?;
+            branches.push((condition, nodes));
+
+            match terminator {
+                Some(Terminator::Elif(next)) => condition = next,
+                Some(Terminator::Else) => {
+                    let (fallback, terminator) = self.parse_nodes()?;
+                    return match terminator {
+                        Some(Terminator::End) => Ok(Node::If { branches, fallback }),
+                        Some(terminator) => {
+                            bail!("unexpected $({}) after $(else)", terminator.name())
+                        }
+                        None => bail!("missing $(end) for $(if)"),
+                    };
+                }
+                Some(Terminator::End) => {
+                    return Ok(Node::If {
+                        branches,
+                        fallback: Vec::new(),
+                    })
+                }
+                Some(Terminator::Or) => bail!("unexpected $(or) within $(if)"),
+                None => bail!("missing $(end) for $(if)"),
+            }
+        }
+    }
+
+    fn parse_random(&mut self) -> anyhow::Result<Node> {
+        let mut choices = Vec::new();
+
+        loop {
+            let (nodes, terminator) = self.parse_nodes()?;
+            choices.push(nodes);
+
+            match terminator {
+                Some(Terminator::Or) => {}
+                Some(Terminator::End) => return Ok(Node::Random(choices)),
+                Some(terminator) => {
+                    bail!("unexpected $({}) within $(random)", terminator.name())
+                }
+                None => bail!("missing $(end) for $(random)"),
+            }
+        }
+    }
+
+    /// Parses the tag content following a "$(" up to the closing ")"
+    fn parse_tag(&mut self) -> anyhow::Result<Tag> {
+        let rest = &self.input[self.position..];
+        let mut in_string = false;
+        let mut escaped = false;
+        let mut end = None;
+
+        for (index, char) in rest.char_indices() {
+            match char {
+                _ if escaped => escaped = false,
+                '\\' if in_string => escaped = true,
+                '"' => in_string = !in_string,
+                ')' if !in_string => {
+                    end = Some(index);
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        let end = end.context("missing closing \")\"")?;
+        let content = &rest[..end];
+        self.position += end + 1;
+
+        parse_tag_content(content)
+    }
+}
+
+fn parse_tag_content(content: &str) -> anyhow::Result<Tag> {
+    let tokens = tokenize(content)?;
+    let mut tokens = tokens.into_iter().peekable();
+
+    let first = match tokens.next() {
+        Some(Token::Word(word)) => word,
+        Some(token) => bail!("unexpected {token:?}"),
+        None => bail!("empty tag"),
+    };
+
+    let tag = match first.as_str() {
+        "if" => Tag::If(parse_condition(&mut tokens)?),
+        "elif" => Tag::Elif(parse_condition(&mut tokens)?),
+        "else" => Tag::Else,
+        "random" => Tag::Random,
+        "or" => Tag::Or,
+        "end" => Tag::End,
+        _ => {
+            validate_variable_name(&first)?;
+
+            let mut filters = Vec::new();
+            while let Some(token) = tokens.next() {
+                if token != Token::Pipe {
+                    bail!("expected \"|\" before filter");
+                }
+
+                filters.push(parse_filter(&mut tokens)?);
+            }
+
+            Tag::Expr(Expr {
+                variable: first,
+                filters,
+            })
+        }
+    };
+
+    if let Some(token) = tokens.next() {
+        bail!("unexpected {token:?}");
+    }
+
+    Ok(tag)
+}
+
+fn validate_variable_name(name: &str) -> anyhow::Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || char == '_')
+    {
+        bail!("invalid variable name \"{name}\"");
+    }
+
+    Ok(())
+}
+
+fn parse_filter(
+    tokens: &mut std::iter::Peekable<impl Iterator<Item = Token>>,
+) -> anyhow::Result<Filter> {
+    let name = match tokens.next() {
+        Some(Token::Word(word)) => word,
+        _ => bail!("expected filter name after \"|\""),
+    };
+
+    let mut args = Vec::new();
+
+    while tokens.next_if_eq(&Token::Colon).is_some() {
+        match tokens.next() {
+            Some(Token::Word(value)) | Some(Token::Str(value)) => args.push(value),
+            _ => bail!("expected argument after \":\" for filter \"{name}\""),
+        }
+    }
+
+    let expect_args = |min: usize, max: usize| -> anyhow::Result<()> {
+        if args.len() < min || args.len() > max {
+            bail!("filter \"{name}\" expects {min} to {max} arguments");
+        }
+        Ok(())
+    };
+
+    let filter = match name.as_str() {
+        "upper" => {
+            expect_args(0, 0)?;
+            Filter::Upper
+        }
+        "lower" => {
+            expect_args(0, 0)?;
+            Filter::Lower
+        }
+        "capitalize" => {
+            expect_args(0, 0)?;
+            Filter::Capitalize
+        }
+        "trim" => {
+            expect_args(0, 0)?;
+            Filter::Trim
+        }
+        "number" => {
+            expect_args(0, 1)?;
+            let decimal_places = match args.first() {
+                Some(value) => Some(
+                    value
+                        .parse()
+                        .ok()
+                        .filter(|value| *value <= 10)
+                        .context("number filter decimal places must be from 0 to 10")?,
+                ),
+                None => None,
+            };
+
+            Filter::Number(decimal_places)
+        }
+        "pluralize" => {
+            expect_args(1, 2)?;
+            let singular = args[0].clone();
+            let plural = args
+                .get(1)
+                .cloned()
+                .unwrap_or_else(|| format!("{singular}s"));
+
+            Filter::Pluralize { singular, plural }
+        }
+        "default" => {
+            expect_args(1, 1)?;
+            Filter::Default(args[0].clone())
+        }
+        _ => bail!("unknown filter \"{name}\""),
+    };
+
+    Ok(filter)
+}
+
+fn parse_condition(
+    tokens: &mut std::iter::Peekable<impl Iterator<Item = Token>>,
+) -> anyhow::Result<Condition> {
+    let negate = tokens.next_if_eq(&Token::Not).is_some();
+    let left = parse_operand(tokens.next())?;
+
+    let comparison = match tokens.next() {
+        Some(Token::Operator(operator)) => {
+            if negate {
+                bail!("\"!\" cannot be used with a comparison");
+            }
+
+            Some((operator, parse_operand(tokens.next())?))
+        }
+        Some(token) => bail!("unexpected {token:?} in condition"),
+        None => None,
+    };
+
+    Ok(Condition {
+        negate,
+        left,
+        comparison,
+    })
+}
+
+fn parse_operand(token: Option<Token>) -> anyhow::Result<Operand> {
+    match token {
+        Some(Token::Str(value)) => Ok(Operand::Literal(TemplateValue::Text(value))),
+        Some(Token::Word(word)) => {
+            if let Ok(number) = word.parse::<f64>() {
+                return Ok(Operand::Literal(TemplateValue::Number(number)));
+            }
+
+            match word.as_str() {
+                "true" => Ok(Operand::Literal(TemplateValue::Bool(true))),
+                "false" => Ok(Operand::Literal(TemplateValue::Bool(false))),
+                _ => {
+                    validate_variable_name(&word)?;
+                    Ok(Operand::Variable(word))
+                }
+            }
+        }
+        Some(token) => bail!("unexpected {token:?} in condition"),
+        None => bail!("missing value in condition"),
+    }
+}
+
+fn tokenize(content: &str) -> anyhow::Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = content.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        let token = match char {
+            _ if char.is_whitespace() => continue,
+            '|' => Token::Pipe,
+            ':' => Token::Colon,
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => value.extend(chars.next()),
+                        Some('"') => break,
+                        Some(char) => value.push(char),
+                        None => bail!("unclosed string"),
+                    }
+                }
+                Token::Str(value)
+            }
+            '=' if chars.next_if_eq(&'=').is_some() => Token::Operator(Operator::Eq),
+            '!' if chars.next_if_eq(&'=').is_some() => Token::Operator(Operator::Ne),
+            '!' => Token::Not,
+            '>' if chars.next_if_eq(&'=').is_some() => Token::Operator(Operator::Ge),
+            '>' => Token::Operator(Operator::Gt),
+            '<' if chars.next_if_eq(&'=').is_some() => Token::Operator(Operator::Le),
+            '<' => Token::Operator(Operator::Lt),
+            _ if char.is_alphanumeric() || char == '_' || char == '-' || char == '.' => {
+                let mut word = String::from(char);
+                while let Some(char) = chars.next_if(|char| {
+                    char.is_alphanumeric() || *char == '_' || *char == '-' || *char == '.'
+                }) {
+                    word.push(char);
+                }
+                Token::Word(word)
+            }
+            _ => bail!("unexpected character \"{char}\""),
+        };
+
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+/// Renders a `template` using the variables from the `ctx`, templates
+/// that fail to parse are rendered as-is
+pub fn render_template(template: &str, ctx: &TemplateContext) -> String {
+    match Template::parse(template) {
+        Ok(template) => template.render(ctx, &mut rand::thread_rng()),
+        Err(err) => {
+            log::warn!("failed to parse template: {err:?}");
+            template.to_string()
+        }
+    }
+}
+
+/// Parses a template ensuring it only references the available `variables`,
+/// numbered arguments (i.e `arg1`) are available when `args` is available
+fn validate_template(template: &str, variables: &[&str]) -> anyhow::Result<()> {
+    let template = Template::parse(template)?;
+    let has_arguments = variables.contains(&"args");
+
+    for variable in template.variables() {
+        let is_argument = variable
+            .strip_prefix("arg")
+            .is_some_and(|index| !index.is_empty() && index.chars().all(|c| c.is_ascii_digit()));
+
+        if !(variables.contains(&variable) || (has_arguments && is_argument)) {
+            bail!("unknown variable \"{variable}\"");
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates all the templates used by an event (Chat messages, announcements,
+/// timeout reasons and feedback replies) along with the chat pattern of the
+/// trigger
+pub fn validate_event_templates(
+    trigger: &EventTrigger,
+    outcomes: Option<&EventOutcomes>,
+    feedback: Option<&EventFeedback>,
+) -> anyhow::Result<()> {
+    // Named capture groups from chat patterns are available as variables
+    let captures: Vec<String> = match trigger {
+        EventTrigger::ChatPattern { pattern } => compile_pattern(pattern)
+            .context("invalid chat pattern")?
+            .capture_names()
+            .flatten()
+            .map(String::from)
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let mut variables = VARIABLES.to_vec();
+    variables.extend(trigger_variables(&EventTriggerType::from_event_trigger(
+        trigger,
+    )));
+    variables.extend(captures.iter().map(String::as_str));
+
+    if let Some(outcomes) = outcomes {
+        for (index, step) in outcomes.0.iter().enumerate() {
+            validate_outcome_templates(&step.outcome, &variables)
+                .with_context(|| format!("invalid template in outcome step {}", index + 1))?;
+        }
+    }
+
+    if let Some(feedback) = feedback {
+        for (name, template) in [
+            ("cooldown reply", &feedback.cooldown_reply),
+            ("role reply", &feedback.role_reply),
+        ] {
+            if let Some(template) = template {
+                let variables = [variables.as_slice(), FEEDBACK_VARIABLES].concat();
+                validate_template(template, &variables)
+                    .with_context(|| format!("invalid template in {name}"))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the templates of all stored events, events with templates that are
+/// invalid (Created before templates were validated or referencing variables
+/// their trigger doesn't provide) are logged as these templates are rendered
+/// as-is
+pub async fn check_event_templates(db: DatabaseConnection) -> anyhow::Result<()> {
+    for (event, err) in find_invalid_event_templates(&db).await? {
+        log::warn!(
+            "event \"{}\" ({}) has an invalid template: {err:#}",
+            event.name,
+            event.id
+        );
+    }
+
+    Ok(())
+}
+
+/// Finds the stored events with invalid templates along with the
+/// validation error for the event
+async fn find_invalid_event_templates(
+    db: &DatabaseConnection,
+) -> anyhow::Result<Vec<(EventModel, anyhow::Error)>> {
+    let events = EventModel::all(db).await?;
+
+    Ok(events
+        .into_iter()
+        .filter_map(|event| {
+            validate_event_templates(&event.trigger, Some(&event.outcomes), Some(&event.feedback))
+                .err()
+                .map(|err| (event, err))
+        })
+        .collect())
+}
+
+fn validate_outcome_templates(outcome: &EventOutcome, variables: &[&str]) -> anyhow::Result<()> {
+    match outcome {
+        EventOutcome::SendChatMessage(data) => validate_template(&data.template, variables),
+        EventOutcome::Announcement(data) => validate_template(&data.template, variables),
+        EventOutcome::TimeoutUser(data) => validate_template(&data.reason, variables),
+        EventOutcome::Random(data) => data
+            .choices
+            .iter()
+            .try_for_each(|choice| validate_outcome_templates(&choice.outcome, variables)),
+        _ => Ok(()),
+    }
+}
+
+/// Creates sample event data for previewing templates for the `trigger_type`
+pub fn sample_event_data(trigger_type: &EventTriggerType) -> EventData {
+    let input_data = match trigger_type {
+        EventTriggerType::Redeem => EventInputData::Redeem {
+            redemption_id: "sample-redemption-id".to_string(),
+            reward_id: "sample-reward-id".to_string(),
+            reward_name: "Throw Things".to_string(),
+            cost: 500,
+            user_input: "Sample message".to_string(),
+        },
+        EventTriggerType::Command
+        | EventTriggerType::ChatPattern
+        | EventTriggerType::FirstMessage => EventInputData::Chat {
+            message_id: MsgId::from_static("sample-message-id"),
+            message: "!command 5 sample".to_string(),
+            fragments: Vec::<Fragment>::new(),
+            cheer: None,
+            captures: HashMap::new(),
+            args: vec!["5".to_string(), "sample".to_string()],
+        },
+        EventTriggerType::Subscription => EventInputData::Subscription {
+            tier: SubscriptionTier::Tier1,
+            is_gift: false,
+        },
+        EventTriggerType::GiftedSubscription => EventInputData::GiftedSubscription {
+            tier: SubscriptionTier::Tier1,
+            cumulative_total: Some(25),
+            anonymous: false,
+            total: 5,
+        },
+        EventTriggerType::ReSubscription => EventInputData::ReSubscription {
+            cumulative_months: 12,
+            duration_months: 1,
+            message: "Sample message".to_string(),
+            streak_months: Some(6),
+            tier: SubscriptionTier::Tier1,
+        },
+        EventTriggerType::Bits => EventInputData::Bits {
+            bits: 1000,
+            anonymous: false,
+            message: "Sample message".to_string(),
+        },
+        EventTriggerType::Raid => EventInputData::Raid { viewers: 25 },
+        EventTriggerType::AdBreakBegin => EventInputData::AdBreakBegin {
+            duration_seconds: 90,
+        },
+        EventTriggerType::ShoutoutReceive => EventInputData::ShoutoutReceive { viewer_count: 25 },
+        EventTriggerType::HypeTrainBegin
+        | EventTriggerType::HypeTrainProgress
+        | EventTriggerType::HypeTrainEnd => EventInputData::HypeTrain {
+            level: 2,
+            total: 1500,
+            progress: Some(500),
+            goal: Some(1800),
+        },
+        EventTriggerType::PollBegin
+        | EventTriggerType::PollProgress
+        | EventTriggerType::PollEnd => EventInputData::Poll {
+            title: "Sample poll".to_string(),
+            choices: vec![
+                TwitchPollChoice {
+                    title: "Yes".to_string(),
+                    votes: 10,
+                },
+                TwitchPollChoice {
+                    title: "No".to_string(),
+                    votes: 5,
+                },
+            ],
+            winning_choice: Some("Yes".to_string()),
+            total_votes: 15,
+        },
+        EventTriggerType::PredictionBegin
+        | EventTriggerType::PredictionProgress
+        | EventTriggerType::PredictionLock
+        | EventTriggerType::PredictionEnd => EventInputData::Prediction {
+            title: "Sample prediction".to_string(),
+            outcomes: vec![
+                TwitchPredictionOutcome {
+                    id: "sample-outcome-1".to_string(),
+                    title: "Win".to_string(),
+                    users: 10,
+                    channel_points: 5000,
+                },
+                TwitchPredictionOutcome {
+                    id: "sample-outcome-2".to_string(),
+                    title: "Lose".to_string(),
+                    users: 5,
+                    channel_points: 2500,
+                },
+            ],
+            winning_outcome: Some("Win".to_string()),
+            total_points: 7500,
+        },
+        EventTriggerType::CharityDonation => EventInputData::CharityDonation {
+            charity_name: "Sample Charity".to_string(),
+            amount: 10.0,
+            decimal_places: 2,
+            currency: "USD".to_string(),
+        },
+        EventTriggerType::Follow
+        | EventTriggerType::Timer
+        | EventTriggerType::StreamOnline
+        | EventTriggerType::StreamOffline
+        | EventTriggerType::Webhook => EventInputData::None,
+    };
+
+    EventData {
+        user: Some(TwitchEventUser {
+            id: UserId::from_static("sample-user-id"),
+            name: UserName::from_static("sampleuser"),
+            display_name: DisplayName::from_static("SampleUser"),
+        }),
+        input_data,
+        remaining_uses: Some(2),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        find_invalid_event_templates, sample_event_data, trigger_variables,
+        validate_event_templates, Template, TemplateContext, VARIABLES,
+    };
+    use crate::{
+        database::{
+            entity::events::{
+                CreateEvent, EventFeedback, EventModel, EventOutcome, EventOutcomeRandom,
+                EventOutcomeRandomChoice, EventOutcomeSendChat, EventOutcomeStep, EventOutcomes,
+                EventTrigger, EventTriggerType,
+            },
+            mock_database,
+        },
+        events::matching::{EventData, EventInputData},
+        twitch::models::TwitchEventUser,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+    use sea_orm::Iterable;
+    use std::collections::HashMap;
+    use twitch_api::types::{DisplayName, MsgId, UserId, UserName};
+
+    fn render(template: &str, ctx: &TemplateContext) -> String {
+        Template::parse(template)
+            .unwrap()
+            .render(ctx, &mut StdRng::seed_from_u64(0))
+    }
+
+    fn bits_context(bits: i64) -> TemplateContext {
+        TemplateContext::from_event_data(&EventData {
+            user: Some(TwitchEventUser {
+                id: UserId::from_static("mock-user-id"),
+                name: UserName::from_static("mockuser"),
+                display_name: DisplayName::from_static("Mock User"),
+            }),
+            input_data: EventInputData::Bits {
+                bits,
+                anonymous: false,
+                message: "".to_string(),
+            },
+            remaining_uses: None,
+        })
+    }
+
+    fn chat_outcome(template: &str) -> EventOutcomeStep {
+        EventOutcomeStep {
+            delay: 0,
+            outcome: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                template: template.to_string(),
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Tests that existing variable templates render the same as before,
+    /// unknown variables render as empty and "$$(" is a literal "$("
+    #[test]
+    fn test_render_variables() {
+        let ctx = TemplateContext::from_event_data(&EventData {
+            user: None,
+            input_data: EventInputData::Chat {
+                message_id: MsgId::from_static("mock-message-id"),
+                message: "!throw 5 hello".to_string(),
+                fragments: Vec::new(),
+                cheer: None,
+                captures: HashMap::from([("item".to_string(), "hat".to_string())]),
+                args: vec!["5".to_string(), "hello".to_string()],
+            },
+            remaining_uses: Some(2),
+        });
+
+        assert_eq!(
+            render(
+                "$(user) threw $(arg1) $(item)s ($(args)) $(remainingUses)",
+                &ctx
+            ),
+            "Anonymous threw 5 hats (5 hello) 2"
+        );
+        assert_eq!(render("[$(unknown)]", &ctx), "[]");
+        assert_eq!(render("$$(user) costs $5", &ctx), "$(user) costs $5");
+    }
+
+    /// Tests the formatting filters
+    #[test]
+    fn test_render_filters() {
+        let ctx = bits_context(12345);
+
+        assert_eq!(render("$(user | upper)", &ctx), "MOCKUSER");
+        assert_eq!(render("$(user | capitalize)", &ctx), "Mockuser");
+        assert_eq!(render("$(bits | number)", &ctx), "12,345");
+        assert_eq!(render("$(bits | number:2)", &ctx), "12,345.00");
+        assert_eq!(render("$(bits | pluralize:bit)", &ctx), "bits");
+        assert_eq!(
+            render(
+                "$(bits | pluralize:\"person\":\"people\")",
+                &bits_context(1)
+            ),
+            "person"
+        );
+        assert_eq!(
+            render("$(userInput | default:\"no message\")", &ctx),
+            "no message"
+        );
+        assert_eq!(render("$(missing | default:none | upper)", &ctx), "NONE");
+    }
+
+    /// Tests conditionals and random choices
+    #[test]
+    fn test_render_logic() {
+        let template = Template::parse(
+            "$(if bits >= 1000)Big$(elif bits > 10)Medium$(else)Small$(end) $(if !userInput)silent$(end)",
+        )
+        .unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        assert_eq!(template.render(&bits_context(5000), &mut rng), "Big silent");
+        assert_eq!(
+            template.render(&bits_context(100), &mut rng),
+            "Medium silent"
+        );
+        assert_eq!(template.render(&bits_context(1), &mut rng), "Small silent");
+
+        let template = Template::parse("$(random)a$(or)b$(or)c$(end)").unwrap();
+        let ctx = TemplateContext::default();
+        let mut rendered: Vec<String> = (0..100)
+            .map(|seed| template.render(&ctx, &mut StdRng::seed_from_u64(seed)))
+            .collect();
+        rendered.sort();
+        rendered.dedup();
+
+        assert_eq!(rendered, vec!["a", "b", "c"]);
+    }
+
+    /// Tests that invalid templates are rejected
+    #[test]
+    fn test_parse_errors() {
+        for template in [
+            "$(user",
+            "$(user | unknown)",
+            "$(if bits)missing end",
+            "$(end)",
+            "$(random)a$(else)b$(end)",
+            "$(user | pluralize)",
+            "$(user | default:\"unclosed)",
+        ] {
+            assert!(Template::parse(template).is_err(), "{template}");
+        }
+    }
+
+    /// Tests validating the templates of an event, named capture groups of
+    /// chat patterns and "remaining" for feedback are allowed
+    #[test]
+    fn test_validate_event_templates() {
+        let trigger = EventTrigger::ChatPattern {
+            pattern: "throw (?<item>\\w+)".to_string(),
+        };
+
+        let outcomes = EventOutcomes(vec![chat_outcome("$(user) threw $(item) $(arg1)")]);
+        let feedback = EventFeedback {
+            cooldown_reply: Some("Wait $(remaining)s".to_string()),
+            ..Default::default()
+        };
+        validate_event_templates(&trigger, Some(&outcomes), Some(&feedback)).unwrap();
+
+        // Templates nested within random outcomes are checked
+        let outcomes = EventOutcomes(vec![EventOutcomeStep {
+            delay: 0,
+            outcome: EventOutcome::Random(EventOutcomeRandom {
+                choices: vec![EventOutcomeRandomChoice {
+                    weight: 1,
+                    outcome: chat_outcome("$(usr)").outcome,
+                }],
+            }),
+        }]);
+        let err = validate_event_templates(&trigger, Some(&outcomes), None).unwrap_err();
+        assert!(format!("{err:#}").contains("unknown variable \"usr\""));
+
+        // "remaining" is only available to feedback
+        let outcomes = EventOutcomes(vec![chat_outcome("$(remaining)")]);
+        assert!(validate_event_templates(&trigger, Some(&outcomes), None).is_err());
+
+        let outcomes = EventOutcomes(vec![chat_outcome("$(if user)")]);
+        assert!(validate_event_templates(&trigger, Some(&outcomes), None).is_err());
+
+        // Invalid chat patterns are rejected
+        let trigger = EventTrigger::ChatPattern {
+            pattern: "throw (?<item>\\w+".to_string(),
+        };
+        let err = validate_event_templates(&trigger, None, None).unwrap_err();
+        assert!(format!("{err:#}").contains("invalid chat pattern"));
+    }
+
+    /// Tests that the variables for each trigger cover the variables set
+    /// for the sample data of the trigger
+    #[test]
+    fn test_trigger_variables() {
+        for trigger_type in EventTriggerType::iter() {
+            let variables = trigger_variables(&trigger_type);
+            let ctx = TemplateContext::from_event_data(&sample_event_data(&trigger_type));
+
+            for variable in ctx.variables.keys() {
+                let is_argument = variable.starts_with("arg") && variables.contains(&"args");
+                assert!(
+                    VARIABLES.contains(&variable.as_str())
+                        || variables.contains(&variable.as_str())
+                        || is_argument,
+                    "{trigger_type:?} is missing {variable}"
+                );
+            }
+        }
+    }
+
+    /// Tests that templates can only use the variables of their trigger
+    #[test]
+    fn test_validate_trigger_variables() {
+        let outcomes = EventOutcomes(vec![chat_outcome("$(user) gifted $(bits) bits")]);
+
+        validate_event_templates(&EventTrigger::Bits { min_bits: 0 }, Some(&outcomes), None)
+            .unwrap();
+
+        let err =
+            validate_event_templates(&EventTrigger::Follow, Some(&outcomes), None).unwrap_err();
+        assert!(format!("{err:#}").contains("unknown variable \"bits\""));
+
+        // Webhooks provide their own input data
+        validate_event_templates(
+            &EventTrigger::Webhook {
+                slug: None,
+                secret: "secret".to_string(),
+            },
+            Some(&outcomes),
+            None,
+        )
+        .unwrap();
+
+        // Arguments are only available to chat triggers
+        let outcomes = EventOutcomes(vec![chat_outcome("$(arg1)")]);
+        assert!(validate_event_templates(&EventTrigger::Follow, Some(&outcomes), None).is_err());
+    }
+
+    /// Tests that stored events with invalid templates are found
+    #[tokio::test]
+    async fn test_find_invalid_event_templates() {
+        let db = mock_database().await;
+
+        let create = |name: &str, template: &str| CreateEvent {
+            enabled: true,
+            name: name.to_string(),
+            trigger: EventTrigger::Follow,
+            cooldown: Default::default(),
+            outcomes: chat_outcome(template).outcome.into(),
+            outcome_delay: Default::default(),
+            require_role: Default::default(),
+            role_requirements: Default::default(),
+            conditions: Default::default(),
+            feedback: Default::default(),
+            usage_limits: Default::default(),
+        };
+
+        EventModel::create(&db, create("Valid", "Thanks for the follow $(user)"))
+            .await
+            .unwrap();
+        let invalid = EventModel::create(&db, create("Invalid", "$(if user)Thanks"))
+            .await
+            .unwrap();
+
+        let events = find_invalid_event_templates(&db).await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0.id, invalid.id);
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! Internal server for handling OAuth responses and serving the app overlay HTML
 
-use crate::events::EventRecvHandle;
+use crate::events::{queue::OutcomeQueue, EventMessageChannel};
 use crate::state::runtime_app_data::RuntimeAppDataStore;
 use crate::twitch::manager::Twitch;
 use crate::{database::entity::app_data::AppDataModel, storage::Storage};
@@ -17,9 +17,11 @@ mod error;
 pub mod models;
 pub mod routes;
 
+#[allow(clippy::too_many_arguments)]
 pub async fn start_http_server(
     db: DatabaseConnection,
-    event_handle: EventRecvHandle,
+    event_sender: EventMessageChannel,
+    outcome_queue: OutcomeQueue,
     app_handle: AppHandle,
     twitch: Twitch,
     runtime_app_data: RuntimeAppDataStore,
@@ -30,7 +32,8 @@ pub async fn start_http_server(
     // build our application with a single route
     let app = routes::router()
         .layer(Extension(db))
-        .layer(Extension(event_handle))
+        .layer(Extension(event_sender))
+        .layer(Extension(outcome_queue))
         .layer(Extension(app_handle))
         .layer(Extension(twitch))
         .layer(Extension(runtime_app_data))
@@ -5,6 +5,8 @@ use std::{
 };
 
 use axum::{
+    extract::Path,
+    http::StatusCode,
     response::{
         sse::{Event, KeepAlive},
         Sse,
@@ -13,14 +15,15 @@ use axum::{
 };
 use futures::Stream;
 use tokio_stream::wrappers::BroadcastStream;
+use uuid::Uuid;
 
 use crate::{
-    events::{EventMessage, EventRecvHandle},
+    events::{queue::OutcomeQueue, EventMessage, EventMessageChannel},
     state::runtime_app_data::RuntimeAppDataStore,
 };
 
 pub async fn handle_sse(
-    Extension(event_handle): Extension<EventRecvHandle>,
+    Extension(event_sender): Extension<EventMessageChannel>,
     Extension(runtime_app_data): Extension<RuntimeAppDataStore>,
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
     // Increase number of active overlays
@@ -30,7 +33,7 @@ pub async fn handle_sse(
         })
         .await;
 
-    let stream = BroadcastStream::new(event_handle.0);
+    let stream = BroadcastStream::new(event_sender.subscribe());
 
     Sse::new(OverlayEventStream {
         runtime_app_data,
@@ -39,6 +42,21 @@ pub async fn handle_sse(
     .keep_alive(KeepAlive::default())
 }
 
+/// POST /outcomes/:outcome_id/complete
+///
+/// Reports an outcome sent to the overlay as complete, frees the
+/// slot the outcome held in the outcome queue
+pub async fn handle_outcome_complete(
+    Path(outcome_id): Path<Uuid>,
+    Extension(outcome_queue): Extension<OutcomeQueue>,
+) -> StatusCode {
+    if outcome_queue.complete(outcome_id) {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
 /// Wrapper around the event handle to receive events for the runtime  
 ///
 /// Once the connection is dropped the number of active overlays is
@@ -8,12 +8,17 @@ mod data;
 mod events;
 mod oauth;
 mod overlay;
+mod webhook;
 
 pub fn router() -> Router {
     Router::new()
         .route("/oauth", get(oauth::handle_oauth))
         .route("/oauth/complete", post(oauth::handle_oauth_complete))
         .route("/events", get(events::handle_sse))
+        .route(
+            "/outcomes/:outcome_id/complete",
+            post(events::handle_outcome_complete),
+        )
         .route(
             "/calibration",
             post(calibration::handle_calibration_progress),
@@ -31,4 +36,5 @@ pub fn router() -> Router {
         .route("/overlay/icon", get(overlay::icon))
         .route("/data/get-auth-token", get(data::handle_get_auth_token))
         .route("/data/set-auth-token", post(data::handle_set_auth_token))
+        .route("/trigger/:event_id", post(webhook::handle_trigger))
 }
@@ -1,5 +1,6 @@
 use crate::{
     database::entity::twitch_access::{SetTwitchAccess, TwitchAccessModel},
+    events::matching::sync_stream_session,
     http::error::HttpResult,
     twitch::manager::Twitch,
 };
@@ -26,12 +27,16 @@ pub async fn handle_oauth() -> impl IntoResponse {
 pub struct OAuthComplete {
     access_token: AccessToken,
     scopes: Vec<Scope>,
+    /// OAuth state, used to identify bot account logins
+    #[serde(default)]
+    state: Option<String>,
 }
 
 /// POST /oauth/complete
 ///
 /// Handles the completion of OAuth logging into the twitch account storing
-/// the access token and authorized scopes
+/// the access token and authorized scopes, logins started for the separate
+/// bot account are stored as the bot account
 pub async fn handle_oauth_complete(
     Extension(db): Extension<DatabaseConnection>,
     Extension(twitch): Extension<Twitch>,
@@ -42,6 +47,26 @@ pub async fn handle_oauth_complete(
     let access_token = token.access_token.clone();
     let scopes = req.scopes;
 
+    let is_bot_login = req
+        .state
+        .as_deref()
+        .is_some_and(|state| twitch.take_bot_login_state(state));
+
+    if is_bot_login {
+        twitch.set_bot_authenticated(token).await;
+
+        TwitchAccessModel::set_bot(
+            &db,
+            SetTwitchAccess {
+                access_token,
+                scopes,
+            },
+        )
+        .await?;
+
+        return Ok(Json(()));
+    }
+
     twitch.set_authenticated(token).await;
 
     TwitchAccessModel::set(
@@ -53,5 +78,8 @@ pub async fn handle_oauth_complete(
     )
     .await?;
 
+    // Sync stream sessions for the newly authenticated user
+    sync_stream_session(&db, &twitch).await;
+
     Ok(Json(()))
 }
@@ -0,0 +1,256 @@
+use crate::{
+    database::entity::events::{EventModel, EventTrigger},
+    events::{
+        matching::{is_webhook_secret_valid, match_webhook_event, EventData, EventInputData},
+        processing::execute_event,
+        queue::OutcomeQueue,
+        EventMessageChannel,
+    },
+    http::error::{DynHttpError, HttpError},
+    twitch::manager::Twitch,
+};
+use axum::{
+    body::Bytes,
+    extract::Path,
+    http::{HeaderMap, StatusCode},
+    Extension,
+};
+use log::error;
+use reqwest::header::AUTHORIZATION;
+use sea_orm::DatabaseConnection;
+use thiserror::Error;
+
+/// Header the webhook secret can be provided through
+const WEBHOOK_SECRET_HEADER: &str = "X-Webhook-Secret";
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("Unknown webhook")]
+    UnknownWebhook,
+
+    #[error("Invalid webhook secret")]
+    InvalidSecret,
+
+    #[error("Invalid webhook body: {0}")]
+    InvalidBody(serde_json::Error),
+}
+
+impl HttpError for WebhookError {
+    fn status(&self) -> StatusCode {
+        match self {
+            WebhookError::UnknownWebhook => StatusCode::NOT_FOUND,
+            WebhookError::InvalidSecret => StatusCode::UNAUTHORIZED,
+            WebhookError::InvalidBody(_) => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+/// POST /trigger/:event_id
+///
+/// Triggers a webhook event by its event ID or slug, the request must provide the
+/// webhook secret either through the "X-Webhook-Secret" header or as a bearer token.
+///
+/// An optional JSON body can be provided as the event input data
+pub async fn handle_trigger(
+    Path(key): Path<String>,
+    Extension(db): Extension<DatabaseConnection>,
+    Extension(twitch): Extension<Twitch>,
+    Extension(event_sender): Extension<EventMessageChannel>,
+    Extension(outcome_queue): Extension<OutcomeQueue>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<StatusCode, DynHttpError> {
+    let (event, event_data) = accept_trigger(&db, &key, &headers, &body).await?;
+
+    // Execute the event in the background, the requester doesn't
+    // need to wait for the outcome delay
+    tokio::spawn(async move {
+        if let Err(err) = execute_event(
+            &db,
+            &twitch,
+            &event_sender,
+            &outcome_queue,
+            event,
+            event_data,
+        )
+        .await
+        {
+            error!("error while executing webhook event outcome: {err:?}");
+        }
+    });
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Finds the webhook event for the `key` and checks the request secret, creates
+/// the event data to execute the event with from the request `body`
+///
+/// Webhooks are not triggered by a Twitch user, only the input data is taken
+/// from the body so the requester cannot provide the user
+async fn accept_trigger(
+    db: &DatabaseConnection,
+    key: &str,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<(EventModel, EventData), DynHttpError> {
+    let event = match_webhook_event(db, key)
+        .await?
+        .ok_or(WebhookError::UnknownWebhook)?;
+
+    let secret = match &event.trigger {
+        EventTrigger::Webhook { secret, .. } => secret,
+        _ => return Err(WebhookError::UnknownWebhook.into()),
+    };
+
+    let provided_secret = get_request_secret(headers).unwrap_or_default();
+    if !is_webhook_secret_valid(secret, provided_secret) {
+        return Err(WebhookError::InvalidSecret.into());
+    }
+
+    let input_data: EventInputData = if body.is_empty() {
+        EventInputData::default()
+    } else {
+        serde_json::from_slice(body).map_err(WebhookError::InvalidBody)?
+    };
+
+    let event_data = EventData {
+        user: None,
+        input_data,
+        remaining_uses: None,
+    };
+
+    Ok((event, event_data))
+}
+
+/// Obtains the webhook secret from the request headers
+fn get_request_secret(headers: &HeaderMap) -> Option<&str> {
+    if let Some(value) = headers.get(WEBHOOK_SECRET_HEADER) {
+        return value.to_str().ok();
+    }
+
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+}
+
+#[cfg(test)]
+mod test {
+    use super::accept_trigger;
+    use crate::{
+        database::{
+            entity::events::{
+                CreateEvent, EventModel, EventOutcome, EventOutcomeSendChat, EventTrigger,
+            },
+            mock_database,
+        },
+        http::error::DynHttpError,
+    };
+    use axum::{
+        body::Bytes,
+        extract::Path,
+        http::{HeaderMap, StatusCode},
+        routing::post,
+        Extension, Json, Router,
+    };
+    use sea_orm::DatabaseConnection;
+    use serde_json::{json, Value};
+
+    /// Trigger route that responds with the event data the event
+    /// would be executed with instead of executing it
+    async fn handle_test_trigger(
+        Path(key): Path<String>,
+        Extension(db): Extension<DatabaseConnection>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> Result<Json<Value>, DynHttpError> {
+        let (_, event_data) = accept_trigger(&db, &key, &headers, &body).await?;
+        Ok(Json(json!({
+            "user": event_data.user,
+            "input_data": event_data.input_data,
+        })))
+    }
+
+    /// Starts a server for the trigger route, returns the base URL
+    async fn start_trigger_server(db: DatabaseConnection) -> String {
+        let app = Router::new()
+            .route("/trigger/:event_id", post(handle_test_trigger))
+            .layer(Extension(db));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        format!("http://{addr}")
+    }
+
+    /// Tests that a webhook request cannot provide the user that
+    /// triggered the event, only the input data is accepted
+    #[tokio::test]
+    async fn test_trigger_ignores_user() {
+        let db = mock_database().await;
+
+        EventModel::create(
+            &db,
+            CreateEvent {
+                enabled: true,
+                name: "Test Event".to_string(),
+                trigger: EventTrigger::Webhook {
+                    slug: Some("stream-deck".to_string()),
+                    secret: "secret".to_string(),
+                },
+                cooldown: Default::default(),
+                outcomes: EventOutcome::SendChatMessage(EventOutcomeSendChat {
+                    template: "test".to_string(),
+                    ..Default::default()
+                })
+                .into(),
+                outcome_delay: Default::default(),
+                require_role: Default::default(),
+                role_requirements: Default::default(),
+                conditions: Default::default(),
+                feedback: Default::default(),
+                usage_limits: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let base_url = start_trigger_server(db).await;
+        let client = reqwest::Client::new();
+
+        let response = client
+            .post(format!("{base_url}/trigger/stream-deck"))
+            .bearer_auth("secret")
+            .json(&json!({
+                "user": {
+                    "id": "spoofed-id",
+                    "name": "spoofed",
+                    "display_name": "Spoofed"
+                },
+                "bits": 100,
+                "anonymous": false,
+                "message": "test"
+            }))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["user"], Value::Null);
+        assert_eq!(body["input_data"]["bits"], 100);
+
+        // Invalid secrets are rejected
+        let response = client
+            .post(format!("{base_url}/trigger/stream-deck"))
+            .header("X-Webhook-Secret", "wrong")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
@@ -1,7 +1,11 @@
 use anyhow::Context;
-use database::{clean_old_data, entity::app_data::AppDataModel};
+use database::{
+    clean_old_data,
+    entity::{app_data::AppDataModel, event_groups::EventGroupModel},
+};
 use events::{
-    create_event_channel, processing::process_twitch_events, scheduler::create_scheduler,
+    create_event_channel, matching::sync_stream_session, processing::process_twitch_events,
+    queue::OutcomeQueue, scheduler::create_scheduler, template::check_event_templates,
 };
 use sea_orm::DatabaseConnection;
 use state::runtime_app_data::RuntimeAppDataStore;
@@ -59,8 +63,16 @@ pub fn run() {
             commands::twitch::get_twitch_oauth_uri,
             commands::twitch::is_authenticated,
             commands::twitch::logout,
+            commands::twitch::get_twitch_bot_oauth_uri,
+            commands::twitch::is_bot_authenticated,
+            commands::twitch::logout_bot,
             commands::twitch::get_redeems_list,
             commands::twitch::refresh_redeems_list,
+            commands::twitch::create_reward,
+            commands::twitch::update_reward,
+            commands::twitch::set_reward_paused,
+            commands::twitch::set_reward_enabled,
+            commands::twitch::delete_reward,
             // Item manipulation commands
             commands::items::get_item_by_id,
             commands::items::get_items,
@@ -86,6 +98,16 @@ pub fn run() {
             commands::events::update_event_orderings,
             commands::events::get_event_executions,
             commands::events::delete_event_executions,
+            commands::events::get_outcome_queue,
+            commands::events::preview_template,
+            // Event group commands
+            commands::event_groups::get_event_groups,
+            commands::event_groups::create_event_group,
+            commands::event_groups::update_event_group,
+            commands::event_groups::set_event_group_enabled,
+            commands::event_groups::delete_event_group,
+            commands::event_groups::get_event_group_events,
+            commands::event_groups::set_event_group_events,
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
@@ -105,18 +127,27 @@ fn setup(app: &mut App) -> Result<(), Box<dyn Error>> {
         .context("failed to load database")?;
 
     let (twitch, twitch_event_rx) = Twitch::new(handle.clone());
-    let (event_tx, event_rx) = create_event_channel();
+    let event_tx = create_event_channel();
+    let outcome_queue = OutcomeQueue::default();
 
     let runtime_app_data = RuntimeAppDataStore::new(handle.clone());
 
     // Create background event scheduler
-    let scheduler_handle = create_scheduler(db.clone(), twitch.clone(), event_tx.clone());
+    let scheduler_handle = create_scheduler(
+        db.clone(),
+        twitch.clone(),
+        event_tx.clone(),
+        outcome_queue.clone(),
+    );
 
     let storage = Storage::new_fs(handle)?;
 
     // Run background cleanup
     spawn(clean_old_data(db.clone()));
 
+    // Flag stored events with invalid templates
+    spawn(check_event_templates(db.clone()));
+
     // Provide runtime app data stores
     app.manage(runtime_app_data.clone());
 
@@ -127,6 +158,9 @@ fn setup(app: &mut App) -> Result<(), Box<dyn Error>> {
     app.manage(event_tx.clone());
     app.manage(twitch.clone());
 
+    // Provide access to the outcome queue
+    app.manage(outcome_queue.clone());
+
     // Provide database access
     app.manage(db.clone());
 
@@ -137,28 +171,49 @@ fn setup(app: &mut App) -> Result<(), Box<dyn Error>> {
         let twitch = twitch.clone();
         let db = db.clone();
 
-        async move { twitch.attempt_auth_stored(db).await }
+        async move {
+            twitch.attempt_auth_stored(db.clone()).await;
+
+            // Sync stream sessions missed while the app was closed
+            if twitch.is_authenticated().await {
+                sync_stream_session(&db, &twitch).await;
+            }
+        }
+    });
+
+    // Attempt to authenticate the bot account using the saved token
+    _ = spawn({
+        let twitch = twitch.clone();
+        let db = db.clone();
+
+        async move { twitch.attempt_bot_auth_stored(db).await }
     });
 
     // Handle events triggered by twitch
     _ = spawn(process_twitch_events(
+        handle.clone(),
         db.clone(),
         twitch.clone(),
         event_tx.clone(),
+        outcome_queue.clone(),
         twitch_event_rx,
     ));
 
+    let event_groups =
+        block_on(EventGroupModel::all(&db)).context("failed to load event groups")?;
+
     // Run HTTP server
     _ = spawn(http::start_http_server(
         db,
-        event_rx,
+        event_tx,
+        outcome_queue,
         handle.clone(),
         twitch,
         runtime_app_data,
         storage.clone(),
     ));
 
-    tray::create_tray_menu(app)?;
+    tray::create_tray_menu(app, &event_groups)?;
 
     Ok(())
 }
@@ -2,40 +2,51 @@
 //!
 //! Tray menu logic for the minimize to tray functionality
 
+use crate::{
+    database::entity::event_groups::EventGroupModel, events::groups::set_event_group_enabled,
+};
 use anyhow::Context;
+use log::error;
+use sea_orm::DatabaseConnection;
 use tauri::{
-    menu::{IconMenuItem, Menu, MenuItem},
+    menu::{CheckMenuItem, IconMenuItem, Menu, MenuItem, Submenu},
     tray::TrayIconBuilder,
-    App, AppHandle, Manager,
+    App, AppHandle, Manager, Wry,
 };
+use uuid::Uuid;
 
 #[cfg(not(debug_assertions))]
 const TRAY_NAME: &str = "VTFTK - Lite - VTuber Fun Toolkit";
 #[cfg(debug_assertions)]
 const TRAY_NAME: &str = "VTFTK - Lite - VTuber Fun Toolkit (Dev)";
 
+/// ID of the app tray icon
+const TRAY_ID: &str = "main";
+
+/// Prefix for the IDs of the event group menu items
+const EVENT_GROUP_ID_PREFIX: &str = "event-group:";
+
 /// Creates a tray menu for the app
-pub fn create_tray_menu(app: &mut App) -> anyhow::Result<()> {
+pub fn create_tray_menu(app: &mut App, event_groups: &[EventGroupModel]) -> anyhow::Result<()> {
     let icon = app
         .default_window_icon()
         .context("failed to get app icon")?
         .clone();
 
-    let title_i = IconMenuItem::new(app, TRAY_NAME, false, Some(icon.clone()), None::<&str>)?;
-    let open_i = MenuItem::with_id(app, "open", "Open", true, None::<&str>)?;
-    let quit_i = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-    let menu = Menu::with_items(app, &[&title_i, &open_i, &quit_i])?;
+    let menu = create_menu(app.handle(), event_groups)?;
 
-    TrayIconBuilder::new()
+    TrayIconBuilder::with_id(TRAY_ID)
         .icon(icon)
         .tooltip("VTFTK")
         .menu(&menu)
         .menu_on_left_click(true)
-        .on_menu_event(move |app, event| {
-            if event.id() == open_i.id() {
-                handle_open_clicked(app).expect("failed to open")
-            } else if event.id == quit_i.id() {
-                handle_quit_clicked(app).expect("failed to quit")
+        .on_menu_event(move |app, event| match event.id().as_ref() {
+            "open" => handle_open_clicked(app).expect("failed to open"),
+            "quit" => handle_quit_clicked(app).expect("failed to quit"),
+            id => {
+                if let Some(group_id) = id.strip_prefix(EVENT_GROUP_ID_PREFIX) {
+                    handle_event_group_clicked(app, group_id);
+                }
             }
         })
         .build(app)?;
@@ -43,6 +54,43 @@ pub fn create_tray_menu(app: &mut App) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Updates the tray menu to reflect the current event groups
+pub fn update_tray_menu(app: &AppHandle, event_groups: &[EventGroupModel]) -> anyhow::Result<()> {
+    let tray = app.tray_by_id(TRAY_ID).context("missing tray icon")?;
+    let menu = create_menu(app, event_groups)?;
+    tray.set_menu(Some(menu))?;
+    Ok(())
+}
+
+/// Creates the menu for the tray icon
+fn create_menu(app: &AppHandle, event_groups: &[EventGroupModel]) -> anyhow::Result<Menu<Wry>> {
+    let icon = app
+        .default_window_icon()
+        .context("failed to get app icon")?
+        .clone();
+
+    let title_i = IconMenuItem::new(app, TRAY_NAME, false, Some(icon), None::<&str>)?;
+    let open_i = MenuItem::with_id(app, "open", "Open", true, None::<&str>)?;
+    let quit_i = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+
+    // Event groups can be toggled from the menu
+    let groups_i = Submenu::new(app, "Event Groups", !event_groups.is_empty())?;
+    for group in event_groups {
+        let group_i = CheckMenuItem::with_id(
+            app,
+            format!("{EVENT_GROUP_ID_PREFIX}{}", group.id),
+            &group.name,
+            true,
+            group.enabled,
+            None::<&str>,
+        )?;
+        groups_i.append(&group_i)?;
+    }
+
+    let menu = Menu::with_items(app, &[&title_i, &open_i, &groups_i, &quit_i])?;
+    Ok(menu)
+}
+
 /// Handles the "Open" button in the tray menu being clicked
 /// to bring the app back into focus or re-create the window
 /// if it has been closed
@@ -61,6 +109,35 @@ fn handle_open_clicked(app: &AppHandle) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Handles an event group being clicked in the tray menu, toggles
+/// whether the group is enabled
+fn handle_event_group_clicked(app: &AppHandle, group_id: &str) {
+    let group_id = match Uuid::parse_str(group_id) {
+        Ok(value) => value,
+        Err(_) => return,
+    };
+
+    let app = app.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let db = app.state::<DatabaseConnection>().inner().clone();
+
+        let group = match EventGroupModel::get_by_id(&db, group_id).await {
+            Ok(Some(value)) => value,
+            Ok(None) => return,
+            Err(err) => {
+                error!("failed to load event group: {err:?}");
+                return;
+            }
+        };
+
+        let enabled = !group.enabled;
+        if let Err(err) = set_event_group_enabled(&app, &db, group, enabled).await {
+            error!("failed to toggle event group: {err:?}");
+        }
+    });
+}
+
 /// Handles "Quit" being clicked in the tray menu, closes the
 /// application
 fn handle_quit_clicked(app: &AppHandle) -> anyhow::Result<()> {
@@ -0,0 +1,116 @@
+//! # Cache
+//!
+//! Cache for values requested from twitch that expire after a
+//! time-to-live, used to prevent repeated requests for the same values
+
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Cache of values that expire after the `ttl`
+pub struct TtlCache<K, V> {
+    /// Time-to-live for cached values
+    ttl: Duration,
+    /// Cached values along with the time they were cached
+    entries: Mutex<HashMap<K, (Instant, V)>>,
+}
+
+impl<K, V> TtlCache<K, V>
+where
+    K: Hash + Eq,
+    V: Clone,
+{
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Default::default(),
+        }
+    }
+
+    /// Get a value from the cache, produces [None] if the
+    /// value is not cached or has expired
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.get_at(key, Instant::now())
+    }
+
+    /// Store a value in the cache
+    pub fn insert(&self, key: K, value: V) {
+        self.insert_at(key, value, Instant::now())
+    }
+
+    /// Remove a value from the cache
+    pub fn remove(&self, key: &K) {
+        self.entries
+            .lock()
+            .expect("cache lock poisoned")
+            .remove(key);
+    }
+
+    /// Remove all values from the cache
+    pub fn clear(&self) {
+        self.entries.lock().expect("cache lock poisoned").clear();
+    }
+
+    fn get_at(&self, key: &K, now: Instant) -> Option<V> {
+        let entries = &*self.entries.lock().expect("cache lock poisoned");
+        let (cached_at, value) = entries.get(key)?;
+
+        if now.duration_since(*cached_at) >= self.ttl {
+            return None;
+        }
+
+        Some(value.clone())
+    }
+
+    fn insert_at(&self, key: K, value: V, now: Instant) {
+        let entries = &mut *self.entries.lock().expect("cache lock poisoned");
+
+        // Drop expired values
+        entries.retain(|_, (cached_at, _)| now.duration_since(*cached_at) < self.ttl);
+        entries.insert(key, (now, value));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TtlCache;
+    use std::time::{Duration, Instant};
+
+    /// Tests that cached values expire after the TTL
+    #[test]
+    fn test_ttl_cache_expiry() {
+        let cache = TtlCache::new(Duration::from_secs(60));
+        let start = Instant::now();
+
+        assert_eq!(cache.get_at(&"key", start), None);
+
+        cache.insert_at("key", 1, start);
+        assert_eq!(
+            cache.get_at(&"key", start + Duration::from_secs(30)),
+            Some(1)
+        );
+
+        // Value has expired
+        assert_eq!(cache.get_at(&"key", start + Duration::from_secs(60)), None);
+
+        // Expired values are dropped when inserting
+        cache.insert_at("other", 2, start + Duration::from_secs(60));
+        assert_eq!(cache.entries.lock().unwrap().len(), 1);
+
+        cache.insert_at("removed", 3, start + Duration::from_secs(60));
+        cache.remove(&"removed");
+        assert_eq!(
+            cache.get_at(&"removed", start + Duration::from_secs(60)),
+            None
+        );
+
+        cache.clear();
+        assert_eq!(
+            cache.get_at(&"other", start + Duration::from_secs(60)),
+            None
+        );
+    }
+}
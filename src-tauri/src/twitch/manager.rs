@@ -1,10 +1,26 @@
-use super::{models::TwitchEvent, websocket::WebsocketManagedTask};
+use super::{
+    cache::TtlCache,
+    models::{ChatIdentity, TwitchEvent},
+    moderation::{delete_chat_message, send_announcement, send_shoutout, timeout_user},
+    rate_limit::ReplyRateLimiter,
+    redemptions::update_redemption_status,
+    reward_revert::{PendingRewardReverts, RewardState},
+    rewards::{
+        create_custom_reward, delete_custom_reward, get_custom_reward, update_custom_reward,
+        CreateReward, UpdateReward,
+    },
+    streams::get_live_stream,
+    websocket::WebsocketManagedTask,
+};
 use crate::database::entity::twitch_access::TwitchAccessModel;
 use anyhow::{anyhow, Context};
 use futures::TryStreamExt;
 use log::{debug, error, info};
 use sea_orm::{DatabaseConnection, ModelTrait};
-use std::sync::Arc;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 use tauri::{AppHandle, Emitter};
 use tokio::{
     join,
@@ -14,28 +30,41 @@ use twitch_api::{
     helix::{
         channels::{Follower, GetChannelFollowersRequest, Vip},
         chat::{
-            ChannelEmote, SendChatMessageBody, SendChatMessageRequest, SendChatMessageResponse,
+            AnnouncementColor, ChannelEmote, SendChatMessageBody, SendChatMessageRequest,
+            SendChatMessageResponse,
         },
         moderation::Moderator,
-        points::CustomReward,
+        points::{CustomReward, CustomRewardRedemptionStatus},
+        streams::Stream,
+        subscriptions::{BroadcasterSubscription, GetBroadcasterSubscriptionsRequest},
+        users::User,
         Scope,
     },
-    twitch_oauth2::{types::ClientIdRef, AccessToken, ImplicitUserTokenBuilder, UserToken},
-    types::UserId,
+    twitch_oauth2::{
+        types::ClientIdRef, AccessToken, CsrfToken, ImplicitUserTokenBuilder, UserToken,
+    },
+    types::{MsgIdRef, RedemptionIdRef, RewardIdRef, SubscriptionTier, UserId, UserIdRef},
     HelixClient,
 };
+use uuid::Uuid;
 
 /// If you are forking this app program for your own use, please create your own
 /// twitch developer application client ID at https://dev.twitch.tv/console/apps
 pub const TWITCH_CLIENT_ID: &ClientIdRef =
     ClientIdRef::from_static("ooliehf2tmiiipbahv3f0m007x8b2v");
 
+/// Time-to-live for cached follower and subscription details
+const ROLE_CACHE_TTL: Duration = Duration::from_secs(60 * 5);
+
 /// Scopes required from twitch by the app
 pub const TWITCH_REQUIRED_SCOPES: &[Scope] = &[
     // View live Stream Chat and Rooms messages
     Scope::UserReadChat,
     // View Channel Points rewards and their redemptions on your channel.
     Scope::ChannelReadRedemptions,
+    // Manage Channel Points rewards and their redemptions on your channel.
+    // (Fulfilling and refunding redemptions)
+    Scope::ChannelManageRedemptions,
     // Get a list of all subscribers to your channel and check if a user is subscribed to your channel
     Scope::ChannelReadSubscriptions,
     // View your channel's Bits information
@@ -55,6 +84,30 @@ pub const TWITCH_REQUIRED_SCOPES: &[Scope] = &[
     Scope::ModeratorReadShoutouts,
     // Scope to read ad break messages
     Scope::ChannelReadAds,
+    // Read hype train events for hype train triggers
+    Scope::ChannelReadHypeTrain,
+    // Read poll events for poll triggers
+    Scope::ChannelReadPolls,
+    // Read prediction events for prediction triggers
+    Scope::ChannelReadPredictions,
+    // Read charity campaign donations for charity donation triggers
+    Scope::ChannelReadCharity,
+    // Timeout users for timeout outcomes
+    Scope::ModeratorManageBannedUsers,
+    // Delete chat messages for delete message outcomes
+    Scope::ModeratorManageChatMessages,
+    // Send shoutouts for shoutout outcomes
+    Scope::ModeratorManageShoutouts,
+    // Send announcements for announcement outcomes
+    Scope::ModeratorManageAnnouncements,
+];
+
+/// Scopes required from twitch for the separate bot account
+pub const TWITCH_BOT_REQUIRED_SCOPES: &[Scope] = &[
+    // Send chat messages
+    Scope::UserWriteChat,
+    // Send announcements (Bot account must be a moderator)
+    Scope::ModeratorManageAnnouncements,
 ];
 
 #[derive(Clone)]
@@ -72,6 +125,12 @@ impl Twitch {
                     state: Default::default(),
                     tx,
                     app_handle,
+                    reply_rate_limiter: Default::default(),
+                    reward_reverts: Default::default(),
+                    followers: TtlCache::new(ROLE_CACHE_TTL),
+                    subscriptions: TtlCache::new(ROLE_CACHE_TTL),
+                    bot_token: Default::default(),
+                    bot_login_state: Default::default(),
                 }),
             },
             rx,
@@ -86,6 +145,94 @@ impl Twitch {
         Ok(url.to_string())
     }
 
+    /// Obtain a URL for logging into the separate bot account, the login
+    /// is identified as the bot login by the OAuth state
+    pub fn create_bot_oauth_uri(&self, redirect_url: reqwest::Url) -> anyhow::Result<String> {
+        let (url, csrf) = ImplicitUserTokenBuilder::new(TWITCH_CLIENT_ID.into(), redirect_url)
+            .set_scopes(TWITCH_BOT_REQUIRED_SCOPES.to_vec())
+            // Allow switching from the broadcaster account to the bot account
+            .force_verify(true)
+            .generate_url();
+
+        *self._inner.bot_login_state.lock().expect("lock poisoned") = Some(csrf);
+
+        Ok(url.to_string())
+    }
+
+    /// Checks if the OAuth `state` is from a pending bot account login,
+    /// consumes the pending login when it matches
+    pub fn take_bot_login_state(&self, state: &str) -> bool {
+        let lock = &mut *self._inner.bot_login_state.lock().expect("lock poisoned");
+        match lock {
+            Some(csrf) if csrf.secret() == state => {
+                *lock = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Attempts to authenticate the bot account using an existing access token (From the database)
+    pub async fn attempt_bot_auth_stored(&self, db: DatabaseConnection) {
+        let access = match TwitchAccessModel::get_bot(&db).await {
+            Ok(Some(value)) => value,
+            Ok(None) => return,
+            Err(err) => {
+                error!("failed to load twitch bot access: {err:?}");
+                return;
+            }
+        };
+
+        let scopes = &access.scopes.0;
+        if TWITCH_BOT_REQUIRED_SCOPES
+            .iter()
+            .any(|required_scope| !scopes.contains(required_scope))
+        {
+            info!("logging out current bot access token, missing required scope");
+
+            // Clear outdated / invalid access token
+            _ = access.delete(&db).await;
+            return;
+        }
+
+        match self.create_user_token(access.access_token.0.clone()).await {
+            Ok(token) => self.set_bot_authenticated(token).await,
+            Err(err) => {
+                error!("stored bot access token is invalid: {}", err);
+
+                // Clear outdated / invalid access token
+                _ = access.delete(&db).await;
+            }
+        }
+    }
+
+    pub async fn set_bot_authenticated(&self, token: UserToken) {
+        *self._inner.bot_token.write().await = Some(token);
+    }
+
+    pub async fn get_bot_token(&self) -> Option<UserToken> {
+        self._inner.bot_token.read().await.clone()
+    }
+
+    pub async fn is_bot_authenticated(&self) -> bool {
+        self._inner.bot_token.read().await.is_some()
+    }
+
+    pub async fn reset_bot(&self) {
+        *self._inner.bot_token.write().await = None;
+    }
+
+    /// Get the token to act as for the `identity`
+    async fn get_identity_token(&self, identity: ChatIdentity) -> anyhow::Result<UserToken> {
+        match identity {
+            ChatIdentity::Broadcaster => self.get_user_token().await.context("not authenticated"),
+            ChatIdentity::Bot => self
+                .get_bot_token()
+                .await
+                .context("bot account is not authenticated"),
+        }
+    }
+
     /// Attempts to authenticate with twitch using an existing access token (From the database)
     pub async fn attempt_auth_stored(&self, db: DatabaseConnection) {
         let access = match TwitchAccessModel::get(&db).await {
@@ -141,15 +288,28 @@ impl Twitch {
         &self,
         message: &str,
     ) -> anyhow::Result<SendChatMessageResponse> {
-        // Obtain twitch access token
-        let token = self.get_user_token().await.context("not authenticated")?;
+        self.send_chat_message_as(ChatIdentity::Broadcaster, message, None)
+            .await
+    }
 
+    /// Sends a chat message to the broadcasters chat from the account
+    /// of the `identity`, optionally replying to the `reply_to` message
+    pub async fn send_chat_message_as(
+        &self,
+        identity: ChatIdentity,
+        message: &str,
+        reply_to: Option<&MsgIdRef>,
+    ) -> anyhow::Result<SendChatMessageResponse> {
         // Get broadcaster user ID
-        let user_id = token.user_id.clone();
+        let broadcaster_id = self.get_user_id().await.context("not authenticated")?;
+
+        // Obtain access token for the sender
+        let token = self.get_identity_token(identity).await?;
 
         // Create chat message request
         let request = SendChatMessageRequest::new();
-        let body = SendChatMessageBody::new(user_id.clone(), user_id, message);
+        let mut body = SendChatMessageBody::new(broadcaster_id, token.user_id.clone(), message);
+        body.reply_parent_message_id = reply_to.map(Into::into);
 
         // Send request and get response
         let response: SendChatMessageResponse = self
@@ -161,6 +321,12 @@ impl Twitch {
         Ok(response)
     }
 
+    /// Attempts to acquire a rate limited chat reply for the `key`, replies
+    /// for the same key are limited to one per `interval`
+    pub fn try_acquire_reply(&self, key: Uuid, interval: Duration) -> bool {
+        self._inner.reply_rate_limiter.try_acquire(key, interval)
+    }
+
     pub async fn get_channel_emotes(&self, user_id: UserId) -> anyhow::Result<Vec<ChannelEmote>> {
         // Obtain twitch access token
         let token = self.get_user_token().await.context("not authenticated")?;
@@ -173,7 +339,25 @@ impl Twitch {
         Ok(emotes)
     }
 
+    /// Get the follower details for a user, cached for [ROLE_CACHE_TTL]
     pub async fn get_follower_by_id(&self, user_id: UserId) -> anyhow::Result<Option<Follower>> {
+        if let Some(follower) = self._inner.followers.get(&user_id) {
+            return Ok(follower);
+        }
+
+        let follower = self.request_follower_by_id(user_id.clone()).await?;
+        self._inner.followers.insert(user_id, follower.clone());
+
+        Ok(follower)
+    }
+
+    /// Removes the cached follower details for a user, used when the
+    /// user follows so the follow is not hidden by a cached result
+    pub fn clear_cached_follower(&self, user_id: &UserId) {
+        self._inner.followers.remove(user_id);
+    }
+
+    async fn request_follower_by_id(&self, user_id: UserId) -> anyhow::Result<Option<Follower>> {
         // Obtain twitch access token
         let token = self.get_user_token().await.context("not authenticated")?;
 
@@ -189,6 +373,285 @@ impl Twitch {
         Ok(response.pop())
     }
 
+    /// Updates the status of a channel point reward redemption, marking
+    /// it as fulfilled or canceled (Refunding the points)
+    pub async fn update_redemption_status(
+        &self,
+        reward_id: &RewardIdRef,
+        redemption_id: &RedemptionIdRef,
+        status: CustomRewardRedemptionStatus,
+    ) -> anyhow::Result<()> {
+        // Obtain twitch access token
+        let token = self.get_user_token().await.context("not authenticated")?;
+
+        update_redemption_status(
+            self.helix_client(),
+            &token,
+            reward_id,
+            redemption_id,
+            status,
+        )
+        .await
+    }
+
+    /// Puts a user in a timeout for `duration` seconds, the broadcaster
+    /// and moderators cannot be put in a timeout
+    pub async fn timeout_user(
+        &self,
+        user_id: &UserIdRef,
+        duration: u32,
+        reason: &str,
+    ) -> anyhow::Result<()> {
+        self.ensure_can_moderate(user_id).await?;
+
+        // Obtain twitch access token
+        let token = self.get_user_token().await.context("not authenticated")?;
+
+        timeout_user(self.helix_client(), &token, user_id, duration, reason).await
+    }
+
+    /// Deletes a chat message sent by the user with the provided `sender_id`,
+    /// messages from the broadcaster and moderators cannot be deleted
+    pub async fn delete_chat_message(
+        &self,
+        message_id: &MsgIdRef,
+        sender_id: &UserIdRef,
+    ) -> anyhow::Result<()> {
+        self.ensure_can_moderate(sender_id).await?;
+
+        // Obtain twitch access token
+        let token = self.get_user_token().await.context("not authenticated")?;
+
+        delete_chat_message(self.helix_client(), &token, message_id).await
+    }
+
+    /// Sends an official shoutout to another broadcaster
+    pub async fn send_shoutout(&self, user_id: &UserIdRef) -> anyhow::Result<()> {
+        // Obtain twitch access token
+        let token = self.get_user_token().await.context("not authenticated")?;
+
+        if token.user_id == *user_id {
+            return Err(anyhow!("broadcaster cannot shoutout themselves"));
+        }
+
+        send_shoutout(self.helix_client(), &token, user_id).await
+    }
+
+    /// Sends a highlighted announcement to chat from the account of
+    /// the `identity`, the bot account must be a moderator
+    pub async fn send_announcement(
+        &self,
+        identity: ChatIdentity,
+        message: &str,
+        color: AnnouncementColor,
+    ) -> anyhow::Result<()> {
+        // Get broadcaster user ID
+        let broadcaster_id = self.get_user_id().await.context("not authenticated")?;
+
+        // Obtain access token for the sender
+        let token = self.get_identity_token(identity).await?;
+
+        send_announcement(self.helix_client(), &token, &broadcaster_id, message, color).await
+    }
+
+    /// Ensures the user can be moderated, Twitch does not allow moderation
+    /// actions against the broadcaster or moderators
+    async fn ensure_can_moderate(&self, user_id: &UserIdRef) -> anyhow::Result<()> {
+        let broadcaster_id = self.get_user_id().await.context("not authenticated")?;
+        if broadcaster_id == *user_id {
+            return Err(anyhow!("cannot moderate the broadcaster"));
+        }
+
+        let moderators = self.get_moderator_list().await?;
+        if moderators
+            .iter()
+            .any(|moderator| moderator.user_id == *user_id)
+        {
+            return Err(anyhow!("cannot moderate a moderator"));
+        }
+
+        Ok(())
+    }
+
+    /// Finds a user by their login name
+    pub async fn get_user_by_login(&self, login: &str) -> anyhow::Result<Option<User>> {
+        // Obtain twitch access token
+        let token = self.get_user_token().await.context("not authenticated")?;
+
+        let user = self
+            .helix_client()
+            .get_user_from_login(login, &token)
+            .await
+            .context("failed to get user")?;
+
+        Ok(user)
+    }
+
+    /// Gets the current live stream of the authenticated user, [None]
+    /// when the user is not live
+    pub async fn get_live_stream(&self) -> anyhow::Result<Option<Stream>> {
+        // Obtain twitch access token
+        let token = self.get_user_token().await.context("not authenticated")?;
+
+        get_live_stream(self.helix_client(), &token).await
+    }
+
+    /// Gets the current details of a custom reward
+    pub async fn get_reward(
+        &self,
+        reward_id: &RewardIdRef,
+    ) -> anyhow::Result<Option<CustomReward>> {
+        // Obtain twitch access token
+        let token = self.get_user_token().await.context("not authenticated")?;
+
+        get_custom_reward(self.helix_client(), &token, reward_id).await
+    }
+
+    /// Schedules restoring the `previous` state of a reward once `duration`
+    /// has elapsed, re-scheduling a reward that already has a pending revert
+    /// keeps the originally captured state and extends the revert
+    pub fn schedule_reward_revert(
+        &self,
+        reward_id: String,
+        previous: RewardState,
+        duration: Duration,
+    ) {
+        let scheduled = self._inner.reward_reverts.schedule(
+            reward_id.clone(),
+            previous,
+            std::time::Instant::now() + duration,
+        );
+
+        let twitch = self.clone();
+
+        tokio::spawn(async move {
+            tokio::time::sleep_until(scheduled.revert_at.into()).await;
+
+            // Revert was re-scheduled or cancelled
+            let Some(state) = twitch
+                ._inner
+                .reward_reverts
+                .take_due(&reward_id, scheduled.generation)
+            else {
+                return;
+            };
+
+            let revert = UpdateReward {
+                paused: state.paused,
+                enabled: state.enabled,
+                ..Default::default()
+            };
+
+            if let Err(err) = twitch
+                .update_reward(RewardIdRef::from_str(&reward_id), &revert)
+                .await
+            {
+                error!("failed to revert reward state: {err:?}");
+            }
+        });
+    }
+
+    /// Cancels any pending revert of the fields of a reward that
+    /// were permanently changed to the `changed` state
+    pub fn cancel_reward_revert(&self, reward_id: &str, changed: RewardState) {
+        self._inner.reward_reverts.cancel(reward_id, changed);
+    }
+
+    /// Creates a new custom reward, rewards created by the app can be
+    /// updated and deleted by the app
+    pub async fn create_reward(&self, create: &CreateReward) -> anyhow::Result<CustomReward> {
+        // Obtain twitch access token
+        let token = self.get_user_token().await.context("not authenticated")?;
+
+        let reward = create_custom_reward(self.helix_client(), &token, create).await?;
+
+        self.modify_rewards_list(|rewards| rewards.push(reward.clone()))
+            .await;
+
+        Ok(reward)
+    }
+
+    /// Updates a custom reward created by the app
+    pub async fn update_reward(
+        &self,
+        reward_id: &RewardIdRef,
+        update: &UpdateReward,
+    ) -> anyhow::Result<CustomReward> {
+        // Obtain twitch access token
+        let token = self.get_user_token().await.context("not authenticated")?;
+
+        let reward = update_custom_reward(self.helix_client(), &token, reward_id, update).await?;
+
+        self.modify_rewards_list(|rewards| {
+            if let Some(existing) = rewards.iter_mut().find(|value| value.id == reward.id) {
+                *existing = reward.clone();
+            }
+        })
+        .await;
+
+        Ok(reward)
+    }
+
+    /// Deletes a custom reward created by the app
+    pub async fn delete_reward(&self, reward_id: &RewardIdRef) -> anyhow::Result<()> {
+        // Obtain twitch access token
+        let token = self.get_user_token().await.context("not authenticated")?;
+
+        delete_custom_reward(self.helix_client(), &token, reward_id).await?;
+
+        self.modify_rewards_list(|rewards| rewards.retain(|value| value.id != *reward_id))
+            .await;
+
+        Ok(())
+    }
+
+    /// Get the tier of a users subscription to the channel, cached
+    /// for [ROLE_CACHE_TTL]
+    pub async fn get_subscription_tier(
+        &self,
+        user_id: UserId,
+    ) -> anyhow::Result<Option<SubscriptionTier>> {
+        if let Some(tier) = self._inner.subscriptions.get(&user_id) {
+            return Ok(tier);
+        }
+
+        let tier = self
+            .request_subscription(user_id.clone())
+            .await?
+            .map(|subscription| subscription.tier);
+        self._inner.subscriptions.insert(user_id, tier.clone());
+
+        Ok(tier)
+    }
+
+    /// Removes the cached subscription tier for a user, used when the user
+    /// subscribes so the subscription is not hidden by a cached result
+    pub fn clear_cached_subscription(&self, user_id: &UserId) {
+        self._inner.subscriptions.remove(user_id);
+    }
+
+    async fn request_subscription(
+        &self,
+        user_id: UserId,
+    ) -> anyhow::Result<Option<BroadcasterSubscription>> {
+        // Obtain twitch access token
+        let token = self.get_user_token().await.context("not authenticated")?;
+
+        // Get broadcaster user ID
+        let broadcaster_id = token.user_id.clone();
+
+        // Create subscriptions request for the specific user
+        let user_ids = [user_id];
+        let request = GetBroadcasterSubscriptionsRequest::broadcaster_id(broadcaster_id)
+            .subscriber(&user_ids);
+
+        // Send request and get response
+        let mut response: Vec<BroadcasterSubscription> =
+            self.helix_client().req_get(request, &token).await?.data;
+
+        Ok(response.pop())
+    }
+
     pub async fn get_user_token(&self) -> Option<UserToken> {
         let lock = &*self.state().await;
         match lock {
@@ -211,6 +674,10 @@ impl Twitch {
                 token.clone(),
             );
 
+            // Clear any cached details from a previous user
+            self._inner.followers.clear();
+            self._inner.subscriptions.clear();
+
             *lock = TwitchManagerState::Authenticated(TwitchManagerStateAuthenticated {
                 token,
                 _websocket: websocket,
@@ -249,6 +716,9 @@ impl Twitch {
             *lock = TwitchManagerState::Initial;
         }
 
+        self._inner.followers.clear();
+        self._inner.subscriptions.clear();
+
         // Tell the app we are authenticated
         _ = self._inner.app_handle.emit("logout", ());
     }
@@ -368,6 +838,19 @@ impl Twitch {
         }
     }
 
+    /// Applies a change to the loaded rewards list, used to keep the list
+    /// up to date after modifying rewards without requesting it again
+    async fn modify_rewards_list(&self, action: impl FnOnce(&mut Vec<CustomReward>)) {
+        let state = &mut *self.state_mut().await;
+        if let TwitchManagerState::Authenticated(state) = state {
+            if let Some(rewards) = state.rewards.as_mut() {
+                let mut list = rewards.to_vec();
+                action(&mut list);
+                *rewards = list.into();
+            }
+        }
+    }
+
     async fn request_moderator_list(&self) -> anyhow::Result<Vec<Moderator>> {
         let user_token = self.get_user_token().await.context("not authenticated")?;
         let user_id = user_token.user_id.clone();
@@ -432,6 +915,18 @@ struct TwitchInner {
     state: RwLock<TwitchManagerState>,
     tx: broadcast::Sender<TwitchEvent>,
     app_handle: AppHandle,
+    /// Rate limiter for automatic chat replies
+    reply_rate_limiter: ReplyRateLimiter,
+    /// Pending reverts of reward states
+    reward_reverts: PendingRewardReverts,
+    /// Cached follower details for users
+    followers: TtlCache<UserId, Option<Follower>>,
+    /// Cached subscription tiers for users
+    subscriptions: TtlCache<UserId, Option<SubscriptionTier>>,
+    /// Token for the separate bot account
+    bot_token: RwLock<Option<UserToken>>,
+    /// OAuth state for a pending bot account login
+    bot_login_state: Mutex<Option<CsrfToken>>,
 }
 
 pub struct TwitchManagerStateAuthenticated {
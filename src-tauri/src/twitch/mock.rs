@@ -0,0 +1,76 @@
+//! # Mock Helix
+//!
+//! Helpers for testing helix requests against a local mock server
+
+use super::manager::TWITCH_REQUIRED_SCOPES;
+use axum::{
+    body::Bytes,
+    http::{uri::Authority, Request, Response, Uri},
+    Router,
+};
+use std::net::SocketAddr;
+use twitch_api::{
+    client::BoxedFuture,
+    helix::Scope,
+    twitch_oauth2::{AccessToken, ClientId, UserToken},
+    types::{UserId, UserName},
+    HelixClient, HttpClient,
+};
+
+/// HTTP client that sends all requests to the mock helix server
+pub struct MockHelixClient {
+    addr: SocketAddr,
+    client: reqwest::Client,
+}
+
+impl HttpClient for MockHelixClient {
+    type Error = reqwest::Error;
+
+    fn req(
+        &self,
+        request: Request<Bytes>,
+    ) -> BoxedFuture<'_, Result<Response<Bytes>, Self::Error>> {
+        let (mut parts, body) = request.into_parts();
+
+        // Redirect the request to the mock server
+        let mut uri = parts.uri.into_parts();
+        uri.scheme = Some("http".parse().unwrap());
+        uri.authority = Some(Authority::try_from(self.addr.to_string()).unwrap());
+        parts.uri = Uri::from_parts(uri).unwrap();
+
+        self.client.req(Request::from_parts(parts, body))
+    }
+}
+
+/// Starts a mock helix server serving the provided `app` on a random
+/// port, returns a helix client that sends its requests to the server
+pub async fn start_mock_helix_server(app: Router) -> HelixClient<'static, MockHelixClient> {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    HelixClient::with_client(MockHelixClient {
+        addr,
+        client: reqwest::Client::new(),
+    })
+}
+
+/// Creates a token for the mock broadcaster with all the required scopes
+pub fn mock_token() -> UserToken {
+    mock_token_with_scopes(TWITCH_REQUIRED_SCOPES.to_vec())
+}
+
+/// Creates a token for the mock broadcaster with specific `scopes`
+pub fn mock_token_with_scopes(scopes: Vec<Scope>) -> UserToken {
+    UserToken::from_existing_unchecked(
+        AccessToken::new("mock-access-token".to_string()),
+        None,
+        ClientId::new("mock-client-id".to_string()),
+        None,
+        UserName::new("mockbroadcaster".to_string()),
+        UserId::new("mock-broadcaster-id".to_string()),
+        Some(scopes),
+        None,
+    )
+}
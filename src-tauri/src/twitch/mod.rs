@@ -1,7 +1,16 @@
 use twitch_api::HelixClient;
 
+pub mod cache;
 pub mod manager;
+#[cfg(test)]
+pub mod mock;
 pub mod models;
+pub mod moderation;
+pub mod rate_limit;
+pub mod redemptions;
+pub mod reward_revert;
+pub mod rewards;
+pub mod streams;
 pub mod websocket;
 
 pub type TwitchClient = HelixClient<'static, reqwest::Client>;
@@ -7,7 +7,7 @@ use twitch_api::{
             subscription::message::SubscriptionMessage,
         },
     },
-    types::{DisplayName, MsgId, RedemptionId, SubscriptionTier, UserId, UserName},
+    types::{DisplayName, MsgId, RedemptionId, SubscriptionTier, Timestamp, UserId, UserName},
 };
 
 #[derive(Debug, Serialize)]
@@ -21,6 +21,16 @@ pub struct TwitchUser {
     pub profile_image_url: Option<String>,
 }
 
+/// Account to send chat messages from
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChatIdentity {
+    /// Send messages from the broadcaster account
+    #[default]
+    Broadcaster,
+    /// Send messages from the separate bot account
+    Bot,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TwitchEventUser {
@@ -140,6 +150,95 @@ pub struct TwitchEventShoutoutReceive {
     pub viewer_count: i64,
 }
 
+#[derive(Debug, Clone)]
+pub struct TwitchEventHypeTrain {
+    /// Last user to contribute to the hype train
+    pub user_id: UserId,
+    /// Last contributor user name
+    pub user_name: UserName,
+    /// Last contributor display name
+    pub user_display_name: DisplayName,
+    /// Current level of the hype train
+    pub level: i64,
+    /// Total points contributed to the hype train
+    pub total: i64,
+    /// Points contributed to the current level
+    pub progress: i64,
+    /// Points required to reach the next level
+    pub goal: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TwitchEventHypeTrainEnd {
+    /// Final level of the hype train
+    pub level: i64,
+    /// Total points contributed to the hype train
+    pub total: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TwitchEventStreamOnline {
+    /// Twitch ID of the stream
+    pub stream_id: String,
+    /// Time the stream started
+    pub started_at: Timestamp,
+}
+
+#[derive(Debug, Clone)]
+pub struct TwitchEventCharityDonation {
+    pub user_id: UserId,
+    pub user_name: UserName,
+    pub user_display_name: DisplayName,
+    /// Name of the charity donated to
+    pub charity_name: String,
+    /// Amount donated in the currency minor unit (i.e cents for USD)
+    pub amount: i64,
+    /// Number of decimal places used by the currency
+    pub decimal_places: i32,
+    /// ISO-4217 three-letter currency code
+    pub currency: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwitchPollChoice {
+    /// Title of the choice
+    pub title: String,
+    /// Total votes for the choice (Including channel points and bits votes)
+    pub votes: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TwitchEventPoll {
+    /// Question asked by the poll
+    pub title: String,
+    /// Choices for the poll
+    pub choices: Vec<TwitchPollChoice>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwitchPredictionOutcome {
+    /// ID of the outcome
+    pub id: String,
+    /// Title of the outcome
+    pub title: String,
+    /// Number of users that predicted the outcome
+    pub users: i64,
+    /// Total channel points used to predict the outcome
+    pub channel_points: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TwitchEventPrediction {
+    /// Title of the prediction
+    pub title: String,
+    /// Possible outcomes for the prediction
+    pub outcomes: Vec<TwitchPredictionOutcome>,
+    /// ID of the outcome that won (Only present once resolved)
+    pub winning_outcome_id: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub enum TwitchEvent {
     Redeem(TwitchEventRedeem),
@@ -152,6 +251,19 @@ pub enum TwitchEvent {
     Raid(TwitchEventRaid),
     AdBreakBegin(TwitchEventAdBreakBegin),
     ShoutoutReceive(TwitchEventShoutoutReceive),
+    HypeTrainBegin(TwitchEventHypeTrain),
+    HypeTrainProgress(TwitchEventHypeTrain),
+    HypeTrainEnd(TwitchEventHypeTrainEnd),
+    StreamOnline(TwitchEventStreamOnline),
+    StreamOffline,
+    PollBegin(TwitchEventPoll),
+    PollProgress(TwitchEventPoll),
+    PollEnd(TwitchEventPoll),
+    PredictionBegin(TwitchEventPrediction),
+    PredictionProgress(TwitchEventPrediction),
+    PredictionLock(TwitchEventPrediction),
+    PredictionEnd(TwitchEventPrediction),
+    CharityDonation(TwitchEventCharityDonation),
 
     ModeratorsChanged,
     VipsChanged,
@@ -0,0 +1,284 @@
+//! # Moderation
+//!
+//! Helix requests for moderating the broadcasters chat, timeouts,
+//! deleting messages, shoutouts and announcements
+
+use anyhow::{anyhow, Context};
+use twitch_api::{
+    helix::{
+        chat::{
+            AnnouncementColor, SendAShoutoutRequest, SendChatAnnouncementBody,
+            SendChatAnnouncementRequest, SendChatAnnouncementResponse,
+        },
+        moderation::{BanUserBody, BanUserRequest, DeleteChatMessagesRequest},
+        Scope,
+    },
+    twitch_oauth2::{TwitchToken, UserToken},
+    types::{MsgIdRef, UserIdRef},
+    HelixClient, HttpClient,
+};
+
+/// Maximum duration of a timeout in seconds (2 weeks)
+pub const MAX_TIMEOUT_DURATION: u32 = 1_209_600;
+
+/// Ensures the `token` was granted the `scope`, tokens from before
+/// the scope was required must login again to obtain it
+fn require_scope(token: &UserToken, scope: Scope) -> anyhow::Result<()> {
+    if !token.scopes().contains(&scope) {
+        return Err(anyhow!(
+            "missing required permission \"{scope}\", logout and login again to grant it"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Puts a user in a timeout for `duration` seconds
+pub async fn timeout_user<C>(
+    helix_client: &HelixClient<'_, C>,
+    token: &UserToken,
+    user_id: &UserIdRef,
+    duration: u32,
+    reason: &str,
+) -> anyhow::Result<()>
+where
+    C: HttpClient,
+{
+    require_scope(token, Scope::ModeratorManageBannedUsers)?;
+
+    let duration = duration.clamp(1, MAX_TIMEOUT_DURATION);
+    let request = BanUserRequest::new(&token.user_id, &token.user_id);
+    let body = BanUserBody::new(user_id, reason, duration);
+
+    helix_client
+        .req_post(request, body, token)
+        .await
+        .context("failed to timeout user")?;
+
+    Ok(())
+}
+
+/// Deletes a chat message, messages must be less than 6 hours old
+/// and cannot be from the broadcaster or a moderator
+pub async fn delete_chat_message<C>(
+    helix_client: &HelixClient<'_, C>,
+    token: &UserToken,
+    message_id: &MsgIdRef,
+) -> anyhow::Result<()>
+where
+    C: HttpClient,
+{
+    require_scope(token, Scope::ModeratorManageChatMessages)?;
+
+    let request =
+        DeleteChatMessagesRequest::new(&token.user_id, &token.user_id).message_id(message_id);
+
+    helix_client
+        .req_delete(request, token)
+        .await
+        .context("failed to delete chat message")?;
+
+    Ok(())
+}
+
+/// Sends an official shoutout to another broadcaster
+pub async fn send_shoutout<C>(
+    helix_client: &HelixClient<'_, C>,
+    token: &UserToken,
+    to_broadcaster_id: &UserIdRef,
+) -> anyhow::Result<()>
+where
+    C: HttpClient,
+{
+    require_scope(token, Scope::ModeratorManageShoutouts)?;
+
+    let request = SendAShoutoutRequest::new(&token.user_id, to_broadcaster_id, &token.user_id);
+
+    helix_client
+        .req_post(request, Default::default(), token)
+        .await
+        .context("failed to send shoutout")?;
+
+    Ok(())
+}
+
+/// Sends an announcement highlighted with the `color` to the chat of the
+/// broadcaster, the `token` user must be the broadcaster or a moderator
+pub async fn send_announcement<C>(
+    helix_client: &HelixClient<'_, C>,
+    token: &UserToken,
+    broadcaster_id: &UserIdRef,
+    message: &str,
+    color: AnnouncementColor,
+) -> anyhow::Result<()>
+where
+    C: HttpClient,
+{
+    require_scope(token, Scope::ModeratorManageAnnouncements)?;
+
+    let request = SendChatAnnouncementRequest::new(broadcaster_id, &token.user_id);
+    let body =
+        SendChatAnnouncementBody::new(message, color).context("invalid announcement color")?;
+
+    let _: SendChatAnnouncementResponse = helix_client
+        .req_post(request, body, token)
+        .await
+        .context("failed to send announcement")?
+        .data;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{send_announcement, timeout_user, MAX_TIMEOUT_DURATION};
+    use crate::twitch::{
+        manager::TWITCH_REQUIRED_SCOPES,
+        mock::{mock_token, mock_token_with_scopes, start_mock_helix_server, MockHelixClient},
+    };
+    use axum::{
+        extract::{Query, State},
+        http::StatusCode,
+        routing::post,
+        Json, Router,
+    };
+    use serde_json::{json, Value};
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+    use twitch_api::{
+        helix::{chat::AnnouncementColor, Scope},
+        types::UserId,
+        HelixClient,
+    };
+
+    /// Requests received by the mock helix server
+    type ReceivedRequests = Arc<Mutex<Vec<(HashMap<String, String>, Value)>>>;
+
+    async fn handle_ban_user(
+        State(requests): State<ReceivedRequests>,
+        Query(query): Query<HashMap<String, String>>,
+        Json(body): Json<Value>,
+    ) -> Json<Value> {
+        requests.lock().unwrap().push((query.clone(), body.clone()));
+
+        Json(json!({
+            "data": [{
+                "broadcaster_id": query["broadcaster_id"],
+                "moderator_id": query["moderator_id"],
+                "user_id": body["data"]["user_id"],
+                "created_at": "2025-02-15T00:00:00Z",
+                "end_time": "2025-02-15T00:01:00Z"
+            }]
+        }))
+    }
+
+    async fn handle_announcement(
+        State(requests): State<ReceivedRequests>,
+        Query(query): Query<HashMap<String, String>>,
+        Json(body): Json<Value>,
+    ) -> StatusCode {
+        requests.lock().unwrap().push((query, body));
+        StatusCode::NO_CONTENT
+    }
+
+    /// Starts a mock helix server handling moderation requests
+    async fn start_moderation_server() -> (HelixClient<'static, MockHelixClient>, ReceivedRequests)
+    {
+        let requests = ReceivedRequests::default();
+        let app = Router::new()
+            .route("/helix/moderation/bans", post(handle_ban_user))
+            .route("/helix/chat/announcements", post(handle_announcement))
+            .with_state(requests.clone());
+
+        (start_mock_helix_server(app).await, requests)
+    }
+
+    /// Tests putting a user in a timeout, durations longer than the
+    /// maximum should be clamped
+    #[tokio::test]
+    async fn test_timeout_user() {
+        let (helix_client, requests) = start_moderation_server().await;
+        let token = mock_token();
+        let user_id = UserId::new("mock-user-id".to_string());
+
+        timeout_user(&helix_client, &token, &user_id, 60, "Redeemed a timeout")
+            .await
+            .unwrap();
+        timeout_user(&helix_client, &token, &user_id, u32::MAX, "")
+            .await
+            .unwrap();
+
+        let requests = requests.lock().unwrap();
+        let (query, body) = &requests[0];
+        assert_eq!(query["broadcaster_id"], "mock-broadcaster-id");
+        assert_eq!(query["moderator_id"], "mock-broadcaster-id");
+        assert_eq!(
+            body,
+            &json!({
+                "data": {
+                    "user_id": "mock-user-id",
+                    "duration": 60,
+                    "reason": "Redeemed a timeout"
+                }
+            })
+        );
+
+        let (_, body) = &requests[1];
+        assert_eq!(body["data"]["duration"], MAX_TIMEOUT_DURATION);
+    }
+
+    /// Tests that requests are not sent when the token is missing the scope
+    #[tokio::test]
+    async fn test_timeout_user_missing_scope() {
+        let (helix_client, requests) = start_moderation_server().await;
+        let scopes: Vec<Scope> = TWITCH_REQUIRED_SCOPES
+            .iter()
+            .filter(|scope| **scope != Scope::ModeratorManageBannedUsers)
+            .cloned()
+            .collect();
+        let token = mock_token_with_scopes(scopes);
+
+        let result = timeout_user(
+            &helix_client,
+            &token,
+            &UserId::new("mock-user-id".to_string()),
+            60,
+            "",
+        )
+        .await;
+
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("moderator:manage:banned_users"));
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    /// Tests sending a colored announcement
+    #[tokio::test]
+    async fn test_send_announcement() {
+        let (helix_client, requests) = start_moderation_server().await;
+        let token = mock_token();
+
+        send_announcement(
+            &helix_client,
+            &token,
+            &token.user_id,
+            "Hello chat",
+            AnnouncementColor::Purple,
+        )
+        .await
+        .unwrap();
+
+        let requests = requests.lock().unwrap();
+        let (query, body) = &requests[0];
+        assert_eq!(query["broadcaster_id"], "mock-broadcaster-id");
+        assert_eq!(
+            body,
+            &json!({
+                "message": "Hello chat",
+                "color": "purple"
+            })
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! # Rate Limit
+//!
+//! Rate limiting for chat replies sent by the app to prevent the
+//! replies themselves from spamming chat
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use uuid::Uuid;
+
+/// Rate limiter tracking when each key is next allowed to reply
+#[derive(Default)]
+pub struct ReplyRateLimiter {
+    limited_until: Mutex<HashMap<Uuid, Instant>>,
+}
+
+impl ReplyRateLimiter {
+    /// Attempts to acquire a reply for the `key`, only succeeds if no reply for
+    /// the `key` has been acquired within the last `interval`
+    pub fn try_acquire(&self, key: Uuid, interval: Duration) -> bool {
+        self.try_acquire_at(key, interval, Instant::now())
+    }
+
+    fn try_acquire_at(&self, key: Uuid, interval: Duration, now: Instant) -> bool {
+        let limited_until = &mut *self.limited_until.lock().expect("rate limit lock poisoned");
+
+        // Drop keys that are no longer limited, each key expires based
+        // on the interval it was acquired with
+        limited_until.retain(|_, expires_at| now < *expires_at);
+
+        if limited_until.contains_key(&key) {
+            return false;
+        }
+
+        limited_until.insert(key, now + interval);
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ReplyRateLimiter;
+    use std::time::{Duration, Instant};
+    use uuid::Uuid;
+
+    /// Tests that replies are limited within the interval of each key
+    #[test]
+    fn test_reply_rate_limit() {
+        let limiter = ReplyRateLimiter::default();
+        let interval = Duration::from_secs(30);
+        let key = Uuid::new_v4();
+        let other_key = Uuid::new_v4();
+        let start = Instant::now();
+
+        assert!(limiter.try_acquire_at(key, interval, start));
+
+        // Limited within the interval
+        assert!(!limiter.try_acquire_at(key, interval, start + Duration::from_secs(10)));
+
+        // Separate keys are limited separately
+        assert!(limiter.try_acquire_at(other_key, interval, start + Duration::from_secs(10)));
+
+        // Allowed once the interval has passed
+        assert!(limiter.try_acquire_at(key, interval, start + Duration::from_secs(30)));
+
+        // Keys acquired with different intervals expire separately
+        let limiter = ReplyRateLimiter::default();
+        let long_interval = Duration::from_secs(60);
+        let short_interval = Duration::from_secs(5);
+        let long_key = Uuid::new_v4();
+        let short_key = Uuid::new_v4();
+        let start = Instant::now();
+
+        assert!(limiter.try_acquire_at(long_key, long_interval, start));
+        assert!(limiter.try_acquire_at(short_key, short_interval, start));
+
+        // Short interval key is allowed again, acquiring it must not
+        // purge the long interval key
+        let later = start + Duration::from_secs(10);
+        assert!(limiter.try_acquire_at(short_key, short_interval, later));
+        assert!(!limiter.try_acquire_at(long_key, long_interval, later));
+
+        // Long interval key is still limited when checked with a short interval
+        assert!(!limiter.try_acquire_at(long_key, short_interval, later));
+
+        // Long interval key is allowed once its own interval has passed
+        assert!(limiter.try_acquire_at(long_key, long_interval, start + Duration::from_secs(60)));
+    }
+}
@@ -0,0 +1,182 @@
+//! # Redemptions
+//!
+//! Helix requests for managing channel point reward redemptions
+
+use anyhow::Context;
+use twitch_api::{
+    helix::points::{
+        CustomRewardRedemptionStatus, UpdateRedemptionStatusBody, UpdateRedemptionStatusRequest,
+    },
+    twitch_oauth2::UserToken,
+    types::{RedemptionIdRef, RewardIdRef},
+    HelixClient, HttpClient,
+};
+
+/// Updates the status of a redemption of a channel point reward, redemptions can
+/// be marked as FULFILLED or CANCELED (Which refunds the points to the user)
+///
+/// Only redemptions of rewards created by the app client ID can be updated
+pub async fn update_redemption_status<C>(
+    helix_client: &HelixClient<'_, C>,
+    token: &UserToken,
+    reward_id: &RewardIdRef,
+    redemption_id: &RedemptionIdRef,
+    status: CustomRewardRedemptionStatus,
+) -> anyhow::Result<()>
+where
+    C: HttpClient,
+{
+    let request = UpdateRedemptionStatusRequest::new(&token.user_id, reward_id, redemption_id);
+    let body = UpdateRedemptionStatusBody::status(status);
+
+    helix_client
+        .req_patch(request, body, token)
+        .await
+        .context("failed to update redemption status")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::update_redemption_status;
+    use crate::twitch::mock::{mock_token, start_mock_helix_server, MockHelixClient};
+    use axum::{
+        extract::{Query, State},
+        http::StatusCode,
+        routing::patch,
+        Json, Router,
+    };
+    use serde_json::{json, Value};
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+    use twitch_api::{
+        helix::points::CustomRewardRedemptionStatus,
+        types::{RedemptionId, RewardId},
+        HelixClient,
+    };
+
+    /// Requests received by the mock helix server
+    type ReceivedRequests = Arc<Mutex<Vec<(HashMap<String, String>, Value)>>>;
+
+    async fn handle_update_redemption(
+        State(requests): State<ReceivedRequests>,
+        Query(query): Query<HashMap<String, String>>,
+        Json(body): Json<Value>,
+    ) -> (StatusCode, Json<Value>) {
+        requests.lock().unwrap().push((query.clone(), body.clone()));
+
+        // Redemptions that are no longer unfulfilled cannot be updated
+        if query["id"] == "fulfilled-redemption-id" {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                    "error": "Not Found",
+                    "status": 404,
+                    "message": "redemption not found or already fulfilled"
+                })),
+            );
+        }
+
+        (
+            StatusCode::OK,
+            Json(json!({
+                "data": [{
+                    "broadcaster_id": query["broadcaster_id"],
+                    "broadcaster_login": "mockbroadcaster",
+                    "broadcaster_name": "MockBroadcaster",
+                    "id": query["id"],
+                    "user_id": "mock-user-id",
+                    "user_login": "mockuser",
+                    "user_name": "MockUser",
+                    "user_input": "",
+                    "status": body["status"],
+                    "redeemed_at": "2025-02-15T00:00:00Z",
+                    "reward": {
+                        "id": query["reward_id"],
+                        "title": "Test Reward",
+                        "prompt": "",
+                        "cost": 100
+                    }
+                }]
+            })),
+        )
+    }
+
+    /// Starts a mock helix server handling redemption updates
+    async fn start_redemptions_server() -> (HelixClient<'static, MockHelixClient>, ReceivedRequests)
+    {
+        let requests = ReceivedRequests::default();
+        let app = Router::new()
+            .route(
+                "/helix/channel_points/custom_rewards/redemptions",
+                patch(handle_update_redemption),
+            )
+            .with_state(requests.clone());
+
+        (start_mock_helix_server(app).await, requests)
+    }
+
+    /// Tests updating the status of a redemption against a mock helix server
+    #[tokio::test]
+    async fn test_update_redemption_status() {
+        let (helix_client, requests) = start_redemptions_server().await;
+        let token = mock_token();
+
+        let reward_id = RewardId::new("mock-reward-id".to_string());
+        let redemption_id = RedemptionId::new("mock-redemption-id".to_string());
+
+        update_redemption_status(
+            &helix_client,
+            &token,
+            &reward_id,
+            &redemption_id,
+            CustomRewardRedemptionStatus::Fulfilled,
+        )
+        .await
+        .unwrap();
+
+        update_redemption_status(
+            &helix_client,
+            &token,
+            &reward_id,
+            &redemption_id,
+            CustomRewardRedemptionStatus::Canceled,
+        )
+        .await
+        .unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+
+        let (query, body) = &requests[0];
+        assert_eq!(query["broadcaster_id"], "mock-broadcaster-id");
+        assert_eq!(query["reward_id"], "mock-reward-id");
+        assert_eq!(query["id"], "mock-redemption-id");
+        assert_eq!(body["status"], "FULFILLED");
+
+        let (_, body) = &requests[1];
+        assert_eq!(body["status"], "CANCELED");
+    }
+
+    /// Tests that errors from helix are reported
+    #[tokio::test]
+    async fn test_update_redemption_status_error() {
+        let (helix_client, requests) = start_redemptions_server().await;
+        let token = mock_token();
+
+        let result = update_redemption_status(
+            &helix_client,
+            &token,
+            &RewardId::new("mock-reward-id".to_string()),
+            &RedemptionId::new("fulfilled-redemption-id".to_string()),
+            CustomRewardRedemptionStatus::Canceled,
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+}
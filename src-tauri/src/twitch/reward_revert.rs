@@ -0,0 +1,263 @@
+//! # Reward Revert
+//!
+//! Tracking for pending reverts of reward states changed by
+//! the set reward state outcome
+//!
+//! Pending reverts are only kept in memory, reverts that have not
+//! elapsed when the app exits are lost and the rewards keep the
+//! state they were changed to
+
+use std::{collections::HashMap, sync::Mutex, time::Instant};
+
+/// Paused and enabled state of a reward, fields that are not
+/// specified are not changed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RewardState {
+    pub paused: Option<bool>,
+    pub enabled: Option<bool>,
+}
+
+impl RewardState {
+    fn is_empty(&self) -> bool {
+        self.paused.is_none() && self.enabled.is_none()
+    }
+}
+
+/// Revert waiting for its time to elapse
+struct PendingRevert {
+    /// State to restore
+    state: RewardState,
+    /// When the state should be restored
+    revert_at: Instant,
+    /// Identifies the latest schedule of the revert, reverts
+    /// scheduled before the latest are ignored
+    generation: u64,
+}
+
+/// Scheduled revert for a reward, the revert should be applied at
+/// `revert_at` using [PendingRewardReverts::take_due] with the `generation`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduledRevert {
+    pub revert_at: Instant,
+    pub generation: u64,
+}
+
+/// Collection of pending reverts, only one pending revert is
+/// kept for each reward
+#[derive(Default)]
+pub struct PendingRewardReverts {
+    inner: Mutex<PendingRewardRevertsInner>,
+}
+
+#[derive(Default)]
+struct PendingRewardRevertsInner {
+    pending: HashMap<String, PendingRevert>,
+    next_generation: u64,
+}
+
+impl PendingRewardReverts {
+    /// Schedules restoring the `previous` state of the reward at `revert_at`
+    ///
+    /// When a revert is already pending for the reward the state it restores is
+    /// kept (It is the state from before any of the changes) and the revert is
+    /// extended to the later of the two times
+    pub fn schedule(
+        &self,
+        reward_id: String,
+        previous: RewardState,
+        revert_at: Instant,
+    ) -> ScheduledRevert {
+        let inner = &mut *self.inner.lock().expect("reward revert lock poisoned");
+
+        let generation = inner.next_generation;
+        inner.next_generation = inner.next_generation.wrapping_add(1);
+
+        let revert = inner
+            .pending
+            .entry(reward_id)
+            .and_modify(|revert| {
+                revert.state.paused = revert.state.paused.or(previous.paused);
+                revert.state.enabled = revert.state.enabled.or(previous.enabled);
+                revert.revert_at = revert.revert_at.max(revert_at);
+                revert.generation = generation;
+            })
+            .or_insert(PendingRevert {
+                state: previous,
+                revert_at,
+                generation,
+            });
+
+        ScheduledRevert {
+            revert_at: revert.revert_at,
+            generation,
+        }
+    }
+
+    /// Cancels reverting the fields of the reward that were permanently
+    /// changed to the `changed` state
+    pub fn cancel(&self, reward_id: &str, changed: RewardState) {
+        let inner = &mut *self.inner.lock().expect("reward revert lock poisoned");
+
+        let Some(revert) = inner.pending.get_mut(reward_id) else {
+            return;
+        };
+
+        if changed.paused.is_some() {
+            revert.state.paused = None;
+        }
+
+        if changed.enabled.is_some() {
+            revert.state.enabled = None;
+        }
+
+        if revert.state.is_empty() {
+            inner.pending.remove(reward_id);
+        }
+    }
+
+    /// Takes the state to restore for the reward if the revert is still
+    /// pending and was last scheduled with `generation`
+    pub fn take_due(&self, reward_id: &str, generation: u64) -> Option<RewardState> {
+        let inner = &mut *self.inner.lock().expect("reward revert lock poisoned");
+
+        if inner
+            .pending
+            .get(reward_id)
+            .is_none_or(|revert| revert.generation != generation)
+        {
+            return None;
+        }
+
+        inner
+            .pending
+            .remove(reward_id)
+            .map(|revert| revert.state)
+            .filter(|state| !state.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{PendingRewardReverts, RewardState};
+    use std::time::{Duration, Instant};
+
+    /// Tests that re-triggering keeps the original state and extends the revert
+    #[test]
+    fn test_reward_revert_retrigger() {
+        let reverts = PendingRewardReverts::default();
+        let start = Instant::now();
+
+        // Reward was enabled and unpaused before the first trigger
+        let first = reverts.schedule(
+            "reward".to_string(),
+            RewardState {
+                paused: Some(false),
+                enabled: None,
+            },
+            start + Duration::from_secs(60),
+        );
+
+        // Second trigger sees the paused state from the first trigger and
+        // also disables the reward
+        let second = reverts.schedule(
+            "reward".to_string(),
+            RewardState {
+                paused: Some(true),
+                enabled: Some(true),
+            },
+            start + Duration::from_secs(90),
+        );
+
+        assert_eq!(second.revert_at, start + Duration::from_secs(90));
+
+        // First revert was superseded
+        assert_eq!(reverts.take_due("reward", first.generation), None);
+
+        // Original paused state is restored along with the enabled state
+        assert_eq!(
+            reverts.take_due("reward", second.generation),
+            Some(RewardState {
+                paused: Some(false),
+                enabled: Some(true),
+            })
+        );
+
+        // Revert is only applied once
+        assert_eq!(reverts.take_due("reward", second.generation), None);
+    }
+
+    /// Tests that a shorter re-trigger does not shorten the pending revert
+    #[test]
+    fn test_reward_revert_extend() {
+        let reverts = PendingRewardReverts::default();
+        let start = Instant::now();
+        let previous = RewardState {
+            paused: Some(false),
+            enabled: None,
+        };
+
+        reverts.schedule(
+            "reward".to_string(),
+            previous,
+            start + Duration::from_secs(60),
+        );
+        let scheduled = reverts.schedule(
+            "reward".to_string(),
+            previous,
+            start + Duration::from_secs(10),
+        );
+
+        assert_eq!(scheduled.revert_at, start + Duration::from_secs(60));
+    }
+
+    /// Tests that permanently changing a field cancels reverting it
+    #[test]
+    fn test_reward_revert_cancel() {
+        let reverts = PendingRewardReverts::default();
+        let start = Instant::now();
+
+        let scheduled = reverts.schedule(
+            "reward".to_string(),
+            RewardState {
+                paused: Some(false),
+                enabled: Some(true),
+            },
+            start,
+        );
+
+        reverts.cancel(
+            "reward",
+            RewardState {
+                paused: Some(true),
+                enabled: None,
+            },
+        );
+
+        assert_eq!(
+            reverts.take_due("reward", scheduled.generation),
+            Some(RewardState {
+                paused: None,
+                enabled: Some(true),
+            })
+        );
+
+        let scheduled = reverts.schedule(
+            "reward".to_string(),
+            RewardState {
+                paused: Some(false),
+                enabled: None,
+            },
+            start,
+        );
+
+        reverts.cancel(
+            "reward",
+            RewardState {
+                paused: Some(true),
+                enabled: None,
+            },
+        );
+
+        assert_eq!(reverts.take_due("reward", scheduled.generation), None);
+    }
+}
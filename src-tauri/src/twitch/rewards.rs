@@ -0,0 +1,433 @@
+//! # Rewards
+//!
+//! Helix requests for managing channel point custom rewards
+//!
+//! Twitch only allows rewards created by the app client ID to be updated
+//! or deleted, rewards created through the Twitch dashboard are read-only
+
+use anyhow::{anyhow, Context};
+use serde::Deserialize;
+use twitch_api::{
+    helix::points::{
+        update_custom_reward::UpdateCustomReward, CreateCustomRewardBody,
+        CreateCustomRewardRequest, CustomReward, DeleteCustomRewardRequest, GetCustomRewardRequest,
+        UpdateCustomRewardBody, UpdateCustomRewardRequest,
+    },
+    twitch_oauth2::UserToken,
+    types::RewardIdRef,
+    HelixClient, HttpClient,
+};
+
+/// Details for creating a new custom reward
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateReward {
+    /// Title of the reward
+    pub title: String,
+    /// Cost of the reward in channel points
+    pub cost: u32,
+    /// Prompt shown to viewers when redeeming the reward
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// Whether the reward is visible to viewers
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Cooldown in seconds between redemptions, zero for no cooldown
+    #[serde(default)]
+    pub global_cooldown: u32,
+    /// Maximum redemptions per stream, zero for no maximum
+    #[serde(default)]
+    pub max_per_stream: u32,
+    /// Maximum redemptions per user per stream, zero for no maximum
+    #[serde(default)]
+    pub max_per_user_per_stream: u32,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Changes to apply to a custom reward, fields that are not
+/// specified are left unchanged
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdateReward {
+    pub title: Option<String>,
+    pub cost: Option<u32>,
+    pub prompt: Option<String>,
+    pub enabled: Option<bool>,
+    pub paused: Option<bool>,
+    /// Cooldown in seconds between redemptions, zero to remove the cooldown
+    pub global_cooldown: Option<u32>,
+    /// Maximum redemptions per stream, zero to remove the maximum
+    pub max_per_stream: Option<u32>,
+    /// Maximum redemptions per user per stream, zero to remove the maximum
+    pub max_per_user_per_stream: Option<u32>,
+}
+
+/// Gets a custom reward on the channel of the token user, [None] when
+/// the reward does not exist
+pub async fn get_custom_reward<C>(
+    helix_client: &HelixClient<'_, C>,
+    token: &UserToken,
+    reward_id: &RewardIdRef,
+) -> anyhow::Result<Option<CustomReward>>
+where
+    C: HttpClient,
+{
+    let reward_ids: &[&RewardIdRef] = &[reward_id];
+    let request = GetCustomRewardRequest::broadcaster_id(&token.user_id).ids(reward_ids);
+
+    let rewards: Vec<CustomReward> = helix_client
+        .req_get(request, token)
+        .await
+        .context("failed to get reward")?
+        .data;
+
+    Ok(rewards.into_iter().next())
+}
+
+/// Creates a new custom reward on the channel of the token user
+pub async fn create_custom_reward<C>(
+    helix_client: &HelixClient<'_, C>,
+    token: &UserToken,
+    create: &CreateReward,
+) -> anyhow::Result<CustomReward>
+where
+    C: HttpClient,
+{
+    let request = CreateCustomRewardRequest::broadcaster_id(&token.user_id);
+
+    let mut body = CreateCustomRewardBody::new(create.title.as_str(), create.cost as usize);
+    body.prompt = create.prompt.as_deref().map(Into::into);
+    body.is_enabled = Some(create.enabled);
+
+    if create.global_cooldown > 0 {
+        body.is_global_cooldown_enabled = Some(true);
+        body.global_cooldown_seconds = Some(create.global_cooldown as usize);
+    }
+
+    if create.max_per_stream > 0 {
+        body.is_max_per_stream_enabled = Some(true);
+        body.max_per_stream = Some(create.max_per_stream as usize);
+    }
+
+    if create.max_per_user_per_stream > 0 {
+        body.is_max_per_user_per_stream_enabled = Some(true);
+        body.max_per_user_per_stream = Some(create.max_per_user_per_stream as usize);
+    }
+
+    let reward = helix_client
+        .req_post(request, body, token)
+        .await
+        .context("failed to create reward")?
+        .data;
+
+    Ok(reward)
+}
+
+/// Updates a custom reward on the channel of the token user
+pub async fn update_custom_reward<C>(
+    helix_client: &HelixClient<'_, C>,
+    token: &UserToken,
+    reward_id: &RewardIdRef,
+    update: &UpdateReward,
+) -> anyhow::Result<CustomReward>
+where
+    C: HttpClient,
+{
+    let request = UpdateCustomRewardRequest::new(&token.user_id, reward_id);
+
+    let mut body = UpdateCustomRewardBody::default();
+    body.title = update.title.as_deref().map(Into::into);
+    body.cost = update.cost.map(|cost| cost as usize);
+    body.prompt = update.prompt.as_deref().map(Into::into);
+    body.is_enabled = update.enabled;
+    body.is_paused = update.paused;
+
+    if let Some(global_cooldown) = update.global_cooldown {
+        body.is_global_cooldown_enabled = Some(global_cooldown > 0);
+        if global_cooldown > 0 {
+            body.global_cooldown_seconds = Some(global_cooldown as usize);
+        }
+    }
+
+    if let Some(max_per_stream) = update.max_per_stream {
+        body.is_max_per_stream_enabled = Some(max_per_stream > 0);
+        if max_per_stream > 0 {
+            body.max_per_stream = Some(max_per_stream as usize);
+        }
+    }
+
+    if let Some(max_per_user_per_stream) = update.max_per_user_per_stream {
+        body.is_max_per_user_per_stream_enabled = Some(max_per_user_per_stream > 0);
+        if max_per_user_per_stream > 0 {
+            body.max_per_user_per_stream = Some(max_per_user_per_stream as usize);
+        }
+    }
+
+    let response = helix_client
+        .req_patch(request, body, token)
+        .await
+        .context("failed to update reward")?
+        .data;
+
+    match response {
+        UpdateCustomReward::Success(reward) => Ok(reward),
+        _ => Err(anyhow!("unexpected update reward response")),
+    }
+}
+
+/// Deletes a custom reward from the channel of the token user
+pub async fn delete_custom_reward<C>(
+    helix_client: &HelixClient<'_, C>,
+    token: &UserToken,
+    reward_id: &RewardIdRef,
+) -> anyhow::Result<()>
+where
+    C: HttpClient,
+{
+    let request = DeleteCustomRewardRequest::new(&token.user_id, reward_id);
+
+    helix_client
+        .req_delete(request, token)
+        .await
+        .context("failed to delete reward")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        create_custom_reward, delete_custom_reward, get_custom_reward, update_custom_reward,
+        CreateReward, UpdateReward,
+    };
+    use crate::twitch::mock::{mock_token, start_mock_helix_server, MockHelixClient};
+    use axum::{
+        extract::{Query, State},
+        http::StatusCode,
+        routing::get,
+        Json, Router,
+    };
+    use serde_json::{json, Value};
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+    use twitch_api::{types::RewardId, HelixClient};
+
+    /// Requests received by the mock helix server
+    type ReceivedRequests = Arc<Mutex<Vec<(&'static str, HashMap<String, String>, Value)>>>;
+
+    /// Creates a reward response from the fields of a request body
+    fn mock_reward(id: &str, body: &Value) -> Value {
+        json!({
+            "broadcaster_id": "mock-broadcaster-id",
+            "broadcaster_login": "mockbroadcaster",
+            "broadcaster_name": "MockBroadcaster",
+            "id": id,
+            "image": null,
+            "default_image": null,
+            "background_color": "#00E5CB",
+            "title": body.get("title").cloned().unwrap_or(json!("Test Reward")),
+            "prompt": body.get("prompt").cloned().unwrap_or(json!("")),
+            "cost": body.get("cost").cloned().unwrap_or(json!(100)),
+            "is_enabled": body.get("is_enabled").cloned().unwrap_or(json!(true)),
+            "is_paused": body.get("is_paused").cloned().unwrap_or(json!(false)),
+            "is_user_input_required": false,
+            "max_per_stream_setting": {
+                "is_enabled": body.get("is_max_per_stream_enabled").cloned().unwrap_or(json!(false)),
+                "max_per_stream": body.get("max_per_stream").cloned().unwrap_or(json!(0))
+            },
+            "max_per_user_per_stream_setting": {
+                "is_enabled": false,
+                "max_per_user_per_stream": 0
+            },
+            "global_cooldown_setting": {
+                "is_enabled": body.get("is_global_cooldown_enabled").cloned().unwrap_or(json!(false)),
+                "global_cooldown_seconds": body.get("global_cooldown_seconds").cloned().unwrap_or(json!(0))
+            },
+            "is_in_stock": true,
+            "should_redemptions_skip_request_queue": false,
+            "redemptions_redeemed_current_stream": null,
+            "cooldown_expires_at": null
+        })
+    }
+
+    async fn handle_get_reward(
+        State(requests): State<ReceivedRequests>,
+        Query(query): Query<HashMap<String, String>>,
+    ) -> Json<Value> {
+        let reward = mock_reward(&query["id"], &json!({ "is_paused": true }));
+        requests.lock().unwrap().push(("GET", query, Value::Null));
+        Json(json!({ "data": [reward] }))
+    }
+
+    async fn handle_create_reward(
+        State(requests): State<ReceivedRequests>,
+        Query(query): Query<HashMap<String, String>>,
+        Json(body): Json<Value>,
+    ) -> Json<Value> {
+        let reward = mock_reward("created-reward-id", &body);
+        requests.lock().unwrap().push(("POST", query, body));
+        Json(json!({ "data": [reward] }))
+    }
+
+    async fn handle_update_reward(
+        State(requests): State<ReceivedRequests>,
+        Query(query): Query<HashMap<String, String>>,
+        Json(body): Json<Value>,
+    ) -> Json<Value> {
+        let reward = mock_reward(&query["id"], &body);
+        requests.lock().unwrap().push(("PATCH", query, body));
+        Json(json!({ "data": [reward] }))
+    }
+
+    async fn handle_delete_reward(
+        State(requests): State<ReceivedRequests>,
+        Query(query): Query<HashMap<String, String>>,
+    ) -> StatusCode {
+        requests
+            .lock()
+            .unwrap()
+            .push(("DELETE", query, Value::Null));
+        StatusCode::NO_CONTENT
+    }
+
+    /// Starts a mock helix server handling custom rewards
+    async fn start_rewards_server() -> (HelixClient<'static, MockHelixClient>, ReceivedRequests) {
+        let requests = ReceivedRequests::default();
+        let app = Router::new()
+            .route(
+                "/helix/channel_points/custom_rewards",
+                get(handle_get_reward)
+                    .post(handle_create_reward)
+                    .patch(handle_update_reward)
+                    .delete(handle_delete_reward),
+            )
+            .with_state(requests.clone());
+
+        (start_mock_helix_server(app).await, requests)
+    }
+
+    /// Tests getting a reward by ID
+    #[tokio::test]
+    async fn test_get_custom_reward() {
+        let (helix_client, requests) = start_rewards_server().await;
+        let token = mock_token();
+
+        let reward = get_custom_reward(
+            &helix_client,
+            &token,
+            &RewardId::new("mock-reward-id".to_string()),
+        )
+        .await
+        .unwrap()
+        .expect("missing reward");
+
+        assert_eq!(reward.id.as_str(), "mock-reward-id");
+        assert!(reward.is_paused);
+        assert!(reward.is_enabled);
+
+        let requests = requests.lock().unwrap();
+        let (method, query, _) = &requests[0];
+        assert_eq!(*method, "GET");
+        assert_eq!(query["broadcaster_id"], "mock-broadcaster-id");
+        assert_eq!(query["id"], "mock-reward-id");
+    }
+
+    /// Tests creating a reward with a cooldown and no maximum per stream
+    #[tokio::test]
+    async fn test_create_custom_reward() {
+        let (helix_client, requests) = start_rewards_server().await;
+        let token = mock_token();
+
+        let reward = create_custom_reward(
+            &helix_client,
+            &token,
+            &CreateReward {
+                title: "Throw Things".to_string(),
+                cost: 500,
+                prompt: Some("Throws things at the model".to_string()),
+                enabled: true,
+                global_cooldown: 30,
+                max_per_stream: 0,
+                max_per_user_per_stream: 0,
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(reward.id.as_str(), "created-reward-id");
+        assert_eq!(reward.title, "Throw Things");
+        assert_eq!(reward.cost, 500);
+
+        let requests = requests.lock().unwrap();
+        let (method, query, body) = &requests[0];
+        assert_eq!(*method, "POST");
+        assert_eq!(query["broadcaster_id"], "mock-broadcaster-id");
+        assert_eq!(body["is_global_cooldown_enabled"], true);
+        assert_eq!(body["global_cooldown_seconds"], 30);
+        assert!(body.get("is_max_per_stream_enabled").is_none());
+        assert!(body.get("max_per_stream").is_none());
+    }
+
+    /// Tests that only the specified fields are sent when updating a reward
+    /// and that a zero maximum per stream disables the maximum
+    #[tokio::test]
+    async fn test_update_custom_reward() {
+        let (helix_client, requests) = start_rewards_server().await;
+        let token = mock_token();
+        let reward_id = RewardId::new("mock-reward-id".to_string());
+
+        let reward = update_custom_reward(
+            &helix_client,
+            &token,
+            &reward_id,
+            &UpdateReward {
+                cost: Some(1000),
+                paused: Some(true),
+                max_per_stream: Some(0),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(reward.cost, 1000);
+        assert!(reward.is_paused);
+
+        let requests = requests.lock().unwrap();
+        let (method, query, body) = &requests[0];
+        assert_eq!(*method, "PATCH");
+        assert_eq!(query["id"], "mock-reward-id");
+        assert_eq!(
+            body,
+            &json!({
+                "cost": 1000,
+                "is_paused": true,
+                "is_max_per_stream_enabled": false
+            })
+        );
+    }
+
+    /// Tests deleting a reward
+    #[tokio::test]
+    async fn test_delete_custom_reward() {
+        let (helix_client, requests) = start_rewards_server().await;
+        let token = mock_token();
+
+        delete_custom_reward(
+            &helix_client,
+            &token,
+            &RewardId::new("mock-reward-id".to_string()),
+        )
+        .await
+        .unwrap();
+
+        let requests = requests.lock().unwrap();
+        let (method, query, _) = &requests[0];
+        assert_eq!(*method, "DELETE");
+        assert_eq!(query["broadcaster_id"], "mock-broadcaster-id");
+        assert_eq!(query["id"], "mock-reward-id");
+    }
+}
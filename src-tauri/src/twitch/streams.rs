@@ -0,0 +1,102 @@
+//! # Streams
+//!
+//! Helix requests for the live stream of the token user
+
+use anyhow::Context;
+use twitch_api::{
+    helix::streams::{GetStreamsRequest, Stream},
+    twitch_oauth2::UserToken,
+    types::UserIdRef,
+    HelixClient, HttpClient,
+};
+
+/// Gets the current live stream of the token user, [None] when
+/// the user is not live
+pub async fn get_live_stream<C>(
+    helix_client: &HelixClient<'_, C>,
+    token: &UserToken,
+) -> anyhow::Result<Option<Stream>>
+where
+    C: HttpClient,
+{
+    let user_ids: &[&UserIdRef] = &[&token.user_id];
+    let request = GetStreamsRequest::user_ids(user_ids);
+
+    let streams = helix_client
+        .req_get(request, token)
+        .await
+        .context("failed to get streams")?
+        .data;
+
+    Ok(streams.into_iter().next())
+}
+
+#[cfg(test)]
+mod test {
+    use super::get_live_stream;
+    use crate::twitch::mock::{mock_token, start_mock_helix_server};
+    use axum::{
+        extract::{Query, State},
+        routing::get,
+        Json, Router,
+    };
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+
+    async fn handle_get_streams(
+        State(live): State<bool>,
+        Query(query): Query<HashMap<String, String>>,
+    ) -> Json<Value> {
+        if !live {
+            return Json(json!({ "data": [], "pagination": {} }));
+        }
+
+        Json(json!({
+            "data": [{
+                "id": "mock-stream-id",
+                "user_id": query["user_id"],
+                "user_login": "mockbroadcaster",
+                "user_name": "MockBroadcaster",
+                "game_id": "0",
+                "game_name": "",
+                "type": "live",
+                "title": "Mock Stream",
+                "tags": [],
+                "viewer_count": 1,
+                "started_at": "2025-01-01T12:00:00Z",
+                "language": "en",
+                "thumbnail_url": "",
+                "tag_ids": [],
+                "is_mature": false
+            }],
+            "pagination": {}
+        }))
+    }
+
+    /// Tests getting the stream of a live and an offline broadcaster
+    #[tokio::test]
+    async fn test_get_live_stream() {
+        let token = mock_token();
+
+        let app = Router::new()
+            .route("/helix/streams", get(handle_get_streams))
+            .with_state(true);
+        let helix_client = start_mock_helix_server(app).await;
+
+        let stream = get_live_stream(&helix_client, &token)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stream.id.as_str(), "mock-stream-id");
+        assert_eq!(stream.user_id.as_str(), "mock-broadcaster-id");
+        assert_eq!(stream.started_at.as_str(), "2025-01-01T12:00:00Z");
+
+        let app = Router::new()
+            .route("/helix/streams", get(handle_get_streams))
+            .with_state(false);
+        let helix_client = start_mock_helix_server(app).await;
+
+        let stream = get_live_stream(&helix_client, &token).await.unwrap();
+        assert!(stream.is_none());
+    }
+}
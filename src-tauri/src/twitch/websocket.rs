@@ -4,9 +4,11 @@
 
 use super::{
     models::{
-        TwitchEvent, TwitchEventAdBreakBegin, TwitchEventChatMsg, TwitchEventCheerBits,
-        TwitchEventFollow, TwitchEventGiftSub, TwitchEventRaid, TwitchEventReSub,
-        TwitchEventRedeem, TwitchEventShoutoutReceive, TwitchEventSub,
+        TwitchEvent, TwitchEventAdBreakBegin, TwitchEventCharityDonation, TwitchEventChatMsg,
+        TwitchEventCheerBits, TwitchEventFollow, TwitchEventGiftSub, TwitchEventHypeTrain,
+        TwitchEventHypeTrainEnd, TwitchEventPoll, TwitchEventPrediction, TwitchEventRaid,
+        TwitchEventReSub, TwitchEventRedeem, TwitchEventShoutoutReceive, TwitchEventStreamOnline,
+        TwitchEventSub, TwitchPollChoice, TwitchPredictionOutcome,
     },
     TwitchClient,
 };
@@ -26,11 +28,12 @@ use tungstenite::{
 use twitch_api::{
     eventsub::{
         self,
-        channel::ChannelRaidV1,
+        channel::{poll::ChannelPollEndV1Payload, ChannelRaidV1},
         event::websocket::{EventsubWebsocketData, SessionData},
         Event, EventSubscription, Transport,
     },
     twitch_oauth2::{TwitchToken, UserToken},
+    types::{PollChoice, PollStatus, PredictionOutcome, PredictionStatus},
     HelixClient,
 };
 
@@ -88,6 +91,30 @@ async fn websocket_connect(connect_url: &str) -> tungstenite::Result<WsStream> {
         .map(|(socket, _)| socket)
 }
 
+/// Maps the twitch poll choices into [TwitchPollChoice]s
+fn map_poll_choices(choices: Vec<PollChoice>) -> Vec<TwitchPollChoice> {
+    choices
+        .into_iter()
+        .map(|choice| TwitchPollChoice {
+            title: choice.title,
+            votes: choice.votes.unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Maps the twitch prediction outcomes into [TwitchPredictionOutcome]s
+fn map_prediction_outcomes(outcomes: Vec<PredictionOutcome>) -> Vec<TwitchPredictionOutcome> {
+    outcomes
+        .into_iter()
+        .map(|outcome| TwitchPredictionOutcome {
+            id: outcome.id,
+            title: outcome.title,
+            users: outcome.users.unwrap_or_default(),
+            channel_points: outcome.channel_points.unwrap_or_default(),
+        })
+        .collect()
+}
+
 fn map_message<E: EventSubscription + Clone>(
     message: eventsub::Message<E>,
 ) -> anyhow::Result<E::Payload> {
@@ -342,6 +369,169 @@ impl WebsocketClient {
                     }))
             }
 
+            // Hype train started
+            Event::ChannelHypeTrainBeginV1(payload) => {
+                let msg = map_message(payload.message)?;
+                _ = self
+                    .tx
+                    .send(TwitchEvent::HypeTrainBegin(TwitchEventHypeTrain {
+                        user_id: msg.last_contribution.user_id,
+                        user_name: msg.last_contribution.user_login,
+                        user_display_name: msg.last_contribution.user_name,
+                        level: msg.level,
+                        total: msg.total,
+                        progress: msg.progress,
+                        goal: msg.goal,
+                    }))
+            }
+
+            // Hype train received a contribution
+            Event::ChannelHypeTrainProgressV1(payload) => {
+                let msg = map_message(payload.message)?;
+                _ = self
+                    .tx
+                    .send(TwitchEvent::HypeTrainProgress(TwitchEventHypeTrain {
+                        user_id: msg.last_contribution.user_id,
+                        user_name: msg.last_contribution.user_login,
+                        user_display_name: msg.last_contribution.user_name,
+                        level: msg.level,
+                        total: msg.total,
+                        progress: msg.progress,
+                        goal: msg.goal,
+                    }))
+            }
+
+            // Hype train ended
+            Event::ChannelHypeTrainEndV1(payload) => {
+                let msg = map_message(payload.message)?;
+                _ = self
+                    .tx
+                    .send(TwitchEvent::HypeTrainEnd(TwitchEventHypeTrainEnd {
+                        level: msg.level,
+                        total: msg.total,
+                    }))
+            }
+
+            // Stream went live
+            Event::StreamOnlineV1(payload) => {
+                let msg = map_message(payload.message)?;
+                _ = self
+                    .tx
+                    .send(TwitchEvent::StreamOnline(TwitchEventStreamOnline {
+                        stream_id: msg.id,
+                        started_at: msg.started_at,
+                    }))
+            }
+
+            // Stream went offline
+            Event::StreamOfflineV1(payload) => {
+                let _msg = map_message(payload.message)?;
+                _ = self.tx.send(TwitchEvent::StreamOffline)
+            }
+
+            // Charity campaign donation received
+            Event::ChannelCharityCampaignDonateV1(payload) => {
+                let msg = map_message(payload.message)?;
+                _ = self
+                    .tx
+                    .send(TwitchEvent::CharityDonation(TwitchEventCharityDonation {
+                        user_id: msg.user_id,
+                        user_name: msg.user_login,
+                        user_display_name: msg.user_name,
+                        charity_name: msg.charity_name,
+                        amount: msg.amount.value as i64,
+                        decimal_places: msg.amount.decimal_places,
+                        currency: msg.amount.currency,
+                    }))
+            }
+
+            // Poll started
+            Event::ChannelPollBeginV1(payload) => {
+                let msg = map_message(payload.message)?;
+                _ = self.tx.send(TwitchEvent::PollBegin(TwitchEventPoll {
+                    title: msg.title,
+                    choices: map_poll_choices(msg.choices),
+                }))
+            }
+
+            // Poll received votes
+            Event::ChannelPollProgressV1(payload) => {
+                let msg = map_message(payload.message)?;
+                _ = self.tx.send(TwitchEvent::PollProgress(TwitchEventPoll {
+                    title: msg.title,
+                    choices: map_poll_choices(msg.choices),
+                }))
+            }
+
+            // Poll ended
+            Event::ChannelPollEndV1(payload) => {
+                let msg: ChannelPollEndV1Payload = map_message(payload.message)?;
+
+                // Archiving a poll that has already ended sends another end message
+                if !matches!(msg.status, PollStatus::Completed | PollStatus::Terminated) {
+                    return Ok(());
+                }
+
+                _ = self.tx.send(TwitchEvent::PollEnd(TwitchEventPoll {
+                    title: msg.title,
+                    choices: map_poll_choices(msg.choices),
+                }))
+            }
+
+            // Prediction started
+            Event::ChannelPredictionBeginV1(payload) => {
+                let msg = map_message(payload.message)?;
+                _ = self
+                    .tx
+                    .send(TwitchEvent::PredictionBegin(TwitchEventPrediction {
+                        title: msg.title,
+                        outcomes: map_prediction_outcomes(msg.outcomes),
+                        winning_outcome_id: None,
+                    }))
+            }
+
+            // Prediction received predictions
+            Event::ChannelPredictionProgressV1(payload) => {
+                let msg = map_message(payload.message)?;
+                _ = self
+                    .tx
+                    .send(TwitchEvent::PredictionProgress(TwitchEventPrediction {
+                        title: msg.title,
+                        outcomes: map_prediction_outcomes(msg.outcomes),
+                        winning_outcome_id: None,
+                    }))
+            }
+
+            // Prediction locked
+            Event::ChannelPredictionLockV1(payload) => {
+                let msg = map_message(payload.message)?;
+                _ = self
+                    .tx
+                    .send(TwitchEvent::PredictionLock(TwitchEventPrediction {
+                        title: msg.title,
+                        outcomes: map_prediction_outcomes(msg.outcomes),
+                        winning_outcome_id: None,
+                    }))
+            }
+
+            // Prediction ended
+            Event::ChannelPredictionEndV1(payload) => {
+                let msg = map_message(payload.message)?;
+
+                // Canceled predictions have no winner
+                if !matches!(msg.status, PredictionStatus::Resolved) {
+                    return Ok(());
+                }
+
+                _ = self
+                    .tx
+                    .send(TwitchEvent::PredictionEnd(TwitchEventPrediction {
+                        title: msg.title,
+                        outcomes: map_prediction_outcomes(msg.outcomes),
+                        winning_outcome_id: Some(msg.winning_outcome_id.to_string()),
+                    }))
+            }
+
             _ => {}
         }
 
@@ -378,14 +568,19 @@ impl WebsocketClient {
         client: &TwitchClient,
     ) -> anyhow::Result<()> {
         use eventsub::channel::{
-            ChannelAdBreakBeginV1, ChannelChatMessageV1, ChannelCheerV1, ChannelFollowV2,
-            ChannelModeratorAddV1, ChannelModeratorRemoveV1, ChannelPointsCustomRewardAddV1,
-            ChannelPointsCustomRewardRedemptionAddV1, ChannelPointsCustomRewardRemoveV1,
-            ChannelPointsCustomRewardUpdateV1, ChannelShoutoutReceiveV1, ChannelSubscribeV1,
-            ChannelSubscriptionGiftV1, ChannelSubscriptionMessageV1, ChannelVipAddV1,
-            ChannelVipRemoveV1,
+            ChannelAdBreakBeginV1, ChannelCharityCampaignDonateV1, ChannelChatMessageV1,
+            ChannelCheerV1, ChannelFollowV2, ChannelHypeTrainBeginV1, ChannelHypeTrainEndV1,
+            ChannelHypeTrainProgressV1, ChannelModeratorAddV1, ChannelModeratorRemoveV1,
+            ChannelPointsCustomRewardAddV1, ChannelPointsCustomRewardRedemptionAddV1,
+            ChannelPointsCustomRewardRemoveV1, ChannelPointsCustomRewardUpdateV1,
+            ChannelPollBeginV1, ChannelPollEndV1, ChannelPollProgressV1, ChannelPredictionBeginV1,
+            ChannelPredictionEndV1, ChannelPredictionLockV1, ChannelPredictionProgressV1,
+            ChannelShoutoutReceiveV1, ChannelSubscribeV1, ChannelSubscriptionGiftV1,
+            ChannelSubscriptionMessageV1, ChannelVipAddV1, ChannelVipRemoveV1,
         };
 
+        use eventsub::stream::{StreamOfflineV1, StreamOnlineV1};
+
         let user_id = token.user_id.clone();
         let transport = eventsub::Transport::websocket(session_id);
 
@@ -453,6 +648,61 @@ impl WebsocketClient {
                 user_id.clone(),
                 user_id.clone(),
             ))),
+            // Subscribe to hype train started
+            Box::new(EventSub(ChannelHypeTrainBeginV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
+            // Subscribe to hype train progress
+            Box::new(EventSub(ChannelHypeTrainProgressV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
+            // Subscribe to hype train ended
+            Box::new(EventSub(ChannelHypeTrainEndV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
+            // Subscribe to stream going live
+            Box::new(EventSub(StreamOnlineV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
+            // Subscribe to stream going offline
+            Box::new(EventSub(StreamOfflineV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
+            // Subscribe to charity campaign donations
+            Box::new(EventSub(
+                ChannelCharityCampaignDonateV1::broadcaster_user_id(user_id.clone()),
+            )),
+            // Hype chat (Paid pinned chat messages) is not subscribed to, the chat
+            // notification types from twitch_api have no hype chat notice so there
+            // is no event to create a hype chat trigger from
+            // Subscribe to poll started
+            Box::new(EventSub(ChannelPollBeginV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
+            // Subscribe to poll progress
+            Box::new(EventSub(ChannelPollProgressV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
+            // Subscribe to poll ended
+            Box::new(EventSub(ChannelPollEndV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
+            // Subscribe to prediction started
+            Box::new(EventSub(ChannelPredictionBeginV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
+            // Subscribe to prediction progress
+            Box::new(EventSub(ChannelPredictionProgressV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
+            // Subscribe to prediction locked
+            Box::new(EventSub(ChannelPredictionLockV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
+            // Subscribe to prediction ended
+            Box::new(EventSub(ChannelPredictionEndV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
         ];
 
         let mut subscriptions = subscriptions.into_iter().peekable();
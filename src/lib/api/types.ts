@@ -41,13 +41,19 @@ export type VEvent = {
   name: string;
   enabled: boolean;
   trigger: EventTrigger;
-  outcome: EventOutcome;
+  outcomes: EventOutcomeStep[];
   cooldown: VEventCooldown;
   require_role: MinimumRequiredRole;
   outcome_delay: number;
   order: number;
 };
 
+export type EventOutcomeStep = {
+  // Delay in milliseconds before executing the step
+  delay: number;
+  outcome: EventOutcome;
+};
+
 export type VEventCooldown = {
   enabled: boolean;
   duration: number;
@@ -58,7 +64,7 @@ export type CreateEvent = {
   name: string;
   enabled: boolean;
   trigger: EventTrigger;
-  outcome: EventOutcome;
+  outcomes: EventOutcomeStep[];
   cooldown: VEventCooldown;
   require_role: MinimumRequiredRole;
   outcome_delay: number;
@@ -70,7 +76,7 @@ export type UpdateEvent = {
     name: string;
     enabled: boolean;
     trigger: EventTrigger;
-    outcome: EventOutcome;
+    outcomes: EventOutcomeStep[];
     cooldown: VEventCooldown;
     require_role: MinimumRequiredRole;
     outcome_delay: number;